@@ -110,6 +110,7 @@ export async function initializeJobs() {
     await setupRepeatableJobs(priceMonitorQueue, [
       { name: 'update-prices', data: {}, every: 3000 },
       { name: 'check-price-alerts', data: {}, every: 15000 },
+      { name: 'record-prices', data: {}, every: 30000 }, // on-chain minimum record interval
     ]);
     
     // Sync jobs
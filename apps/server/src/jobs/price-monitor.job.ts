@@ -3,6 +3,8 @@ import { WebSocketEvent, PriceData } from '@memecoin-lending/types';
 import { priceService, ExtendedPriceData } from '../services/price.js';
 import { websocketService } from '../websocket/index.js';
 import { getAllTokenDefinitions } from '@memecoin-lending/config';
+import { recordPrice } from '@memecoin-lending/sdk';
+import { getProgram } from '../services/solana.service.js';
 
 export async function priceMonitorJob(job: Job) {
   const jobName = job.name;
@@ -65,6 +67,30 @@ export async function priceMonitorJob(job: Job) {
       
       return { status: 'price_alerts_checked' };
       
+    } else if (jobName === 'record-prices') {
+      // Crank on-chain price checkpoints; create_loan and liquidations use their median TWAP
+      const program = getProgram();
+      const tokenConfigs = await (program.account as any).tokenConfig.all();
+      let recorded = 0;
+
+      for (const { account } of tokenConfigs) {
+        if (!account.enabled || account.blacklisted) continue;
+
+        try {
+          // One checkpoint per transaction - the program rejects bundled record_price calls
+          await recordPrice(program, account.mint);
+          recorded++;
+        } catch (error: any) {
+          // Another cranker got there first within the minimum interval
+          if (error.message?.includes('PriceRecordTooFrequent')) continue;
+          console.error(`❌ record_price failed for ${account.mint.toString().slice(0, 8)}...:`, error.message);
+        }
+      }
+
+      console.log(`✅ Recorded ${recorded}/${tokenConfigs.length} price checkpoints`);
+
+      return { status: 'prices_recorded', recorded, total: tokenConfigs.length };
+
     } else if (jobName === 'clear-price-cache') {
       console.log('🧹 Clearing price cache...');
       
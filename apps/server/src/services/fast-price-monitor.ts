@@ -12,7 +12,8 @@ import { SECURITY_EVENT_TYPES } from '@memecoin-lending/types';
 import { jupiterClient } from './jupiter-client.js';
 
 // Price scaling constant - matches on-chain storage format
-const PRICE_SCALE_DIVISOR = 1_000_000; // 1e6
+// (lamports per whole token * PRICE_SCALE = SOL per token * 1e9 * 1e6)
+const PRICE_SCALE_DIVISOR = 1_000_000_000_000_000; // 1e15
 
 const JUPITER_PRICE_API = 'https://api.jup.ag/price/v3';
 const DEXSCREENER_API = 'https://api.dexscreener.com/latest/dex/tokens';
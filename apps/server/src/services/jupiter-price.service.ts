@@ -62,9 +62,10 @@ export async function getJupiterPrice(tokenMint: string): Promise<{
     // Calculate token price in SOL
     const priceInSol = tokenPriceUsd / solPriceUsd;
 
-    // Convert to lamports per token (with 6 decimal precision for price)
+    // Convert to the on-chain canonical price: lamports per whole token * PRICE_SCALE
+    const LAMPORTS_PER_SOL = 1_000_000_000;
     const PRICE_SCALE = 1_000_000;
-    const priceInLamports = BigInt(Math.floor(priceInSol * PRICE_SCALE));
+    const priceInLamports = BigInt(Math.floor(priceInSol * LAMPORTS_PER_SOL * PRICE_SCALE));
 
     console.log(`[Jupiter] ${tokenMint.slice(0, 8)}... price: ${priceInSol.toExponential(4)} SOL (${priceInLamports} scaled)`);
 
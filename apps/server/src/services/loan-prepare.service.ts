@@ -9,6 +9,7 @@ import {
   TOKEN_PROGRAM_ID, 
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddress,
+  getMint,
 } from '@solana/spl-token';
 import { Program, AnchorProvider } from '@coral-xyz/anchor';
 import BN from 'bn.js';
@@ -17,14 +18,6 @@ import { getJupiterPrice } from './jupiter-price.service.js';
 import { getConnection, getProgram } from './solana.service.js';
 import { assertCircuitBreakerOk } from './circuit-breaker.service.js';
 import { checkWalletRateLimit } from './wallet-rate-limit.service.js';
-import { 
-  getPumpSwapPoolAddress, 
-  extractPoolVaults,
-  POOL_SIZE as PUMPSWAP_POOL_SIZE,
-  POOL_BASE_VAULT_OFFSET as PUMPSWAP_POOL_BASE_VAULT_OFFSET,
-  POOL_QUOTE_VAULT_OFFSET as PUMPSWAP_POOL_QUOTE_VAULT_OFFSET,
-  POOL_MIN_LEN as PUMPSWAP_POOL_MIN_LEN
-} from './pumpswap-pool.service.js';
 
 // Token-2022 Program ID
 const TOKEN_2022_PROGRAM_ID = new PublicKey('TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb');
@@ -35,6 +28,9 @@ const TREASURY_SEED = Buffer.from('treasury');
 const TOKEN_CONFIG_SEED = Buffer.from('token_config');
 const LOAN_SEED = Buffer.from('loan');
 const VAULT_SEED = Buffer.from('vault');
+const RISK_CONFIG_SEED = Buffer.from('risk_config');
+const USER_EXPOSURE_SEED = Buffer.from('user_exposure');
+const PRICE_HISTORY_SEED = Buffer.from('price_history');
 
 // Price signature validity (30 seconds)
 const PRICE_VALIDITY_SECONDS = 30;
@@ -74,28 +70,6 @@ async function getTokenProgramForMint(
   return TOKEN_PROGRAM_ID;
 }
 
-// Helper function to fetch PumpSwap vaults
-async function getPumpSwapVaults(
-  connection: Connection,
-  poolAddress: PublicKey
-): Promise<{ baseVault: PublicKey; quoteVault: PublicKey } | null> {
-  try {
-    const poolAccount = await connection.getAccountInfo(poolAddress);
-    if (!poolAccount || poolAccount.data.length < PUMPSWAP_POOL_MIN_LEN) {
-      console.warn('[PrepareLoan] PumpSwap pool account not found or too small');
-      return null;
-    }
-    
-    const vaults = extractPoolVaults(poolAccount.data);
-    console.log(`[PrepareLoan] PumpSwap vaults - base: ${vaults.baseVault.toString().slice(0,8)}..., quote: ${vaults.quoteVault.toString().slice(0,8)}...`);
-    
-    return vaults;
-  } catch (error) {
-    console.error('[PrepareLoan] Failed to fetch PumpSwap vaults:', error);
-    return null;
-  }
-}
-
 export async function prepareLoanTransaction(
   request: PrepareLoanRequest
 ): Promise<PrepareLoanResponse> {
@@ -158,7 +132,23 @@ export async function prepareLoanTransaction(
     program.programId
   );
 
-  // Fetch token config to get pool address
+  const [riskConfigPda] = PublicKey.findProgramAddressSync(
+    [RISK_CONFIG_SEED],
+    program.programId
+  );
+
+  const [userExposurePda] = PublicKey.findProgramAddressSync(
+    [USER_EXPOSURE_SEED, borrower.toBuffer()],
+    program.programId
+  );
+
+  // The approved price is checked on-chain against the TWAP of this history
+  const [priceHistoryPda] = PublicKey.findProgramAddressSync(
+    [PRICE_HISTORY_SEED, tokenMint.toBuffer()],
+    program.programId
+  );
+
+  // Fetch token config (LTV for the estimate below)
   let tokenConfig;
   try {
     tokenConfig = await (program.account as any).tokenConfig.fetch(tokenConfigPda);
@@ -168,7 +158,6 @@ export async function prepareLoanTransaction(
     }
     throw error;
   }
-  let poolAddress = tokenConfig.poolAddress;
 
   // Detect token program (SPL Token vs Token-2022)
   const tokenProgramId = await getTokenProgramForMint(connection, tokenMint);
@@ -181,56 +170,6 @@ export async function prepareLoanTransaction(
     tokenProgramId
   );
 
-  // Check if PumpSwap and fetch vaults
-  // Debug: log the actual poolType format
-  console.log('[PrepareLoan] Token config poolType:', JSON.stringify(tokenConfig.poolType), typeof tokenConfig.poolType);
-
-  // Handle both Anchor enum object format and numeric format
-  let isPumpSwap = false;
-  if (typeof tokenConfig.poolType === 'object' && tokenConfig.poolType !== null) {
-    const poolTypeKey = Object.keys(tokenConfig.poolType)[0];
-    isPumpSwap = poolTypeKey === 'pumpSwap';
-    console.log('[PrepareLoan] Pool type key:', poolTypeKey, 'isPumpSwap:', isPumpSwap);
-  } else {
-    isPumpSwap = tokenConfig.poolType === 3;
-    console.log('[PrepareLoan] Pool type numeric:', tokenConfig.poolType, 'isPumpSwap:', isPumpSwap);
-  }
-  
-  let pumpswapBaseVault: PublicKey | null = null;
-  let pumpswapQuoteVault: PublicKey | null = null;
-
-  if (isPumpSwap) {
-    console.log('[PrepareLoan] PumpSwap pool address:', poolAddress.toString());
-    let vaults = await getPumpSwapVaults(connection, poolAddress);
-    
-    // If vaults fetch failed, try discovering the pool
-    if (!vaults) {
-      console.warn('[PrepareLoan] Could not fetch vaults from stored pool, trying pool discovery');
-      const discoveredPool = await getPumpSwapPoolAddress(connection, tokenMint);
-      
-      if (discoveredPool && !discoveredPool.equals(poolAddress)) {
-        console.log('[PrepareLoan] Found different pool via discovery:', discoveredPool.toString());
-        vaults = await getPumpSwapVaults(connection, discoveredPool);
-        
-        if (vaults) {
-          // Update the pool address for the transaction
-          poolAddress = discoveredPool;
-          console.log('[PrepareLoan] Using discovered pool address');
-        }
-      }
-    }
-    
-    if (!vaults) {
-      // For PumpSwap, we might not need the vault accounts if the on-chain program handles it
-      console.warn('[PrepareLoan] Could not fetch PumpSwap vaults, proceeding without them');
-      // Don't throw error, let the transaction builder handle it
-    } else {
-      pumpswapBaseVault = vaults.baseVault;
-      pumpswapQuoteVault = vaults.quoteVault;
-      console.log('[PrepareLoan] PumpSwap token detected, vaults fetched successfully');
-    }
-  }
-
   // 3. Build the transaction
   console.log(`[PrepareLoan] Building transaction...`);
   
@@ -238,25 +177,23 @@ export async function prepareLoanTransaction(
   const accounts: any = {
     protocolState: protocolStatePda,
     tokenConfig: tokenConfigPda,
+    riskConfig: riskConfigPda,
     loan: loanPda,
     treasury: treasuryPda,
     borrower: borrower,
+    userExposure: userExposurePda,
     borrowerTokenAccount: borrowerTokenAccount,
     vault: vaultPda,
-    poolAccount: poolAddress,
+    priceHistory: priceHistoryPda,
     tokenMint: tokenMint,
+    // The price authority co-signs, so no attestation sysvar or authority set is passed
     priceAuthority: priceAuthority.publicKey,
+    instructionsSysvar: null,
+    priceAuthoritySet: null,
     tokenProgram: tokenProgramId,  // Use detected program (Token or Token-2022)
     systemProgram: SystemProgram.programId,
   };
 
-  // For PumpSwap, add vault accounts as named optional accounts
-  if (pumpswapBaseVault && pumpswapQuoteVault) {
-    accounts.pumpswapBaseVault = pumpswapBaseVault;
-    accounts.pumpswapQuoteVault = pumpswapQuoteVault;
-    console.log('[PrepareLoan] Added PumpSwap vaults to accounts');
-  }
-
   const tx = await program.methods
     .createLoan(
       collateralAmount,
@@ -284,12 +221,15 @@ export async function prepareLoanTransaction(
   });
 
   // Calculate estimated SOL amount (for display)
+  // approvedPrice is lamports per whole token * PRICE_SCALE, collateral is in raw units
   const PRICE_SCALE = 1_000_000n;
   const BPS_DIVISOR = 10000n;
   const ltvBps = BigInt(tokenConfig.ltvBps);
+  const mintInfo = await getMint(connection, tokenMint, undefined, tokenProgramId);
+  const tokenUnit = 10n ** BigInt(mintInfo.decimals);
   
   const collateralBigInt = BigInt(request.collateralAmount);
-  const estimatedSol = (collateralBigInt * approvedPrice * ltvBps) / (PRICE_SCALE * BPS_DIVISOR);
+  const estimatedSol = (collateralBigInt * approvedPrice * ltvBps) / (PRICE_SCALE * tokenUnit * BPS_DIVISOR);
 
   console.log(`[PrepareLoan] Transaction prepared successfully`);
   console.log(`[PrepareLoan] - Loan PDA: ${loanPda.toString()}`);
@@ -33,7 +33,8 @@ import { SECURITY_EVENT_TYPES } from '@memecoin-lending/types';
 import { recordLoanExposure, recordRepayment } from './exposure-monitor.service.js';
 import { recordLiquidationResult } from './liquidation-tracker.service.js';
 import { PROGRAM_ID, getNetworkConfig, getCurrentNetwork } from '@memecoin-lending/config';
-import { getAdminKeypair } from '../config/keys.js';
+import { getAdminKeypair, getPriceAuthorityKeypair } from '../config/keys.js';
+import { getJupiterPrice } from './jupiter-price.service.js';
 import { lpLimitsService } from './lp-limits.service.js';
 import { programMonitor } from './program-monitor.service.js';
 import { recordSuccessfulLoan } from './wallet-rate-limit.service.js';
@@ -74,6 +75,10 @@ function parsePoolTypeFromByte(poolTypeByte: number): PoolType {
     case 1: return PoolType.Orca;
     case 2: return PoolType.Pumpfun;
     case 3: return PoolType.PumpSwap;
+    case 4: return PoolType.RaydiumCpmm;
+    case 5: return PoolType.RaydiumClmm;
+    case 6: return PoolType.MeteoraDlmm;
+    case 7: return PoolType.MeteoraDammV2;
     default: return PoolType.Raydium;
   }
 }
@@ -169,6 +174,9 @@ class LoanService {
       maxLoanAmount: '100000000000', // 100 SOL in lamports
       activeLoansCount: '0',
       totalVolume: '0',
+      totalPrincipalRecovered: '0',
+      totalLiquidationProfit: '0',
+      totalFeeRevenue: '0',
       isProtocolToken: false, // Manual whitelist tokens are not protocol tokens
     };
 
@@ -302,23 +310,31 @@ class LoanService {
         throw new Error(lpCheck.reason || 'Loan would exceed liquidity pool limits');
       }
       
-      // Build unsigned transaction
+      // Build the transaction with a price approved by the price authority
+      const priceAuthority = getPriceAuthorityKeypair();
+      const jupiterPrice = await getJupiterPrice(params.tokenMint);
       const tx = await buildCreateLoanTransaction(client.program, {
         tokenMint: new PublicKey(params.tokenMint),
         collateralAmount: new BN(params.collateralAmount),
         durationSeconds: new BN(params.durationSeconds),
         borrower: new PublicKey(params.borrower),
+        approvedPrice: new BN(jupiterPrice.priceInLamports.toString()),
+        priceTimestamp: new BN(jupiterPrice.timestamp),
+        priceAuthority: priceAuthority.publicKey,
       });
       
       // Get recent blockhash
       const { blockhash } = await client.connection.getLatestBlockhash();
       tx.recentBlockhash = blockhash;
       tx.feePayer = new PublicKey(params.borrower);
+
+      // Price authority co-signs; the borrower signs the rest
+      tx.partialSign(priceAuthority);
       
       // SECURITY: Track token for real-time price monitoring via WebSocket
       fastPriceMonitor.trackToken(params.tokenMint);
       
-      // Serialize and return (unsigned)
+      // Serialize and return (borrower signature still missing)
       const serializedTx = tx.serialize({ 
         requireAllSignatures: false,
         verifySignatures: false 
@@ -22,9 +22,12 @@ export async function getProtocolState(program: Program): Promise<ProtocolState>
     buybackWallet: account.buybackWallet.toString(),
     operationsWallet: account.operationsWallet.toString(),
     paused: account.paused,
+    totalPausedSeconds: account.totalPausedSeconds?.toNumber() ?? 0,
     totalLoansCreated: account.totalLoansCreated.toString(),
     totalSolBorrowed: account.totalSolBorrowed.toString(),
     totalFeesEarned: account.totalFeesEarned.toString(),
+    totalPrincipalRecovered: account.totalPrincipalRecovered?.toString() || '0',
+    totalLiquidationProfit: account.totalLiquidationProfit?.toString() || '0',
     activeLoansCount: account.activeLoansCount.toString(),
     protocolFeeBps: account.protocolFeeBps,
     treasuryFeeBps: account.treasuryFeeBps,
@@ -55,6 +58,9 @@ export async function getTokenConfig(
       maxLoanAmount: account.maxLoanAmount.toString(),
       activeLoansCount: account.activeLoansCount.toString(),
       totalVolume: account.totalVolume.toString(),
+      totalPrincipalRecovered: account.totalPrincipalRecovered?.toString() || '0',
+      totalLiquidationProfit: account.totalLiquidationProfit?.toString() || '0',
+      totalFeeRevenue: account.totalFeeRevenue?.toString() || '0',
       isProtocolToken: account.isProtocolToken ?? false,
     };
   } catch (error) {
@@ -63,12 +69,24 @@ export async function getTokenConfig(
   }
 }
 
+async function getTotalPausedSeconds(program: Program): Promise<number> {
+  const [protocolStatePDA] = pda.getProtocolStatePDA(program.programId);
+  const account = await (program.account as any).protocolState.fetch(protocolStatePDA);
+  return account.totalPausedSeconds?.toNumber() ?? 0;
+}
+
+/** On-chain due date pushed back by protocol pauses since the loan was opened */
+function effectiveDueAt(loan: any, totalPausedSeconds: number): number {
+  return loan.dueAt.toNumber() + totalPausedSeconds - (loan.pausedSecondsAtOpen?.toNumber() ?? 0);
+}
+
 export async function getLoan(
   program: Program,
   loanPubkey: PublicKey
 ): Promise<Loan | null> {
   try {
     const account = await (program.account as any).loan.fetch(loanPubkey);
+    const totalPausedSeconds = await getTotalPausedSeconds(program);
     
     return {
       pubkey: loanPubkey.toString(),
@@ -79,7 +97,7 @@ export async function getLoan(
       entryPrice: account.entryPrice.toString(),
       liquidationPrice: account.liquidationPrice.toString(),
       createdAt: account.createdAt.toNumber(),
-      dueAt: account.dueAt.toNumber(),
+      dueAt: effectiveDueAt(account, totalPausedSeconds),
       status: parseLoanStatus(account.status),
       index: account.index.toNumber(),
     };
@@ -91,6 +109,7 @@ export async function getLoan(
 export async function getAllLoans(program: Program): Promise<Loan[]> {
   try {
     const accounts = await (program.account as any).loan.all();
+    const totalPausedSeconds = await getTotalPausedSeconds(program);
     return accounts
       .map((acc: any) => ({
         pubkey: acc.publicKey.toString(),
@@ -101,7 +120,7 @@ export async function getAllLoans(program: Program): Promise<Loan[]> {
         entryPrice: acc.account.entryPrice.toString(),
         liquidationPrice: acc.account.liquidationPrice.toString(),
         createdAt: acc.account.createdAt.toNumber(),
-        dueAt: acc.account.dueAt.toNumber(),
+        dueAt: effectiveDueAt(acc.account, totalPausedSeconds),
         status: parseLoanStatus(acc.account.status),
         index: acc.account.index.toNumber(),
       }));
@@ -123,6 +142,7 @@ export async function getLoansByBorrower(
     const accounts = await (program.account as any).loan.all([
       { memcmp: { offset: 8, bytes: borrower.toBase58() } }
     ]);
+    const totalPausedSeconds = await getTotalPausedSeconds(program);
     return accounts.map((acc: any) => ({
       pubkey: acc.publicKey.toString(),
       borrower: acc.account.borrower.toString(),
@@ -132,7 +152,7 @@ export async function getLoansByBorrower(
       entryPrice: acc.account.entryPrice.toString(),
       liquidationPrice: acc.account.liquidationPrice.toString(),
       createdAt: acc.account.createdAt.toNumber(),
-      dueAt: acc.account.dueAt.toNumber(),
+      dueAt: effectiveDueAt(acc.account, totalPausedSeconds),
       status: parseLoanStatus(acc.account.status),
       index: acc.account.index.toNumber(),
     }));
@@ -149,6 +169,7 @@ export async function getLoansByToken(
     const accounts = await (program.account as any).loan.all([
       { memcmp: { offset: 40, bytes: mint.toBase58() } }
     ]);
+    const totalPausedSeconds = await getTotalPausedSeconds(program);
     return accounts.map((acc: any) => ({
       pubkey: acc.publicKey.toString(),
       borrower: acc.account.borrower.toString(),
@@ -158,7 +179,7 @@ export async function getLoansByToken(
       entryPrice: acc.account.entryPrice.toString(),
       liquidationPrice: acc.account.liquidationPrice.toString(),
       createdAt: acc.account.createdAt.toNumber(),
-      dueAt: acc.account.dueAt.toNumber(),
+      dueAt: effectiveDueAt(acc.account, totalPausedSeconds),
       status: parseLoanStatus(acc.account.status),
       index: acc.account.index.toNumber(),
     }));
@@ -216,6 +237,10 @@ export function parsePoolType(poolTypeData: any): PoolType {
   if (poolTypeData.orca) return PoolType.Orca;
   if (poolTypeData.pumpfun) return PoolType.Pumpfun;
   if (poolTypeData.pumpswap) return PoolType.PumpSwap;
+  if (poolTypeData.raydiumCpmm) return PoolType.RaydiumCpmm;
+  if (poolTypeData.raydiumClmm) return PoolType.RaydiumClmm;
+  if (poolTypeData.meteoraDlmm) return PoolType.MeteoraDlmm;
+  if (poolTypeData.meteoraDammV2) return PoolType.MeteoraDammV2;
   return PoolType.Raydium;
 }
 
@@ -225,6 +250,7 @@ export function parseLoanStatus(statusData: any): LoanStatus {
   if (statusData.repaid) return LoanStatus.Repaid;
   if (statusData.liquidatedTime) return LoanStatus.LiquidatedTime;
   if (statusData.liquidatedPrice) return LoanStatus.LiquidatedPrice;
+  if (statusData.inAuction) return LoanStatus.InAuction;
   return LoanStatus.Active;
 }
 
@@ -235,6 +261,10 @@ export function poolTypeToNumber(poolType: PoolType): number {
     case PoolType.Orca: return 1;
     case PoolType.Pumpfun: return 2;
     case PoolType.PumpSwap: return 3;
+    case PoolType.RaydiumCpmm: return 4;
+    case PoolType.RaydiumClmm: return 5;
+    case PoolType.MeteoraDlmm: return 6;
+    case PoolType.MeteoraDammV2: return 7;
     default: return 0;
   }
 }
@@ -138,12 +138,18 @@ export class MemecoinLendingClient {
     const borrower = params.borrower 
       ? new PublicKey(params.borrower) 
       : this.wallet.publicKey;
+
+    if (params.approvedPrice === undefined || params.priceTimestamp === undefined) {
+      throw new Error('createLoan needs a price approved by the price authority');
+    }
     
     return instructions.createLoan(this.program, {
       tokenMint: mint,
       collateralAmount: new BN(params.collateralAmount),
       durationSeconds: new BN(params.durationSeconds),
       borrower,
+      approvedPrice: new BN(params.approvedPrice),
+      priceTimestamp: new BN(params.priceTimestamp),
     });
   }
 
@@ -171,6 +177,10 @@ export class MemecoinLendingClient {
     return instructions.resumeProtocol(this.program);
   }
 
+  async revokeRole(role: Record<string, Record<string, never>>): Promise<TransactionSignature> {
+    return instructions.revokeRole(this.program, role);
+  }
+
   async withdrawTreasury(amount: BN): Promise<TransactionSignature> {
     return instructions.withdrawTreasury(this.program, amount);
   }
@@ -184,10 +194,31 @@ export class MemecoinLendingClient {
     treasuryFeeBps?: number;
     buybackFeeBps?: number;
     operationsFeeBps?: number;
+    liquidationPenaltyBps?: number;
   }): Promise<TransactionSignature> {
     return instructions.updateFees(this.program, params);
   }
 
+  async updateLiquidationSettings(params: {
+    permissionlessLiquidation?: boolean;
+    keeperBonusBps?: number;
+    liquidatorExclusivitySeconds?: BN;
+  }): Promise<TransactionSignature> {
+    return instructions.updateLiquidationSettings(this.program, params);
+  }
+
+  async markLiquidatable(loanPubkey: PublicKey): Promise<TransactionSignature> {
+    return instructions.markLiquidatable(this.program, loanPubkey);
+  }
+
+  async migrateLoan(loanPubkey: PublicKey): Promise<TransactionSignature> {
+    return instructions.migrateLoan(this.program, loanPubkey);
+  }
+
+  async recordPrice(tokenMint: PublicKey): Promise<TransactionSignature> {
+    return instructions.recordPrice(this.program, tokenMint);
+  }
+
   async updateWallets(params: {
     newBuybackWallet?: PublicKey;
     newOperationsWallet?: PublicKey;
@@ -195,6 +226,45 @@ export class MemecoinLendingClient {
     return instructions.updateWallets(this.program, params);
   }
 
+  async proposeChange(change: Record<string, unknown>): Promise<TransactionSignature> {
+    return instructions.proposeChange(this.program, change);
+  }
+
+  async executeChange(id: BN, poolAccount?: PublicKey): Promise<TransactionSignature> {
+    return instructions.executeChange(this.program, id, poolAccount);
+  }
+
+  async cancelChange(id: BN): Promise<TransactionSignature> {
+    return instructions.cancelChange(this.program, id);
+  }
+
+  async initializeMultisig(signers: PublicKey[], threshold: number): Promise<TransactionSignature> {
+    return instructions.initializeMultisig(this.program, signers, threshold);
+  }
+
+  async proposeMultisigAction(action: Record<string, unknown>): Promise<TransactionSignature> {
+    return instructions.proposeMultisigAction(this.program, action);
+  }
+
+  async approveMultisigAction(id: BN): Promise<TransactionSignature> {
+    return instructions.approveMultisigAction(this.program, id);
+  }
+
+  async executeMultisigAction(id: BN): Promise<TransactionSignature> {
+    return instructions.executeMultisigAction(this.program, id);
+  }
+
+  async cancelMultisigAction(id: BN): Promise<TransactionSignature> {
+    return instructions.cancelMultisigAction(this.program, id);
+  }
+
+  async setRole(
+    role: Record<string, Record<string, never>>,
+    holder: PublicKey
+  ): Promise<TransactionSignature> {
+    return instructions.setRole(this.program, role, holder);
+  }
+
   async initializeStaking(
     stakingTokenMint: PublicKey,
     epochDuration: BN
@@ -6,6 +6,7 @@ import {
   Transaction,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   Connection,
 } from '@solana/web3.js';
 import {
@@ -16,7 +17,8 @@ import {
 } from '@solana/spl-token';
 import * as pda from '../pda';
 import { getCommonInstructionAccounts } from '../utils';
-import { PUMPFUN_PROGRAM_ID, RAYDIUM_PROGRAM_ID, ORCA_PROGRAM_ID } from '@memecoin-lending/config';
+import { extractPumpSwapVaults, extractCpmmVaults } from '../pool-price';
+import { PUMPFUN_PROGRAM_ID } from '@memecoin-lending/config';
 import { PoolType } from '@memecoin-lending/types';
 import { 
   PUMPFUN_GLOBAL, 
@@ -111,84 +113,99 @@ export async function whitelistToken(
     .rpc();
 }
 
-export async function buildCreateLoanTransaction(
-  program: Program,
-  params: {
-    tokenMint: PublicKey;
-    collateralAmount: BN;
-    durationSeconds: BN;
-    borrower: PublicKey;
-  }
-): Promise<Transaction> {
+interface CreateLoanParams {
+  tokenMint: PublicKey;
+  collateralAmount: BN;
+  durationSeconds: BN;
+  borrower: PublicKey;
+  /** Price approved by the price authority (lamports per whole token * PRICE_SCALE) */
+  approvedPrice: BN;
+  priceTimestamp: BN;
+  /** Co-signing price authority; omit when approving with Ed25519 attestations */
+  priceAuthority?: PublicKey;
+}
+
+async function getCreateLoanAccounts(program: Program, params: CreateLoanParams) {
   const [protocolState] = pda.getProtocolStatePDA(program.programId);
   const [tokenConfig] = pda.getTokenConfigPDA(params.tokenMint, program.programId);
+  const [riskConfig] = pda.getRiskConfigPDA(program.programId);
   const [treasury] = pda.getTreasuryPDA(program.programId);
-  
+  const [userExposure] = pda.getUserExposurePDA(params.borrower, program.programId);
+  const [priceHistory] = pda.getPriceHistoryPDA(params.tokenMint, program.programId);
+  const [priceAuthoritySet] = pda.getPriceAuthoritySetPDA(program.programId);
+
   // Fetch protocol state to get loan index
   const protocolStateAccount = await (program.account as any).protocolState.fetch(protocolState);
   if (!protocolStateAccount) {
     throw new Error('Protocol not initialized');
   }
-  
-  // Fetch token config to get pool address
-  const tokenConfigAccount = await (program.account as any).tokenConfig.fetch(tokenConfig);
-  if (!tokenConfigAccount) {
-    throw new Error('Token not whitelisted');
-  }
-  
-  // Get the pool account from token config
-  const poolAccount = tokenConfigAccount.poolAddress;
-  
+
   // Use protocol state's total loans count as the loan index
   const loanIndex = protocolStateAccount.totalLoansCreated;
-  
+
   const [loan] = pda.getLoanPDA(
     params.borrower,
     params.tokenMint,
     loanIndex,
     program.programId
   );
-  
-  // Use new vault derivation - vault is now derived from loan PDA
-  const [vaultTokenAccount] = PublicKey.findProgramAddressSync(
+
+  // Vault is derived from the loan PDA
+  const [vault] = PublicKey.findProgramAddressSync(
     [Buffer.from("vault"), loan.toBuffer()],
     program.programId
   );
-  
+
+  const tokenProgram = await getTokenProgramForMint(
+    program.provider.connection,
+    params.tokenMint
+  );
   const borrowerTokenAccount = await getAssociatedTokenAddress(
     params.tokenMint,
-    params.borrower
+    params.borrower,
+    false,
+    tokenProgram
   );
 
-  // Determine pool program based on pool type
-  let poolProgram: PublicKey;
-  if (tokenConfigAccount.poolType.pumpfun) {
-    poolProgram = PUMPFUN_PROGRAM_ID;
-  } else if (tokenConfigAccount.poolType.raydium) {
-    poolProgram = RAYDIUM_PROGRAM_ID;
-  } else {
-    poolProgram = ORCA_PROGRAM_ID;
-  }
+  // Once the M-of-N price authority set exists it must be passed
+  const setInfo = await program.provider.connection.getAccountInfo(priceAuthoritySet);
+
+  return {
+    protocolState,
+    tokenConfig,
+    riskConfig,
+    loan,
+    treasury,
+    borrower: params.borrower,
+    userExposure,
+    borrowerTokenAccount,
+    vault,
+    priceHistory,
+    tokenMint: params.tokenMint,
+    priceAuthority: params.priceAuthority ?? null,
+    instructionsSysvar: params.priceAuthority ? null : SYSVAR_INSTRUCTIONS_PUBKEY,
+    priceAuthoritySet: setInfo ? priceAuthoritySet : null,
+    tokenProgram,
+    systemProgram: SystemProgram.programId,
+  };
+}
+
+export async function buildCreateLoanTransaction(
+  program: Program,
+  params: CreateLoanParams
+): Promise<Transaction> {
+  const accounts = await getCreateLoanAccounts(program, params);
 
   // Build transaction without sending
-  const tx = await program.methods
-    .createLoan(params.collateralAmount, params.durationSeconds)
-    .accounts({
-      loan,
-      protocolState,
-      tokenConfig,
-      treasury,
-      borrower: params.borrower,
-      borrowerTokenAccount,
-      vaultTokenAccount,
-      tokenMint: params.tokenMint,
-      poolAccount,        // Now properly set!
-      poolProgram,        // Pool program for CPI if needed
-      ...getCommonInstructionAccounts(),
-    })
-    .transaction();  // Returns Transaction instead of sending
-  
-  return tx;
+  return program.methods
+    .createLoan(
+      params.collateralAmount,
+      params.durationSeconds,
+      params.approvedPrice,
+      params.priceTimestamp
+    )
+    .accounts(accounts)
+    .transaction();
 }
 
 export async function buildRepayLoanTransaction(
@@ -257,78 +274,18 @@ export async function buildRepayLoanTransaction(
 
 export async function createLoan(
   program: Program,
-  params: {
-    tokenMint: PublicKey;
-    collateralAmount: BN;
-    durationSeconds: BN;
-    borrower: PublicKey;
-  }
+  params: CreateLoanParams
 ): Promise<TransactionSignature> {
-  const [protocolState] = pda.getProtocolStatePDA(program.programId);
-  const [tokenConfig] = pda.getTokenConfigPDA(params.tokenMint, program.programId);
-  const [treasury] = pda.getTreasuryPDA(program.programId);
-  
-  // Fetch protocol state to get loan index
-  const protocolStateAccount = await (program.account as any).protocolState.fetch(protocolState);
-  if (!protocolStateAccount) {
-    throw new Error('Protocol not initialized');
-  }
-  
-  // Fetch token config to get pool address
-  const tokenConfigAccount = await (program.account as any).tokenConfig.fetch(tokenConfig);
-  if (!tokenConfigAccount) {
-    throw new Error('Token not whitelisted');
-  }
-  
-  // Get the pool account from token config
-  const poolAccount = tokenConfigAccount.poolAddress;
-  
-  // Use protocol state's total loans count as the loan index
-  const loanIndex = protocolStateAccount.totalLoansCreated;
-  
-  const [loan] = pda.getLoanPDA(
-    params.borrower,
-    params.tokenMint,
-    loanIndex,
-    program.programId
-  );
-  
-  // Use new vault derivation - vault is now derived from loan PDA
-  const [vaultTokenAccount] = PublicKey.findProgramAddressSync(
-    [Buffer.from("vault"), loan.toBuffer()],
-    program.programId
-  );
-  
-  const borrowerTokenAccount = await getAssociatedTokenAddress(
-    params.tokenMint,
-    params.borrower
-  );
-
-  // Determine pool program based on pool type
-  let poolProgram: PublicKey;
-  if (tokenConfigAccount.poolType.pumpfun) {
-    poolProgram = PUMPFUN_PROGRAM_ID;
-  } else if (tokenConfigAccount.poolType.raydium) {
-    poolProgram = RAYDIUM_PROGRAM_ID;
-  } else {
-    poolProgram = ORCA_PROGRAM_ID;
-  }
+  const accounts = await getCreateLoanAccounts(program, params);
 
   return program.methods
-    .createLoan(params.collateralAmount, params.durationSeconds)
-    .accounts({
-      loan,
-      protocolState,
-      tokenConfig,
-      treasury,
-      borrower: params.borrower,
-      borrowerTokenAccount,
-      vaultTokenAccount,
-      tokenMint: params.tokenMint,
-      poolAccount,        // Now properly set!
-      poolProgram,        // Pool program for CPI if needed
-      ...getCommonInstructionAccounts(),
-    })
+    .createLoan(
+      params.collateralAmount,
+      params.durationSeconds,
+      params.approvedPrice,
+      params.priceTimestamp
+    )
+    .accounts(accounts)
     .rpc();
 }
 
@@ -394,6 +351,26 @@ export async function repayLoan(
     .rpc();
 }
 
+/**
+ * Risk accounts every liquidation passes: the risk config, the token's price history (its
+ * TWAP decides price liquidations), the insurance fund if it exists and the borrower exposure
+ */
+async function getLiquidationRiskAccounts(program: Program, loanAccount: any) {
+  const [riskConfig] = pda.getRiskConfigPDA(program.programId);
+  const [priceHistory] = pda.getPriceHistoryPDA(loanAccount.tokenMint, program.programId);
+  const [insuranceFund] = pda.getInsuranceFundPDA(program.programId);
+  const [userExposure] = pda.getUserExposurePDA(loanAccount.borrower, program.programId);
+
+  const insuranceFundInfo = await program.provider.connection.getAccountInfo(insuranceFund);
+
+  return {
+    riskConfig,
+    priceHistory,
+    insuranceFund: insuranceFundInfo ? insuranceFund : null,
+    userExposure,
+  };
+}
+
 export async function liquidate(
   program: Program,
   loanPubkey: PublicKey,
@@ -407,11 +384,9 @@ export async function liquidate(
   
   const tokenMint = loanAccount.tokenMint;
   
-  // Fetch token config for pool address
   const [tokenConfigPDA] = pda.getTokenConfigPDA(tokenMint, program.programId);
-  const tokenConfigAccount = await (program.account as any).tokenConfig.fetch(tokenConfigPDA);
-  
-  const poolAccount = tokenConfigAccount.poolAddress;
+  const { riskConfig, priceHistory, insuranceFund, userExposure } =
+    await getLiquidationRiskAccounts(program, loanAccount);
   
   const [protocolState] = pda.getProtocolStatePDA(program.programId);
   const protocolStateAccount = await (program.account as any).protocolState.fetch(protocolState);
@@ -437,17 +412,21 @@ export async function liquidate(
   );
 
   return program.methods
-    .liquidate(minSolOutput, null) // Legacy parameters
+    .liquidate(minSolOutput, null, false) // Legacy parameters
     .accounts({
       protocolState,
       tokenConfig: tokenConfigPDA,
       loan: loanPubkey,
       treasury,
       operationsWallet,
+      borrower: loanAccount.borrower,
       vaultTokenAccount,
       vaultAuthority,
       tokenMint,
-      poolAccount,
+      riskConfig,
+      priceHistory,
+      insuranceFund,
+      userExposure,
       payer: program.provider.publicKey!,
       tokenProgram: tokenProgramId,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -479,10 +458,10 @@ export async function liquidateWithPumpfun(
   const { minSolOutput, bondingCurve, bondingCurveTokenAccount } = 
     await preparePumpfunLiquidation(connection, tokenMint, collateralAmount);
   
-  // Fetch token config and protocol state
+  // Derive token config and risk accounts, fetch protocol state
   const [tokenConfigPDA] = pda.getTokenConfigPDA(tokenMint, program.programId);
-  const tokenConfigAccount = await (program.account as any).tokenConfig.fetch(tokenConfigPDA);
-  const poolAccount = tokenConfigAccount.poolAddress;
+  const { riskConfig, priceHistory, insuranceFund, userExposure } =
+    await getLiquidationRiskAccounts(program, loanAccount);
   
   const [protocolState] = pda.getProtocolStatePDA(program.programId);
   const protocolStateAccount = await (program.account as any).protocolState.fetch(protocolState);
@@ -502,17 +481,21 @@ export async function liquidateWithPumpfun(
   );
 
   return program.methods
-    .liquidate(minSolOutput, null) // No Jupiter data for PumpFun
+    .liquidate(minSolOutput, null, false) // No Jupiter data for PumpFun
     .accounts({
       protocolState,
       tokenConfig: tokenConfigPDA,
       loan: loanPubkey,
       treasury,
       operationsWallet,
+      borrower: loanAccount.borrower,
       vaultTokenAccount,
       vaultAuthority,
       tokenMint,
-      poolAccount,
+      riskConfig,
+      priceHistory,
+      insuranceFund,
+      userExposure,
       pumpfunProgram: PUMPFUN_PROGRAM_ID,
       pumpfunGlobal: PUMPFUN_GLOBAL,
       pumpfunFeeRecipient: PUMPFUN_FEE_RECIPIENT,
@@ -556,10 +539,10 @@ export async function liquidateWithJupiter(
   const { minSolOutput, swapData, routeAccounts } = 
     await prepareJupiterLiquidation(tokenMint, collateralAmount, vaultAuthority, slippageBps);
   
-  // Fetch token config and protocol state
+  // Derive token config and risk accounts, fetch protocol state
   const [tokenConfigPDA] = pda.getTokenConfigPDA(tokenMint, program.programId);
-  const tokenConfigAccount = await (program.account as any).tokenConfig.fetch(tokenConfigPDA);
-  const poolAccount = tokenConfigAccount.poolAddress;
+  const { riskConfig, priceHistory, insuranceFund, userExposure } =
+    await getLiquidationRiskAccounts(program, loanAccount);
   
   const [protocolState] = pda.getProtocolStatePDA(program.programId);
   const protocolStateAccount = await (program.account as any).protocolState.fetch(protocolState);
@@ -574,17 +557,21 @@ export async function liquidateWithJupiter(
   );
 
   return program.methods
-    .liquidate(minSolOutput, Array.from(swapData)) // Jupiter swap data
+    .liquidate(minSolOutput, Array.from(swapData), false) // Jupiter swap data
     .accounts({
       protocolState,
       tokenConfig: tokenConfigPDA,
       loan: loanPubkey,
       treasury,
       operationsWallet,
+      borrower: loanAccount.borrower,
       vaultTokenAccount,
       vaultAuthority,
       tokenMint,
-      poolAccount,
+      riskConfig,
+      priceHistory,
+      insuranceFund,
+      userExposure,
       jupiterProgram: JUPITER_V6_PROGRAM_ID,
       payer: program.provider.publicKey!,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -595,6 +582,9 @@ export async function liquidateWithJupiter(
     .rpc();
 }
 
+/**
+ * Queue a token config change; it can be executed with `executeChange` after the timelock.
+ */
 export async function updateTokenConfig(
   program: Program,
   params: {
@@ -603,20 +593,17 @@ export async function updateTokenConfig(
     ltvBps?: number;
   }
 ): Promise<TransactionSignature> {
-  const [protocolState] = pda.getProtocolStatePDA(program.programId);
-  const [tokenConfig] = pda.getTokenConfigPDA(params.mint, program.programId);
-
-  return program.methods
-    .updateTokenConfig(
-      params.enabled ?? null,
-      params.ltvBps ?? null
-    )
-    .accounts({
-      protocolState,
-      tokenConfig,
-      admin: program.provider.publicKey!,
-    })
-    .rpc();
+  return proposeChange(program, {
+    tokenConfig: {
+      mint: params.mint,
+      enabled: params.enabled ?? null,
+      ltvBps: params.ltvBps ?? null,
+      poolAddress: null,
+      poolType: null,
+      minLoanAmount: null,
+      maxLoanAmount: null,
+    },
+  });
 }
 
 export async function pauseProtocol(
@@ -628,7 +615,7 @@ export async function pauseProtocol(
     .pauseProtocol()
     .accounts({
       protocolState,
-      admin: program.provider.publicKey!,
+      guardian: program.provider.publicKey!,
     })
     .rpc();
 }
@@ -647,24 +634,36 @@ export async function resumeProtocol(
     .rpc();
 }
 
-export async function withdrawTreasury(
+/**
+ * Revoke a role immediately (admin only). Granting one goes through proposeChange.
+ */
+export async function revokeRole(
   program: Program,
-  amount: BN
+  role: Record<string, Record<string, never>>
 ): Promise<TransactionSignature> {
   const [protocolState] = pda.getProtocolStatePDA(program.programId);
-  const [treasury] = pda.getTreasuryPDA(program.programId);
 
   return program.methods
-    .withdrawTreasury(amount)
+    .revokeRole(role)
     .accounts({
       protocolState,
-      treasury,
       admin: program.provider.publicKey!,
-      systemProgram: SystemProgram.programId,
     })
     .rpc();
 }
 
+/**
+ * Propose a treasury withdrawal to the caller. Runs once the multisig threshold approves it.
+ */
+export async function withdrawTreasury(
+  program: Program,
+  amount: BN
+): Promise<TransactionSignature> {
+  return proposeMultisigAction(program, {
+    withdrawTreasury: { amount, recipient: program.provider.publicKey! },
+  });
+}
+
 export async function fundTreasury(
   program: Program,
   amount: BN
@@ -683,6 +682,9 @@ export async function fundTreasury(
     .rpc();
 }
 
+/**
+ * Queue a fee change; it can be executed with `executeChange` after the timelock.
+ */
 export async function updateFees(
   program: Program,
   params: {
@@ -690,44 +692,350 @@ export async function updateFees(
     treasuryFeeBps?: number;
     buybackFeeBps?: number;
     operationsFeeBps?: number;
+    liquidationPenaltyBps?: number;
   }
 ): Promise<TransactionSignature> {
-  const [protocolState] = pda.getProtocolStatePDA(program.programId);
-  
+  return proposeChange(program, {
+    fees: {
+      protocolFeeBps: params.protocolFeeBps ?? null,
+      treasuryFeeBps: params.treasuryFeeBps ?? null,
+      buybackFeeBps: params.buybackFeeBps ?? null,
+      operationsFeeBps: params.operationsFeeBps ?? null,
+      liquidationPenaltyBps: params.liquidationPenaltyBps ?? null,
+    },
+  });
+}
+
+/**
+ * Queue a liquidation settings change; it can be executed with `executeChange` after the timelock.
+ */
+export async function updateLiquidationSettings(
+  program: Program,
+  params: {
+    permissionlessLiquidation?: boolean;
+    keeperBonusBps?: number;
+    liquidatorExclusivitySeconds?: BN;
+  }
+): Promise<TransactionSignature> {
+  return proposeChange(program, {
+    liquidationSettings: {
+      permissionlessLiquidation: params.permissionlessLiquidation ?? null,
+      keeperBonusBps: params.keeperBonusBps ?? null,
+      liquidatorExclusivitySeconds: params.liquidatorExclusivitySeconds ?? null,
+    },
+  });
+}
+
+/**
+ * Record when a loan's TWAP crossed its liquidation price (or clear a stale mark).
+ * Starts the authorized liquidator's exclusivity window for permissionless keepers.
+ */
+export async function markLiquidatable(
+  program: Program,
+  loanPubkey: PublicKey
+): Promise<TransactionSignature> {
+  const loanAccount = await (program.account as any).loan.fetch(loanPubkey);
+  const [priceHistory] = pda.getPriceHistoryPDA(loanAccount.tokenMint, program.programId);
+  const [tokenConfig] = pda.getTokenConfigPDA(loanAccount.tokenMint, program.programId);
+  const [riskConfig] = pda.getRiskConfigPDA(program.programId);
+
   return program.methods
-    .updateFees(
-      params.protocolFeeBps ?? null,
-      params.treasuryFeeBps ?? null,
-      params.buybackFeeBps ?? null,
-      params.operationsFeeBps ?? null
-    )
+    .markLiquidatable()
     .accounts({
-      protocolState,
-      admin: program.provider.publicKey!,
+      tokenConfig,
+      riskConfig,
+      loan: loanPubkey,
+      tokenMint: loanAccount.tokenMint,
+      priceHistory,
+    })
+    .rpc();
+}
+
+/**
+ * Record a pool price checkpoint for a token's TWAP. Anyone may crank this.
+ * The program rejects it unless it is the only non-compute-budget instruction in the transaction.
+ */
+export async function recordPrice(
+  program: Program,
+  tokenMint: PublicKey
+): Promise<TransactionSignature> {
+  const [tokenConfig] = pda.getTokenConfigPDA(tokenMint, program.programId);
+  const [priceHistory] = pda.getPriceHistoryPDA(tokenMint, program.programId);
+  const tokenConfigAccount = await (program.account as any).tokenConfig.fetch(tokenConfig);
+  const poolAccount: PublicKey = tokenConfigAccount.poolAddress;
+
+  // Vault-priced pools read their reserves from the two token vaults
+  let vaults: { baseVault: PublicKey; quoteVault: PublicKey } | null = null;
+  if (tokenConfigAccount.poolType.pumpSwap || tokenConfigAccount.poolType.raydiumCpmm) {
+    const poolInfo = await program.provider.connection.getAccountInfo(poolAccount);
+    if (!poolInfo) {
+      throw new Error('Pool account not found');
+    }
+    vaults = tokenConfigAccount.poolType.pumpSwap
+      ? extractPumpSwapVaults(poolInfo.data)
+      : extractCpmmVaults(poolInfo.data);
+  }
+
+  return program.methods
+    .recordPrice()
+    .accounts({
+      tokenConfig,
+      tokenMint,
+      priceHistory,
+      poolAccount,
+      pumpswapBaseVault: vaults?.baseVault ?? null,
+      pumpswapQuoteVault: vaults?.quoteVault ?? null,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      payer: program.provider.publicKey!,
+      systemProgram: SystemProgram.programId,
     })
     .rpc();
 }
 
+/**
+ * Recompute the liquidation price of a loan opened before canonical prices. Anyone may call this.
+ */
+export async function migrateLoan(
+  program: Program,
+  loanPubkey: PublicKey
+): Promise<TransactionSignature> {
+  const loanAccount = await (program.account as any).loan.fetch(loanPubkey);
+  const [tokenConfig] = pda.getTokenConfigPDA(loanAccount.tokenMint, program.programId);
+  const [riskConfig] = pda.getRiskConfigPDA(program.programId);
+
+  return program.methods
+    .migrateLoan()
+    .accounts({
+      tokenConfig,
+      riskConfig,
+      loan: loanPubkey,
+      tokenMint: loanAccount.tokenMint,
+    })
+    .rpc();
+}
+
+/**
+ * Queue a fee wallet change; it can be executed with `executeChange` after the timelock.
+ */
 export async function updateWallets(
   program: Program,
   params: {
     newBuybackWallet?: PublicKey;
     newOperationsWallet?: PublicKey;
   }
+): Promise<TransactionSignature> {
+  return proposeChange(program, {
+    wallets: {
+      buybackWallet: params.newBuybackWallet ?? null,
+      operationsWallet: params.newOperationsWallet ?? null,
+    },
+  });
+}
+
+/**
+ * Queue a timelocked parameter change (Anchor enum encoding, e.g. `{ liquidator: { newLiquidator } }`).
+ */
+export async function proposeChange(
+  program: Program,
+  change: Record<string, unknown>
 ): Promise<TransactionSignature> {
   const [protocolState] = pda.getProtocolStatePDA(program.programId);
-  
+  const state = await (program.account as any).protocolState.fetch(protocolState);
+  const [pendingChange] = pda.getPendingChangePDA(state.changeNonce, program.programId);
+
   return program.methods
-    .updateWallets(
-      params.newBuybackWallet ?? null,
-      params.newOperationsWallet ?? null
-    )
+    .proposeChange(change)
+    .accounts({
+      protocolState,
+      pendingChange,
+      authority: program.provider.publicKey!,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+/**
+ * Apply a queued change once its delay has passed. Anyone may call this.
+ */
+export async function executeChange(
+  program: Program,
+  id: BN,
+  poolAccount?: PublicKey
+): Promise<TransactionSignature> {
+  const [protocolState] = pda.getProtocolStatePDA(program.programId);
+  const [pendingChange] = pda.getPendingChangePDA(id, program.programId);
+  const pending = await (program.account as any).pendingChange.fetch(pendingChange);
+  const targetMint = pending.change.tokenConfig?.mint ?? pending.change.tokenRiskOverrides?.mint;
+  const tokenConfig = targetMint
+    ? pda.getTokenConfigPDA(targetMint, program.programId)[0]
+    : null;
+  const riskConfig = pending.change.riskConfig ? pda.getRiskConfigPDA(program.programId)[0] : null;
+  // An insurance fee change only needs the fund when it exists (a zero fee applies without it)
+  const [insuranceFundPda] = pda.getInsuranceFundPDA(program.programId);
+  const fundExists = pending.change.insuranceFee
+    && (await program.provider.connection.getAccountInfo(insuranceFundPda)) !== null;
+  const insuranceFund = fundExists ? insuranceFundPda : null;
+
+  return program.methods
+    .executeChange()
+    .accounts({
+      protocolState,
+      pendingChange,
+      proposer: pending.proposedBy,
+      tokenConfig,
+      poolAccount: poolAccount ?? null,
+      riskConfig,
+      insuranceFund,
+      executor: program.provider.publicKey!,
+    })
+    .rpc();
+}
+
+export async function cancelChange(
+  program: Program,
+  id: BN
+): Promise<TransactionSignature> {
+  const [protocolState] = pda.getProtocolStatePDA(program.programId);
+  const [pendingChange] = pda.getPendingChangePDA(id, program.programId);
+  const pending = await (program.account as any).pendingChange.fetch(pendingChange);
+
+  return program.methods
+    .cancelChange()
     .accounts({
       protocolState,
+      pendingChange,
+      proposer: pending.proposedBy,
+      authority: program.provider.publicKey!,
+    })
+    .rpc();
+}
+
+export async function initializeMultisig(
+  program: Program,
+  signers: PublicKey[],
+  threshold: number
+): Promise<TransactionSignature> {
+  const [protocolState] = pda.getProtocolStatePDA(program.programId);
+  const [multisig] = pda.getMultisigPDA(program.programId);
+
+  return program.methods
+    .initializeMultisig(signers, threshold)
+    .accounts({
+      protocolState,
+      multisig,
       admin: program.provider.publicKey!,
+      systemProgram: SystemProgram.programId,
     })
     .rpc();
 }
+
+/**
+ * Open a multisig proposal (Anchor enum encoding, e.g. `{ initiateAdminTransfer: { newAdmin } }`).
+ */
+export async function proposeMultisigAction(
+  program: Program,
+  action: Record<string, unknown>
+): Promise<TransactionSignature> {
+  const [protocolState] = pda.getProtocolStatePDA(program.programId);
+  const [multisig] = pda.getMultisigPDA(program.programId);
+  const state = await (program.account as any).multisig.fetch(multisig);
+  const [proposal] = pda.getMultisigProposalPDA(state.proposalNonce, program.programId);
+
+  return program.methods
+    .proposeMultisigAction(action)
+    .accounts({
+      protocolState,
+      multisig,
+      proposal,
+      proposer: program.provider.publicKey!,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+export async function approveMultisigAction(
+  program: Program,
+  id: BN
+): Promise<TransactionSignature> {
+  const [multisig] = pda.getMultisigPDA(program.programId);
+  const [proposal] = pda.getMultisigProposalPDA(id, program.programId);
+
+  return program.methods
+    .approveMultisigAction()
+    .accounts({
+      multisig,
+      proposal,
+      signer: program.provider.publicKey!,
+    })
+    .rpc();
+}
+
+/**
+ * Execute a multisig proposal once it has enough approvals. Anyone may call this.
+ */
+export async function executeMultisigAction(
+  program: Program,
+  id: BN
+): Promise<TransactionSignature> {
+  const [protocolState] = pda.getProtocolStatePDA(program.programId);
+  const [multisig] = pda.getMultisigPDA(program.programId);
+  const [treasury] = pda.getTreasuryPDA(program.programId);
+  const [proposal] = pda.getMultisigProposalPDA(id, program.programId);
+  const pending = await (program.account as any).multisigProposal.fetch(proposal);
+  const recipient =
+    pending.action.emergencyDrain?.recipient ?? pending.action.withdrawTreasury?.recipient ?? null;
+  const priceAuthoritySet =
+    pending.action.rotatePriceSigner || pending.action.updatePriceQuorum
+      ? pda.getPriceAuthoritySetPDA(program.programId)[0]
+      : null;
+
+  return program.methods
+    .executeMultisigAction()
+    .accounts({
+      protocolState,
+      multisig,
+      proposal,
+      proposer: pending.proposedBy,
+      treasury,
+      recipient,
+      priceAuthoritySet,
+      executor: program.provider.publicKey!,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+export async function cancelMultisigAction(
+  program: Program,
+  id: BN
+): Promise<TransactionSignature> {
+  const [multisig] = pda.getMultisigPDA(program.programId);
+  const [proposal] = pda.getMultisigProposalPDA(id, program.programId);
+  const pending = await (program.account as any).multisigProposal.fetch(proposal);
+
+  return program.methods
+    .cancelMultisigAction()
+    .accounts({
+      multisig,
+      proposal,
+      proposer: pending.proposedBy,
+      authority: program.provider.publicKey!,
+    })
+    .rpc();
+}
+
+/**
+ * Queue a role grant (`{ guardian: {} }`, `{ riskManager: {} }` or `{ treasurer: {} }`).
+ * Executed with `executeChange` after the timelock; use `revokeRole` to remove a role.
+ */
+export async function setRole(
+  program: Program,
+  role: Record<string, Record<string, never>>,
+  holder: PublicKey
+): Promise<TransactionSignature> {
+  return proposeChange(program, { role: { role, holder } });
+}
+
 export async function closeProtocolState(
   program: Program
 ): Promise<TransactionSignature> {
@@ -136,3 +136,71 @@ export function getFeeReceiverPDA(programId: PublicKey): [PublicKey, number] {
     programId
   );
 }
+
+export function getPriceHistoryPDA(
+  mint: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('price_history'), mint.toBuffer()],
+    programId
+  );
+}
+
+export function getInsuranceFundPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('insurance_fund')],
+    programId
+  );
+}
+
+export function getPriceAuthoritySetPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('price_authority_set')],
+    programId
+  );
+}
+
+export function getRiskConfigPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('risk_config')],
+    programId
+  );
+}
+
+export function getUserExposurePDA(
+  borrower: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('user_exposure'), borrower.toBuffer()],
+    programId
+  );
+}
+
+export function getPendingChangePDA(
+  id: BN,
+  programId: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('pending_change'), id.toArrayLike(Buffer, 'le', 8)],
+    programId
+  );
+}
+
+export function getMultisigPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('multisig')],
+    programId
+  );
+}
+
+export function getMultisigProposalPDA(
+  id: BN,
+  programId: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('multisig_proposal'), id.toArrayLike(Buffer, 'le', 8)],
+    programId
+  );
+}
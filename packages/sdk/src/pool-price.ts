@@ -12,14 +12,17 @@ import BN from 'bn.js';
 // CONSTANTS - Must match programs/memecoin-lending/src/utils.rs
 // ============================================================
 
-/** Price scale factor (1e9) - same as on-chain PRICE_SCALE */
-export const PRICE_SCALE = new BN(1_000_000_000);
+/** Price scale factor (1e6) - same as on-chain PRICE_SCALE */
+export const PRICE_SCALE = new BN(1_000_000);
 
-/** Decimal adjustment for PumpSwap (SOL has 9 decimals, tokens have 6) */
-export const DECIMAL_ADJUSTMENT = new BN(1000);
+/** Default token decimals (pump.fun tokens use 6) */
+export const DEFAULT_TOKEN_DECIMALS = 6;
 
-/** The divisor to convert scaled price to human-readable SOL */
-export const PRICE_TO_SOL_DIVISOR = 1_000_000; // 1e6
+/**
+ * The divisor to convert scaled price to human-readable SOL
+ * On-chain prices are lamports per whole token * PRICE_SCALE
+ */
+export const PRICE_TO_SOL_DIVISOR = 1_000_000_000_000_000; // 1e9 * 1e6
 
 // PumpSwap Pool Layout - MUST match programs/memecoin-lending/src/utils.rs
 // See IDL layout comment in utils.rs for full structure
@@ -28,6 +31,10 @@ const PUMPSWAP_POOL_BASE_VAULT_OFFSET = 139;  // pool_base_token_account
 const PUMPSWAP_POOL_QUOTE_VAULT_OFFSET = 171; // pool_quote_token_account
 const PUMPSWAP_POOL_MIN_LEN = 211;
 
+// Raydium CPMM PoolState layout - MUST match programs/memecoin-lending/src/utils.rs
+const RAYDIUM_CPMM_TOKEN_0_VAULT_OFFSET = 72;
+const RAYDIUM_CPMM_TOKEN_1_VAULT_OFFSET = 104;
+
 /** Token account amount offset */
 const TOKEN_ACCOUNT_AMOUNT_OFFSET = 64;
 
@@ -84,6 +91,24 @@ export function extractPumpSwapVaults(poolData: Buffer): PumpSwapVaults {
   return { baseVault, quoteVault };
 }
 
+/**
+ * Extract the token_0 / token_1 vault addresses from Raydium CPMM pool data
+ */
+export function extractCpmmVaults(poolData: Buffer): PumpSwapVaults {
+  if (poolData.length < RAYDIUM_CPMM_TOKEN_1_VAULT_OFFSET + 32) {
+    throw new Error(`Pool data too short: ${poolData.length} bytes`);
+  }
+
+  const baseVault = new PublicKey(
+    poolData.slice(RAYDIUM_CPMM_TOKEN_0_VAULT_OFFSET, RAYDIUM_CPMM_TOKEN_0_VAULT_OFFSET + 32)
+  );
+  const quoteVault = new PublicKey(
+    poolData.slice(RAYDIUM_CPMM_TOKEN_1_VAULT_OFFSET, RAYDIUM_CPMM_TOKEN_1_VAULT_OFFSET + 32)
+  );
+
+  return { baseVault, quoteVault };
+}
+
 /**
  * Read token amount from a token account's raw data
  */
@@ -99,11 +124,12 @@ export function readTokenAccountAmount(accountData: Buffer): BN {
 /**
  * Calculate PumpSwap price using the same formula as on-chain
  * 
- * Formula: (quote_amount * PRICE_SCALE) / base_amount / 1000
+ * Formula: (quote_amount * PRICE_SCALE * 10^decimals) / base_amount
  */
 export function calculatePumpSwapPrice(
   baseVaultAmount: BN,
-  quoteVaultAmount: BN
+  quoteVaultAmount: BN,
+  tokenDecimals: number = DEFAULT_TOKEN_DECIMALS
 ): BN {
   if (baseVaultAmount.isZero()) {
     throw new Error('Base vault amount is zero');
@@ -114,30 +140,29 @@ export function calculatePumpSwapPrice(
 
   return quoteVaultAmount
     .mul(PRICE_SCALE)
-    .div(baseVaultAmount)
-    .div(DECIMAL_ADJUSTMENT);
+    .mul(new BN(10).pow(new BN(tokenDecimals)))
+    .div(baseVaultAmount);
 }
 
 /**
  * Convert Jupiter price (SOL per token) to scaled format matching on-chain
  * 
  * Jupiter returns: 0.000773 (human-readable SOL per token)
- * On-chain stores: 773 (scaled by 1e6)
+ * On-chain stores: 773_000_000_000 (773_000 lamports per token, scaled by 1e6)
  * 
- * Conversion: jupiterPrice * 1e6
+ * Conversion: jupiterPrice * 1e15
  */
 export function convertJupiterPriceToScaled(jupiterSolPrice: number): BN {
-  // Multiply by 1e6 to match on-chain format
-  // Use Math.round to handle floating point precision
+  // Use Math.round to handle floating point precision; toFixed keeps values above 2^53 as digits
   const scaled = Math.round(jupiterSolPrice * PRICE_TO_SOL_DIVISOR);
-  return new BN(scaled);
+  return new BN(scaled.toFixed(0));
 }
 
 /**
  * Convert scaled price to human-readable SOL
  */
 export function convertScaledPriceToSol(scaledPrice: BN): number {
-  return scaledPrice.toNumber() / PRICE_TO_SOL_DIVISOR;
+  return Number(scaledPrice.toString()) / PRICE_TO_SOL_DIVISOR;
 }
 
 // ============================================================
@@ -150,7 +175,8 @@ export function convertScaledPriceToSol(scaledPrice: BN): number {
 export async function getPriceFromPool(
   connection: Connection,
   poolAddress: PublicKey,
-  poolType: PoolType | string
+  poolType: PoolType | string,
+  tokenDecimals: number = DEFAULT_TOKEN_DECIMALS
 ): Promise<PoolPriceResult> {
   const normalizedPoolType = typeof poolType === 'string' 
     ? poolType as PoolType 
@@ -187,7 +213,7 @@ export async function getPriceFromPool(
   const quoteVaultBalance = readTokenAccountAmount(quoteVaultAccount.data);
 
   // 5. Calculate price
-  const priceScaled = calculatePumpSwapPrice(baseVaultBalance, quoteVaultBalance);
+  const priceScaled = calculatePumpSwapPrice(baseVaultBalance, quoteVaultBalance, tokenDecimals);
   const priceInSol = convertScaledPriceToSol(priceScaled);
 
   return {
@@ -211,13 +237,14 @@ export async function getPriceWithFallback(
   tokenMint: PublicKey,
   poolAddress: PublicKey | null,
   poolType: PoolType | string | null,
-  jupiterApiEndpoint: string = 'https://price.jup.ag/v6/price'
+  jupiterApiEndpoint: string = 'https://price.jup.ag/v6/price',
+  tokenDecimals: number = DEFAULT_TOKEN_DECIMALS
 ): Promise<PoolPriceResult> {
   
   // Try pool price first (if we have pool info)
   if (poolAddress && poolType) {
     try {
-      const poolPrice = await getPriceFromPool(connection, poolAddress, poolType);
+      const poolPrice = await getPriceFromPool(connection, poolAddress, poolType, tokenDecimals);
       return poolPrice;
     } catch (error: any) {
       console.warn(`[PoolPrice] Failed to get pool price, falling back to Jupiter: ${error.message}`);
@@ -212,4 +212,30 @@ export function getLtvModifierDisplay(durationSeconds: number): string {
     const penaltyPercent = (MAX_PENALTY_BPS / 100) * (durationDiff / durationRange);
     return `-${penaltyPercent.toFixed(1)}%`;
   }
-}
\ No newline at end of file
+}
+/** Action tag of a price attestation - it only verifies for the instruction it was signed for */
+export const PRICE_ACTION = {
+  createLoan: 0,
+  extendLoan: 1,
+} as const;
+
+/**
+ * Message an off-line price signer signs for the Ed25519 attestation path:
+ * "MCLEND_PRICE_V2" || action (u8) || mint || price (u64 LE) || timestamp (i64 LE) || borrower
+ */
+export function buildPriceAttestationMessage(
+  action: (typeof PRICE_ACTION)[keyof typeof PRICE_ACTION],
+  mint: PublicKey,
+  price: BN,
+  timestamp: BN,
+  borrower: PublicKey
+): Buffer {
+  return Buffer.concat([
+    Buffer.from('MCLEND_PRICE_V2'),
+    Buffer.from([action]),
+    mint.toBuffer(),
+    price.toArrayLike(Buffer, 'le', 8),
+    timestamp.toTwos(64).toArrayLike(Buffer, 'le', 8),
+    borrower.toBuffer(),
+  ]);
+}
@@ -5,6 +5,10 @@ export enum PoolType {
   Orca = 'orca',
   Pumpfun = 'pumpfun',
   PumpSwap = 'pumpswap',
+  RaydiumCpmm = 'raydiumCpmm',
+  RaydiumClmm = 'raydiumClmm',
+  MeteoraDlmm = 'meteoraDlmm',
+  MeteoraDammV2 = 'meteoraDammV2',
 }
 
 export enum TokenTier {
@@ -18,6 +22,7 @@ export enum LoanStatus {
   Repaid = 'repaid',
   LiquidatedTime = 'liquidatedTime',
   LiquidatedPrice = 'liquidatedPrice',
+  InAuction = 'inAuction',
 }
 
 export interface ProtocolState {
@@ -25,9 +30,13 @@ export interface ProtocolState {
   buybackWallet: string;
   operationsWallet: string;
   paused: boolean;
+  /** Seconds spent paused across completed pauses; loan due dates move back by this */
+  totalPausedSeconds: number;
   totalLoansCreated: string;
   totalSolBorrowed: string;
   totalFeesEarned: string;
+  totalPrincipalRecovered: string;
+  totalLiquidationProfit: string;
   activeLoansCount: string;
   protocolFeeBps: number;
   treasuryFeeBps: number;
@@ -48,6 +57,9 @@ export interface TokenConfig {
   maxLoanAmount: string;
   activeLoansCount: string;
   totalVolume: string;
+  totalPrincipalRecovered: string;
+  totalLiquidationProfit: string;
+  totalFeeRevenue: string;
   isProtocolToken: boolean;
 }
 
@@ -95,6 +107,9 @@ export interface CreateLoanParams {
   collateralAmount: string;
   durationSeconds: number;
   borrower?: string;
+  /** Price approved by the price authority (lamports per whole token * PRICE_SCALE) */
+  approvedPrice?: string;
+  priceTimestamp?: number;
 }
 
 export interface LoanTermsParams {
@@ -238,8 +238,6 @@ pub enum LendingError {
     #[msg("Price signature expired")]
     PriceSignatureExpired = 6086,
 
-    // Keeping InvalidPriceSignature for potential future use
-    // to avoid renumbering all subsequent error codes
     #[msg("Invalid price signature")]
     InvalidPriceSignature = 6087,
     
@@ -270,7 +268,7 @@ pub enum LendingError {
     #[msg("Invalid PumpSwap vault address")]
     InvalidPumpSwapVault = 6096,
 
-    #[msg("Invalid pool owner - must be PumpSwap program")]
+    #[msg("Invalid pool owner - not the program of the configured pool type")]
     InvalidPoolOwner = 6097,
 
     #[msg("Pool token mismatch - base_mint doesn't match expected token")]
@@ -281,4 +279,106 @@ pub enum LendingError {
 
     #[msg("Invalid pool data structure")]
     InvalidPoolData = 6100,
+
+    #[msg("Loan expired")]
+    LoanExpired = 6101,
+
+    #[msg("Invalid borrower")]
+    InvalidBorrower = 6102,
+
+    #[msg("Partial liquidation only for healthy-by-time, underwater-by-price loans")]
+    PartialLiquidationNotAllowed = 6103,
+
+    #[msg("Partial liquidation sold too much collateral")]
+    PartialLiquidationTooLarge = 6104,
+
+    #[msg("Loan health not restored")]
+    HealthNotRestored = 6105,
+
+    #[msg("Not enough TWAP samples")]
+    InsufficientTwapSamples = 6106,
+
+    #[msg("Price recorded too recently")]
+    PriceRecordTooFrequent = 6107,
+
+    #[msg("Price quorum not met")]
+    PriceQuorumNotMet = 6108,
+
+    #[msg("Duplicate price signer")]
+    DuplicatePriceSigner = 6109,
+
+    #[msg("Invalid price quorum configuration")]
+    InvalidPriceQuorum = 6110,
+
+    #[msg("Bonding curve complete - token has migrated to PumpSwap")]
+    PumpfunCurveComplete = 6111,
+
+    #[msg("Bonding curve has not completed")]
+    PumpfunCurveNotComplete = 6112,
+
+    #[msg("Authorized liquidator exclusivity window still active")]
+    LiquidatorExclusivityActive = 6113,
+
+    #[msg("Loan must be marked liquidatable before permissionless liquidation")]
+    LoanNotMarkedLiquidatable = 6114,
+
+    #[msg("Invalid permissionless liquidation settings")]
+    InvalidLiquidationSettings = 6115,
+
+    #[msg("Auction cannot be settled yet - debt not covered, collateral remaining and not expired")]
+    AuctionNotSettleable = 6116,
+
+    #[msg("Auction price is above the bid's limit")]
+    AuctionPriceAboveLimit = 6117,
+
+    #[msg("Loan is not in auction")]
+    LoanNotInAuction = 6118,
+
+    #[msg("Auction has finished - settle it")]
+    AuctionFinished = 6119,
+
+    #[msg("Insurance fund account required while the insurance fee is on")]
+    MissingInsuranceFund = 6120,
+
+    #[msg("Insurance fee exceeds the treasury's share of the loan fee")]
+    InsuranceFeeTooHigh = 6121,
+
+    #[msg("Risk parameter out of bounds")]
+    InvalidRiskParameters = 6122,
+
+    #[msg("Parameter change is still timelocked")]
+    ChangeNotReady = 6123,
+
+    #[msg("Parameter change grace period has passed")]
+    ChangeExpired = 6124,
+
+    #[msg("Token config does not match the queued change")]
+    ChangeTargetMismatch = 6125,
+
+    #[msg("Invalid multisig signers or threshold")]
+    InvalidMultisigConfig = 6126,
+
+    #[msg("Signer is not a multisig member")]
+    NotMultisigSigner = 6127,
+
+    #[msg("Signer has already approved this proposal")]
+    AlreadyApproved = 6128,
+
+    #[msg("Multisig proposal does not have enough approvals")]
+    MultisigThresholdNotMet = 6129,
+
+    #[msg("Price authority set required - the single price authority cannot approve prices")]
+    PriceQuorumRequired = 6130,
+
+    #[msg("Missing Raydium CPMM vault accounts")]
+    MissingCpmmVaults = 6131,
+
+    #[msg("record_price must be a top-level instruction with only compute budget instructions beside it")]
+    PriceRecordNotStandalone = 6132,
+
+    #[msg("Proposal predates the current multisig signer set")]
+    MultisigProposalStale = 6133,
+
+    #[msg("Only role grants are timelocked - revoke a role with revoke_role")]
+    RoleRevocationNotQueued = 6134,
 }
\ No newline at end of file
@@ -0,0 +1,305 @@
+use anchor_lang::prelude::*;
+use crate::state::{LoanStatus, MultisigAction, ParameterChange, PoolType, RiskParams, Role, TokenTier};
+
+// ============================================================
+// Loan events
+// ============================================================
+
+#[event]
+pub struct LoanCreated {
+    pub loan: Pubkey,
+    pub borrower: Pubkey,
+    pub token_mint: Pubkey,
+    pub index: u64,
+    pub collateral_amount: u64,
+    pub sol_borrowed: u64,
+    pub entry_price: u64,
+    pub liquidation_price: u64,
+    pub due_at: i64,
+}
+
+#[event]
+pub struct LoanRepaid {
+    pub loan: Pubkey,
+    pub borrower: Pubkey,
+    pub token_mint: Pubkey,
+    pub sol_repaid: u64,
+    pub protocol_fee: u64,
+    pub collateral_returned: u64,
+}
+
+#[event]
+pub struct LoanPartiallyRepaid {
+    pub loan: Pubkey,
+    pub borrower: Pubkey,
+    pub sol_repaid: u64,
+    pub protocol_fee: u64,
+    pub collateral_released: u64,
+    pub remaining_borrowed: u64,
+    pub remaining_collateral: u64,
+    pub liquidation_price: u64,
+}
+
+#[event]
+pub struct CollateralAdded {
+    pub loan: Pubkey,
+    pub borrower: Pubkey,
+    pub amount: u64,
+    pub collateral_amount: u64,
+    pub liquidation_price: u64,
+}
+
+#[event]
+pub struct LoanExtended {
+    pub loan: Pubkey,
+    pub borrower: Pubkey,
+    pub additional_seconds: u64,
+    pub extension_fee: u64,
+    pub due_at: i64,
+}
+
+#[event]
+pub struct LoanLiquidated {
+    pub loan: Pubkey,
+    pub borrower: Pubkey,
+    pub token_mint: Pubkey,
+    pub liquidator: Pubkey,
+    /// LiquidatedTime or LiquidatedPrice
+    pub reason: LoanStatus,
+    pub partial: bool,
+    pub collateral_sold: u64,
+    pub sol_proceeds: u64,
+    pub principal_repaid: u64,
+    pub treasury_share: u64,
+    pub operations_share: u64,
+    /// Proceeds above the debt refunded to the borrower
+    pub borrower_surplus: u64,
+    /// Proceeds paid to a permissionless liquidator
+    pub keeper_bonus: u64,
+}
+
+#[event]
+pub struct BadDebtRecorded {
+    pub loan: Pubkey,
+    pub token_mint: Pubkey,
+    /// Principal not recovered by the liquidation
+    pub shortfall: u64,
+    /// Part of the shortfall the insurance fund paid to the treasury
+    pub insurance_payout: u64,
+    pub total_bad_debt: u64,
+}
+
+#[event]
+pub struct InsuranceFeeUpdated {
+    pub insurance_fee_bps: u16,
+}
+
+#[event]
+pub struct AuctionStarted {
+    pub loan: Pubkey,
+    pub token_mint: Pubkey,
+    pub initiator: Pubkey,
+    pub reason: LoanStatus,
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub duration: i64,
+    pub collateral_amount: u64,
+    pub debt: u64,
+}
+
+#[event]
+pub struct AuctionBid {
+    pub loan: Pubkey,
+    pub bidder: Pubkey,
+    pub price: u64,
+    pub collateral_bought: u64,
+    pub sol_paid: u64,
+    pub collateral_remaining: u64,
+    pub sol_raised: u64,
+}
+
+#[event]
+pub struct LoanLiquidatableMarked {
+    pub loan: Pubkey,
+    pub token_mint: Pubkey,
+    pub twap_price: u64,
+    pub liquidation_price: u64,
+    /// 0 when the mark was cleared because the loan recovered
+    pub liquidatable_since: i64,
+}
+
+// ============================================================
+// Token config events
+// ============================================================
+
+#[event]
+pub struct TokenWhitelisted {
+    pub mint: Pubkey,
+    pub tier: TokenTier,
+    pub pool_address: Pubkey,
+    pub pool_type: PoolType,
+    pub ltv_bps: u16,
+    pub min_loan_amount: u64,
+    pub max_loan_amount: u64,
+    pub is_protocol_token: bool,
+}
+
+#[event]
+pub struct TokenConfigUpdated {
+    pub mint: Pubkey,
+    pub enabled: bool,
+    pub ltv_bps: u16,
+    pub pool_address: Pubkey,
+    pub pool_type: PoolType,
+}
+
+// ============================================================
+// Protocol admin events
+// ============================================================
+
+#[event]
+pub struct FeesUpdated {
+    pub protocol_fee_bps: u16,
+    pub treasury_fee_bps: u16,
+    pub buyback_fee_bps: u16,
+    pub operations_fee_bps: u16,
+    pub liquidation_penalty_bps: u16,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub role: Role,
+    /// Pubkey::default() when revoked
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct ChangeProposed {
+    pub id: u64,
+    pub change: ParameterChange,
+    pub proposed_by: Pubkey,
+    pub execute_after: i64,
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub id: u64,
+    pub change: ParameterChange,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub id: u64,
+    pub change: ParameterChange,
+}
+
+#[event]
+pub struct RiskConfigUpdated {
+    pub params: RiskParams,
+}
+
+#[event]
+pub struct TokenRiskOverridesUpdated {
+    pub mint: Pubkey,
+    pub overrides: RiskParams,
+}
+
+#[event]
+pub struct LiquidationSettingsUpdated {
+    pub permissionless_liquidation: bool,
+    pub keeper_bonus_bps: u16,
+    pub liquidator_exclusivity_seconds: i64,
+}
+
+#[event]
+pub struct ProtocolPaused {
+    pub paused_by: Pubkey,
+    pub paused_at: i64,
+}
+
+#[event]
+pub struct ProtocolResumed {
+    pub resumed_at: i64,
+    pub total_paused_seconds: i64,
+}
+
+#[event]
+pub struct AdminTransferInitiated {
+    pub current_admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub initiated_at: i64,
+}
+
+#[event]
+pub struct MultisigActionProposed {
+    pub id: u64,
+    pub action: MultisigAction,
+    pub proposed_by: Pubkey,
+    pub execute_after: i64,
+}
+
+#[event]
+pub struct MultisigActionApproved {
+    pub id: u64,
+    pub signer: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct MultisigActionExecuted {
+    pub id: u64,
+    pub action: MultisigAction,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct MultisigActionCancelled {
+    pub id: u64,
+    pub action: MultisigAction,
+}
+
+// ============================================================
+// Staking & fee distribution events
+// ============================================================
+
+#[event]
+pub struct Staked {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub staked_amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub staked_amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct EpochAdvanced {
+    pub epoch: u64,
+    pub epoch_start_time: i64,
+    pub last_epoch_rewards: u64,
+    pub last_epoch_eligible_stake: u64,
+    pub rolled_over_rewards: u64,
+}
+
+#[event]
+pub struct RewardsDistributed {
+    pub epoch: u64,
+    pub amount: u64,
+    pub last_epoch_distributed: u64,
+}
+
+#[event]
+pub struct CreatorFeesDistributed {
+    pub total: u64,
+    pub treasury_amount: u64,
+    pub staking_amount: u64,
+    pub operations_amount: u64,
+}
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked, TokenAccount, Mint, TokenInterface};
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct AddCollateral<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.paused @ LendingError::ProtocolPaused
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        seeds = [TOKEN_CONFIG_SEED, loan.token_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    /// Global risk limits (token overrides applied on top)
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::Active @ LendingError::LoanAlreadyRepaid,
+        constraint = loan.borrower == borrower.key() @ LendingError::Unauthorized
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    pub borrower: Signer<'info>,
+
+    /// Borrower's token account (source of additional collateral)
+    #[account(
+        mut,
+        constraint = borrower_token_account.owner == borrower.key() @ LendingError::InvalidTokenAccountOwner,
+        constraint = borrower_token_account.mint == token_mint.key(),
+        constraint = borrower_token_account.amount >= amount @ LendingError::InsufficientTokenBalance
+    )]
+    pub borrower_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Vault token account holding THIS loan's collateral
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = loan,
+        token::token_program = token_program,
+        seeds = [b"vault", loan.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == loan.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Top up an active loan's collateral to move it away from liquidation
+pub fn add_collateral_handler(ctx: Context<AddCollateral>, amount: u64) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+
+    ReentrancyGuard::enter(protocol_state)?;
+
+    require!(amount > 0, LendingError::InvalidAmount);
+
+    // Extra collateral cannot rescue a loan that is already past due
+    require!(
+        !ValidationUtils::is_loan_liquidatable_by_time(&ctx.accounts.loan, protocol_state, clock.unix_timestamp),
+        LendingError::LoanExpired
+    );
+
+    // Transfer additional collateral tokens to the loan's vault
+    // Use transfer_checked for both SPL Token and Token-2022
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.borrower_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.borrower.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+        },
+    );
+    token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.token_mint.decimals)?;
+
+    // Lower liquidation price for the larger collateral position
+    let loan = &mut ctx.accounts.loan;
+    LoanCalculator::upgrade_loan_prices(
+        loan,
+        ctx.accounts.token_config.ltv_bps,
+        ctx.accounts.risk_config.for_token(&ctx.accounts.token_config).liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    let collateral_amount = SafeMath::add(loan.collateral_amount, amount)?;
+
+    loan.liquidation_price = LoanCalculator::calculate_liquidation_price(
+        loan.sol_borrowed,
+        collateral_amount,
+        ctx.accounts.token_config.ltv_bps,
+        ctx.accounts.risk_config.for_token(&ctx.accounts.token_config).liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    loan.collateral_amount = collateral_amount;
+
+    emit!(CollateralAdded {
+        loan: loan.key(),
+        borrower: loan.borrower,
+        amount,
+        collateral_amount,
+        liquidation_price: loan.liquidation_price,
+    });
+
+    ReentrancyGuard::exit(protocol_state);
+
+    Ok(())
+}
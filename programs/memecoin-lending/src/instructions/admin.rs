@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::Token;
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 use crate::utils::*;
 
 /// Admin control context (pause/resume/update admin)
@@ -19,30 +19,6 @@ pub struct AdminControl<'info> {
     pub admin: Signer<'info>,
 }
 
-/// Withdraw treasury context
-#[derive(Accounts)]
-pub struct WithdrawTreasury<'info> {
-    #[account(
-        mut,
-        seeds = [PROTOCOL_STATE_SEED],
-        bump = protocol_state.bump,
-        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-
-    #[account(
-        mut,
-        seeds = [TREASURY_SEED],
-        bump
-    )]
-    pub treasury: SystemAccount<'info>,
-
-    #[account(mut)]
-    pub admin: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-}
-
 /// Accept admin transfer context
 #[derive(Accounts)]
 pub struct AcceptAdminTransfer<'info> {
@@ -57,57 +33,67 @@ pub struct AcceptAdminTransfer<'info> {
     pub new_admin: Signer<'info>,
 }
 
-/// Emergency drain context
+/// Pause context - the guardian may pause but nothing else
 #[derive(Accounts)]
-pub struct EmergencyDrain<'info> {
+pub struct GuardianControl<'info> {
     #[account(
         mut,
         seeds = [PROTOCOL_STATE_SEED],
         bump = protocol_state.bump,
-        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
+        constraint = protocol_state.has_role(&guardian.key(), Role::Guardian) @ LendingError::Unauthorized
     )]
     pub protocol_state: Account<'info, ProtocolState>,
 
-    #[account(
-        mut,
-        seeds = [TREASURY_SEED],
-        bump
-    )]
-    pub treasury: SystemAccount<'info>,
-
-    #[account(mut)]
-    pub admin: Signer<'info>,
-
-    /// Token accounts to drain (variable)
-    /// In practice, this would be passed as remaining_accounts
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    /// Guardian or admin
+    pub guardian: Signer<'info>,
 }
 
-/// Pause protocol operations
-pub fn pause_handler(ctx: Context<AdminControl>) -> Result<()> {
+pub fn pause_handler(ctx: Context<GuardianControl>) -> Result<()> {
     let protocol_state = &mut ctx.accounts.protocol_state;
     
-    protocol_state.paused = true;
+    start_pause(protocol_state, Clock::get()?.unix_timestamp);
     
+    emit!(ProtocolPaused {
+        paused_by: ctx.accounts.guardian.key(),
+        paused_at: protocol_state.paused_at,
+    });
     
     Ok(())
 }
 
-/// Resume protocol operations
+/// Resume protocol operations - the pause length is added to every loan's due date
 pub fn resume_handler(ctx: Context<AdminControl>) -> Result<()> {
     let protocol_state = &mut ctx.accounts.protocol_state;
-    
+    let now = Clock::get()?.unix_timestamp;
+    
+    if protocol_state.paused {
+        let paused_seconds = now.saturating_sub(protocol_state.paused_at).max(0);
+        protocol_state.total_paused_seconds = protocol_state.total_paused_seconds
+            .checked_add(paused_seconds)
+            .ok_or(LendingError::MathOverflow)?;
+        protocol_state.paused_at = 0;
+    }
     protocol_state.paused = false;
     
+    emit!(ProtocolResumed {
+        resumed_at: now,
+        total_paused_seconds: protocol_state.total_paused_seconds,
+    });
     
     Ok(())
 }
 
+/// Pause, keeping the start of an ongoing pause
+fn start_pause(protocol_state: &mut ProtocolState, now: i64) {
+    if !protocol_state.paused {
+        protocol_state.paused = true;
+        protocol_state.paused_at = now;
+    }
+}
 
-/// Initiate admin transfer (starts 48h timelock)
-pub fn initiate_admin_transfer_handler(ctx: Context<AdminControl>, new_admin: Pubkey) -> Result<()> {
-    let protocol_state = &mut ctx.accounts.protocol_state;
+
+/// Initiate admin transfer (starts 48h timelock) - approved through the multisig
+pub fn apply_admin_transfer_initiation(protocol_state: &mut ProtocolState, new_admin: Pubkey) -> Result<()> {
     let clock = Clock::get()?;
     
     require!(new_admin != Pubkey::default(), LendingError::InvalidAdminAddress);
@@ -116,6 +102,11 @@ pub fn initiate_admin_transfer_handler(ctx: Context<AdminControl>, new_admin: Pu
     protocol_state.pending_admin = new_admin;
     protocol_state.admin_transfer_timestamp = clock.unix_timestamp;
     
+    emit!(AdminTransferInitiated {
+        current_admin: protocol_state.admin,
+        pending_admin: new_admin,
+        initiated_at: clock.unix_timestamp,
+    });
     
     Ok(())
 }
@@ -150,48 +141,87 @@ pub fn cancel_admin_transfer_handler(ctx: Context<AdminControl>) -> Result<()> {
     Ok(())
 }
 
-/// Update authorized liquidator context
-#[derive(Accounts)]
-pub struct UpdateLiquidator<'info> {
-    #[account(
-        mut,
-        seeds = [PROTOCOL_STATE_SEED],
-        bump = protocol_state.bump,
-        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
+/// Revoke a role immediately (admin only) - a compromised holder must not keep it through the timelock
+pub fn revoke_role_handler(ctx: Context<AdminControl>, role: Role) -> Result<()> {
+    apply_role_update(&mut ctx.accounts.protocol_state, role, Pubkey::default())
+}
 
-    pub admin: Signer<'info>,
+/// Assign a role - grants are queued through propose_change / execute_change, revocations use revoke_role
+pub fn apply_role_update(protocol_state: &mut ProtocolState, role: Role, holder: Pubkey) -> Result<()> {
+    match role {
+        Role::Guardian => protocol_state.guardian = holder,
+        Role::RiskManager => protocol_state.risk_manager = holder,
+        Role::Treasurer => protocol_state.treasurer = holder,
+    }
+
+    emit!(RoleUpdated { role, holder });
+
+    Ok(())
 }
 
-/// Update authorized liquidator (admin only)
-pub fn update_liquidator_handler(
-    ctx: Context<UpdateLiquidator>,
-    new_liquidator: Pubkey,
-) -> Result<()> {
+/// Replace the authorized liquidator - queued through propose_change / execute_change
+pub fn apply_liquidator_update(protocol_state: &mut ProtocolState, new_liquidator: Pubkey) -> Result<()> {
     require!(
         new_liquidator != Pubkey::default(),
         LendingError::InvalidLiquidatorAddress
     );
     
-    ctx.accounts.protocol_state.authorized_liquidator = new_liquidator;
+    protocol_state.authorized_liquidator = new_liquidator;
     
     Ok(())
 }
 
-/// Withdraw SOL from treasury
-pub fn withdraw_treasury_handler(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
-    let protocol_state = &mut ctx.accounts.protocol_state;
-    
+/// Configure permissionless liquidation - queued through propose_change / execute_change
+pub fn apply_liquidation_settings_update(
+    protocol_state: &mut ProtocolState,
+    permissionless_liquidation: Option<bool>,
+    keeper_bonus_bps: Option<u16>,
+    liquidator_exclusivity_seconds: Option<i64>,
+) -> Result<()> {
+    if let Some(enabled) = permissionless_liquidation {
+        protocol_state.permissionless_liquidation = enabled;
+    }
+
+    if let Some(bonus_bps) = keeper_bonus_bps {
+        require!(bonus_bps <= MAX_KEEPER_BONUS_BPS, LendingError::InvalidLiquidationSettings);
+        protocol_state.keeper_bonus_bps = bonus_bps;
+    }
+
+    if let Some(window) = liquidator_exclusivity_seconds {
+        require!(
+            (0..=MAX_LIQUIDATOR_EXCLUSIVITY_SECONDS).contains(&window),
+            LendingError::InvalidLiquidationSettings
+        );
+        protocol_state.liquidator_exclusivity_seconds = window;
+    }
+
+    emit!(LiquidationSettingsUpdated {
+        permissionless_liquidation: protocol_state.permissionless_liquidation,
+        keeper_bonus_bps: protocol_state.keeper_bonus_bps,
+        liquidator_exclusivity_seconds: protocol_state.liquidator_exclusivity_seconds,
+    });
+
+    Ok(())
+}
+
+/// Withdraw SOL from treasury - approved through the multisig
+pub fn apply_treasury_withdrawal<'info>(
+    protocol_state: &ProtocolState,
+    treasury: &AccountInfo<'info>,
+    treasury_bump: u8,
+    recipient: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
     // Check treasury has sufficient balance using the utility function
-    let treasury_balance = TreasuryUtils::get_treasury_balance(&ctx.accounts.treasury.to_account_info());
+    let treasury_balance = TreasuryUtils::get_treasury_balance(treasury);
     if treasury_balance < amount {
         return Err(LendingError::InsufficientTreasuryBalance.into());
     }
 
     // Ensure we don't withdraw funds needed for active loans
     let available_balance = TreasuryUtils::get_available_balance(
-        &ctx.accounts.treasury.to_account_info(),
+        treasury,
         protocol_state.total_sol_borrowed,
     )?;
     
@@ -199,17 +229,16 @@ pub fn withdraw_treasury_handler(ctx: Context<WithdrawTreasury>, amount: u64) ->
         return Err(LendingError::InsufficientTreasuryBalance.into());
     }
 
-    // Transfer SOL from treasury to admin using CPI with PDA signer
-    let treasury_bump = ctx.bumps.treasury;
+    // Transfer SOL from treasury to the recipient using CPI with PDA signer
     let treasury_seeds: &[&[u8]] = &[TREASURY_SEED, &[treasury_bump]];
     let treasury_signer_seeds = &[treasury_seeds];
 
     system_program::transfer(
         CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
+            system_program.clone(),
             system_program::Transfer {
-                from: ctx.accounts.treasury.to_account_info(),
-                to: ctx.accounts.admin.to_account_info(),
+                from: treasury.clone(),
+                to: recipient.clone(),
             },
             treasury_signer_seeds,
         ),
@@ -223,14 +252,12 @@ pub fn withdraw_treasury_handler(ctx: Context<WithdrawTreasury>, amount: u64) ->
     Ok(())
 }
 
-/// Update wallet addresses (admin only)
-pub fn update_wallets_handler(
-    ctx: Context<AdminControl>,
+/// Replace the fee wallets - queued through propose_change / execute_change
+pub fn apply_wallets_update(
+    protocol_state: &mut ProtocolState,
     new_buyback_wallet: Option<Pubkey>,
     new_operations_wallet: Option<Pubkey>,
 ) -> Result<()> {
-    let protocol_state = &mut ctx.accounts.protocol_state;
-    
     if let Some(buyback) = new_buyback_wallet {
         protocol_state.buyback_wallet = buyback;
     }
@@ -249,7 +276,7 @@ pub struct BlacklistToken<'info> {
     #[account(
         seeds = [PROTOCOL_STATE_SEED],
         bump = protocol_state.bump,
-        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
+        constraint = protocol_state.has_role(&risk_manager.key(), Role::RiskManager) @ LendingError::Unauthorized
     )]
     pub protocol_state: Account<'info, ProtocolState>,
 
@@ -260,70 +287,173 @@ pub struct BlacklistToken<'info> {
     )]
     pub token_config: Account<'info, TokenConfig>,
 
-    pub admin: Signer<'info>,
+    /// Risk manager or admin
+    pub risk_manager: Signer<'info>,
 }
 
-/// Blacklist a token (admin only) - blocks new loans
+/// Blacklist a token (risk manager or admin) - blocks new loans
 pub fn blacklist_token_handler(ctx: Context<BlacklistToken>) -> Result<()> {
     ctx.accounts.token_config.blacklisted = true;
     Ok(())
 }
 
-/// Remove token from blacklist (admin only)
+/// Remove token from blacklist (risk manager or admin)
 pub fn unblacklist_token_handler(ctx: Context<BlacklistToken>) -> Result<()> {
     ctx.accounts.token_config.blacklisted = false;
     Ok(())
 }
 
-/// Update price authority context
+/// Replace the price authority - approved through the multisig, after PARAMETER_CHANGE_DELAY
+pub fn apply_price_authority_update(protocol_state: &mut ProtocolState, new_price_authority: Pubkey) -> Result<()> {
+    require!(
+        new_price_authority != Pubkey::default(),
+        LendingError::InvalidPriceAuthority
+    );
+    
+    protocol_state.price_authority = new_price_authority;
+    
+    Ok(())
+}
+
 #[derive(Accounts)]
-pub struct UpdatePriceAuthority<'info> {
+pub struct MigrateProtocolState<'info> {
+    /// CHECK: Pre-upgrade accounts are shorter than ProtocolState::LEN and cannot be deserialized -
+    /// owner, discriminator and admin are checked in the handler
     #[account(
         mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump
+    )]
+    pub protocol_state: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTokenConfig<'info> {
+    #[account(
         seeds = [PROTOCOL_STATE_SEED],
         bump = protocol_state.bump,
         constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
     )]
     pub protocol_state: Account<'info, ProtocolState>,
 
+    /// CHECK: Pre-upgrade accounts are shorter than TokenConfig::LEN and cannot be deserialized -
+    /// owner and discriminator are checked in the handler
+    #[account(
+        mut,
+        seeds = [TOKEN_CONFIG_SEED, token_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: UncheckedAccount<'info>,
+
+    /// CHECK: Only used as a PDA seed
+    pub token_mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
     pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
-/// Update price authority (admin only)
-pub fn update_price_authority_handler(
-    ctx: Context<UpdatePriceAuthority>,
-    new_price_authority: Pubkey,
+/// Grow a program account to `new_len`, topping up rent from `payer`
+/// New bytes are zero-initialized; a no-op once the account is large enough
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    discriminator: &[u8],
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
 ) -> Result<()> {
-    require!(
-        new_price_authority != Pubkey::default(),
-        LendingError::InvalidPriceAuthority
-    );
-    
-    ctx.accounts.protocol_state.price_authority = new_price_authority;
-    
+    require!(account.owner == &crate::ID, LendingError::InvalidAccountData);
+    {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= discriminator.len() && data[..discriminator.len()] == *discriminator,
+            LendingError::InvalidAccountData
+        );
+    }
+
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let rent_top_up = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_top_up,
+        )?;
+    }
+
+    account.resize(new_len)?;
     Ok(())
 }
 
-/// Emergency drain all funds (in case of critical vulnerability)
-pub fn emergency_drain_handler(ctx: Context<EmergencyDrain>) -> Result<()> {
-    let protocol_state = &mut ctx.accounts.protocol_state;
-    
+/// Grow the protocol state to the current layout (admin only)
+pub fn migrate_protocol_state_handler(ctx: Context<MigrateProtocolState>) -> Result<()> {
+    let protocol_state = ctx.accounts.protocol_state.to_account_info();
+    {
+        let data = protocol_state.try_borrow_data()?;
+        // admin is the first field after the discriminator
+        require!(data.len() >= 40, LendingError::InvalidAccountData);
+        require!(data[8..40] == ctx.accounts.admin.key().to_bytes(), LendingError::Unauthorized);
+    }
+
+    grow_account(
+        &protocol_state,
+        ProtocolState::DISCRIMINATOR,
+        ProtocolState::LEN,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
+
+/// Grow a token config to the current layout (admin only)
+pub fn migrate_token_config_handler(ctx: Context<MigrateTokenConfig>) -> Result<()> {
+    grow_account(
+        &ctx.accounts.token_config.to_account_info(),
+        TokenConfig::DISCRIMINATOR,
+        TokenConfig::LEN,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
+
+/// Emergency drain all funds (in case of critical vulnerability) - approved through the multisig
+pub fn apply_emergency_drain<'info>(
+    protocol_state: &mut ProtocolState,
+    treasury: &AccountInfo<'info>,
+    treasury_bump: u8,
+    recipient: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
     // Set protocol to paused
-    protocol_state.paused = true;
+    start_pause(protocol_state, Clock::get()?.unix_timestamp);
     
-    // Transfer all SOL from treasury to admin using CPI with PDA signer
-    let treasury_balance = TreasuryUtils::get_treasury_balance(&ctx.accounts.treasury.to_account_info());
+    // Transfer all SOL from treasury to the recipient using CPI with PDA signer
+    let treasury_balance = TreasuryUtils::get_treasury_balance(treasury);
     if treasury_balance > 0 {
-        let treasury_bump = ctx.bumps.treasury;
         let treasury_seeds: &[&[u8]] = &[TREASURY_SEED, &[treasury_bump]];
         let treasury_signer_seeds = &[treasury_seeds];
 
         system_program::transfer(
             CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
+                system_program.clone(),
                 system_program::Transfer {
-                    from: ctx.accounts.treasury.to_account_info(),
-                    to: ctx.accounts.admin.to_account_info(),
+                    from: treasury.clone(),
+                    to: recipient.clone(),
                 },
                 treasury_signer_seeds,
             ),
@@ -331,16 +461,13 @@ pub fn emergency_drain_handler(ctx: Context<EmergencyDrain>) -> Result<()> {
         )?;
     }
 
-    // Reset protocol state tracking
-    protocol_state.total_sol_borrowed = 0;
-    protocol_state.total_fees_earned = 0;
-    
-    
+    // Tracking is left alone. Revenue counters are lifetime totals on ProtocolState and every
+    // TokenConfig alike, and total_sol_borrowed still covers the open loans: repaying or
+    // liquidating them decrements it, TokenConfig.total_active_borrowed and UserExposure together
+
     // Note: In a production emergency drain, you would also want to:
     // 1. Drain all token vaults (passed via remaining_accounts)
     // 2. Emit detailed emergency events
-    // 3. Have multi-sig requirements
-    // 4. Implement timelock delays
     
     Ok(())
 }
\ No newline at end of file
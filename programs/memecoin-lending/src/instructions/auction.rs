@@ -0,0 +1,548 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, CloseAccount, TransferChecked, TokenAccount, Mint, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::*;
+
+// ============================================================
+// Start auction
+// ============================================================
+
+#[derive(Accounts)]
+pub struct StartAuction<'info> {
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.paused @ LendingError::ProtocolPaused
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        seeds = [TOKEN_CONFIG_SEED, loan.token_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    /// Global risk limits - a legacy loan's liquidation price is recomputed with them
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::Active @ LendingError::LoanAlreadyRepaid
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(constraint = token_mint.key() == loan.token_mint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = initiator,
+        space = LiquidationAuction::LEN,
+        seeds = [AUCTION_SEED, loan.key().as_ref()],
+        bump
+    )]
+    pub auction: Box<Account<'info, LiquidationAuction>>,
+
+    /// Recorded pool prices - the auction opens relative to their TWAP (newest checkpoint for an
+    /// expired loan when the TWAP window is too thin)
+    #[account(
+        seeds = [PRICE_HISTORY_SEED, loan.token_mint.as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
+    /// Authorized liquidator, or any signer under the permissionless liquidation rules
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Put a liquidatable loan's collateral up for a descending-price auction
+pub fn start_auction_handler(ctx: Context<StartAuction>) -> Result<()> {
+    let clock = Clock::get()?;
+    let protocol_state = &ctx.accounts.protocol_state;
+    let loan = &mut ctx.accounts.loan;
+    let token_config = &ctx.accounts.token_config;
+    LoanCalculator::upgrade_loan_prices(
+        loan,
+        token_config.ltv_bps,
+        ctx.accounts.risk_config.for_token(token_config).liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let liquidatable_by_time = ValidationUtils::is_loan_liquidatable_by_time(loan, protocol_state, clock.unix_timestamp);
+
+    // TWAP instead of spot reserves, which can be moved inside a single transaction.
+    // Only the price check needs it - an expired loan is not held up by a thin window
+    let twap = PriceFeedUtils::liquidation_twap(
+        &ctx.accounts.price_history,
+        clock.unix_timestamp,
+        liquidatable_by_time,
+    )?;
+    let liquidatable_by_price = twap
+        .is_some_and(|price| ValidationUtils::is_loan_liquidatable_by_price(loan, price));
+    require!(
+        liquidatable_by_time || liquidatable_by_price,
+        LendingError::LoanNotLiquidatable
+    );
+
+    ValidationUtils::validate_liquidator(
+        protocol_state,
+        loan,
+        &ctx.accounts.initiator.key(),
+        liquidatable_by_time,
+        liquidatable_by_price,
+        clock.unix_timestamp,
+    )?;
+
+    let reason = if liquidatable_by_price {
+        LoanStatus::LiquidatedPrice
+    } else {
+        LoanStatus::LiquidatedTime
+    };
+
+    // Without a TWAP the auction opens off the newest checkpoint; the descent corrects a stale start
+    let reference_price = match twap {
+        Some(price) => price,
+        None => PriceFeedUtils::latest_price(&ctx.accounts.price_history)?,
+    };
+    let start_price = SafeMath::mul_div(reference_price, AUCTION_START_PRICE_BPS, BPS_DIVISOR)?;
+    let floor_price = SafeMath::mul_div(reference_price, AUCTION_FLOOR_PRICE_BPS, BPS_DIVISOR)?;
+    require!(floor_price > 0, LendingError::ZeroPrice);
+
+    let debt = LoanCalculator::calculate_liquidation_debt(
+        loan.sol_borrowed,
+        protocol_state.liquidation_penalty_bps,
+    )?;
+
+    let auction = &mut ctx.accounts.auction;
+    auction.loan = loan.key();
+    auction.token_mint = loan.token_mint;
+    auction.initiator = ctx.accounts.initiator.key();
+    auction.reason = reason;
+    auction.start_price = start_price;
+    auction.floor_price = floor_price;
+    auction.started_at = clock.unix_timestamp;
+    auction.duration = AUCTION_DURATION_SECONDS;
+    auction.debt = debt;
+    auction.collateral_remaining = loan.collateral_amount;
+    auction.sol_raised = 0;
+    auction.bump = ctx.bumps.auction;
+    auction.paused_seconds_at_start = protocol_state.total_paused_seconds;
+
+    // Freezes repay / extend / add collateral until settlement
+    loan.status = LoanStatus::InAuction;
+
+    emit!(AuctionStarted {
+        loan: auction.loan,
+        token_mint: auction.token_mint,
+        initiator: auction.initiator,
+        reason,
+        start_price,
+        floor_price,
+        duration: auction.duration,
+        collateral_amount: auction.collateral_remaining,
+        debt,
+    });
+
+    Ok(())
+}
+
+// ============================================================
+// Bid
+// ============================================================
+
+#[derive(Accounts)]
+pub struct Bid<'info> {
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.paused @ LendingError::ProtocolPaused
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::InAuction @ LendingError::LoanNotInAuction
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, loan.key().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Box<Account<'info, LiquidationAuction>>,
+
+    /// Vault token account holding collateral
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Vault authority PDA
+    /// CHECK: PDA signer for the vault
+    #[account(
+        seeds = [VAULT_SEED, loan.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(address = loan.token_mint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Receives the collateral bought
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program
+    )]
+    pub bidder_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Buy up to `max_collateral` at the current auction price, if it is at most `max_price`
+/// Never sells more collateral than needed to cover the remaining debt
+pub fn bid_handler(ctx: Context<Bid>, max_collateral: u64, max_price: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let protocol_state = &ctx.accounts.protocol_state;
+    let auction = &mut ctx.accounts.auction;
+    let token_decimals = ctx.accounts.token_mint.decimals;
+
+    require!(!auction.is_settleable(protocol_state, clock.unix_timestamp), LendingError::AuctionFinished);
+
+    let price = auction.current_price(protocol_state, clock.unix_timestamp);
+    require!(price <= max_price, LendingError::AuctionPriceAboveLimit);
+
+    let collateral_for_debt = LoanCalculator::calculate_collateral_for_value(
+        auction.remaining_debt(),
+        price,
+        token_decimals,
+    )?;
+    let collateral_bought = max_collateral
+        .min(auction.collateral_remaining)
+        .min(collateral_for_debt);
+    let sol_paid = LoanCalculator::calculate_collateral_value(collateral_bought, price, token_decimals)?;
+    require!(collateral_bought > 0 && sol_paid > 0, LendingError::InvalidAmount);
+
+    // SOL is held by the auction account until settlement
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: auction.to_account_info(),
+            },
+        ),
+        sol_paid,
+    )?;
+
+    let loan_key = ctx.accounts.loan.key();
+    let vault_seeds = &[
+        VAULT_SEED,
+        loan_key.as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.bidder_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        collateral_bought,
+        token_decimals,
+    )?;
+
+    auction.collateral_remaining = SafeMath::sub(auction.collateral_remaining, collateral_bought)?;
+    auction.sol_raised = SafeMath::add(auction.sol_raised, sol_paid)?;
+
+    emit!(AuctionBid {
+        loan: loan_key,
+        bidder: ctx.accounts.bidder.key(),
+        price,
+        collateral_bought,
+        sol_paid,
+        collateral_remaining: auction.collateral_remaining,
+        sol_raised: auction.sol_raised,
+    });
+
+    Ok(())
+}
+
+// ============================================================
+// Settle auction
+// ============================================================
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_CONFIG_SEED, loan.token_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::InAuction @ LendingError::LoanNotInAuction
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, loan.key().as_ref()],
+        bump = auction.bump,
+        close = initiator
+    )]
+    pub auction: Box<Account<'info, LiquidationAuction>>,
+
+    /// Auction starter - gets the auction account rent back
+    #[account(
+        mut,
+        address = auction.initiator
+    )]
+    pub initiator: SystemAccount<'info>,
+
+    /// Protocol treasury - receives the principal and 95% of the debt recovered above it
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Operations wallet - receives 5% of the debt recovered above principal
+    #[account(
+        mut,
+        constraint = operations_wallet.key() == protocol_state.operations_wallet @ LendingError::Unauthorized
+    )]
+    pub operations_wallet: SystemAccount<'info>,
+
+    /// Insurance fund - covers shortfalls; required while the insurance fee is on
+    #[account(
+        mut,
+        seeds = [INSURANCE_FUND_SEED],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    /// Borrower - receives surplus SOL
+    #[account(
+        mut,
+        address = loan.borrower @ LendingError::InvalidBorrower
+    )]
+    pub borrower: SystemAccount<'info>,
+
+    /// Receives collateral left over once the debt is covered
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = borrower,
+        associated_token::token_program = token_program
+    )]
+    pub borrower_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Vault token account holding collateral
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Vault authority PDA
+    /// CHECK: PDA signer for the vault
+    #[account(
+        seeds = [VAULT_SEED, loan.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(address = loan.token_mint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Per-borrower exposure tracking (created on first use)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = UserExposure::LEN,
+        seeds = [USER_EXPOSURE_SEED, loan.borrower.as_ref()],
+        bump
+    )]
+    pub user_exposure: Box<Account<'info, UserExposure>>,
+
+    /// Anyone can settle a finished auction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle a finished auction: repay the treasury, pay operations, refund surplus SOL
+/// and leftover collateral to the borrower, and close the loan.
+/// An expired auction that did not cover the debt books the rest as a shortfall
+pub fn settle_auction_handler(ctx: Context<SettleAuction>) -> Result<()> {
+    let clock = Clock::get()?;
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let token_config = &mut ctx.accounts.token_config;
+    let auction = &ctx.accounts.auction;
+
+    ReentrancyGuard::enter(protocol_state)?;
+
+    require!(auction.is_settleable(protocol_state, clock.unix_timestamp), LendingError::AuctionNotSettleable);
+
+    let loan_key = ctx.accounts.loan.key();
+    let sol_borrowed = ctx.accounts.loan.sol_borrowed;
+    let sol_proceeds = auction.sol_raised;
+    let collateral_remaining = auction.collateral_remaining;
+    let collateral_sold = SafeMath::sub(ctx.accounts.loan.collateral_amount, collateral_remaining)?;
+
+    // Debt = principal + protocol fee + liquidation penalty, anything above belongs to the borrower
+    let (debt_recovered, surplus) = auction.settlement_proceeds();
+    let (treasury_share, operations_share, shortfall) =
+        LoanCalculator::split_liquidation_proceeds(debt_recovered, sol_borrowed)?;
+
+    // Pay out bidders' SOL; the auction's rent goes back to the initiator on close
+    **auction.to_account_info().try_borrow_mut_lamports()? -= sol_proceeds;
+    **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += treasury_share;
+    **ctx.accounts.operations_wallet.to_account_info().try_borrow_mut_lamports()? += operations_share;
+    if surplus > 0 {
+        **ctx.accounts.borrower.to_account_info().try_borrow_mut_lamports()? += surplus;
+    }
+
+    let vault_seeds = &[
+        VAULT_SEED,
+        loan_key.as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let vault_signer = &[&vault_seeds[..]];
+
+    if collateral_remaining > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.borrower_token_account.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+                vault_signer,
+            ),
+            collateral_remaining,
+            ctx.accounts.token_mint.decimals,
+        )?;
+    }
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault_token_account.to_account_info(),
+            destination: ctx.accounts.payer.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+        vault_signer,
+    ))?;
+
+    // Book the shortfall as bad debt and let the insurance fund make the treasury whole
+    InsuranceFundUtils::settle_shortfall(
+        protocol_state,
+        token_config,
+        ctx.accounts.insurance_fund.as_deref_mut(),
+        &ctx.accounts.treasury.to_account_info(),
+        loan_key,
+        shortfall,
+    )?;
+
+    let loan = &mut ctx.accounts.loan;
+    loan.status = auction.reason;
+
+    protocol_state.active_loans_count = SafeMath::sub(protocol_state.active_loans_count, 1)?;
+    protocol_state.total_sol_borrowed = SafeMath::sub(protocol_state.total_sol_borrowed, sol_borrowed)?;
+    RevenueTracker::record_liquidation(protocol_state, token_config, debt_recovered, sol_borrowed)?;
+
+    token_config.active_loans_count = SafeMath::sub(token_config.active_loans_count, 1)?;
+    token_config.total_active_borrowed = SafeMath::sub(token_config.total_active_borrowed, sol_borrowed)?;
+
+    UserExposureTracker::record_principal_closed(
+        &mut ctx.accounts.user_exposure,
+        loan.borrower,
+        ctx.bumps.user_exposure,
+        sol_borrowed,
+        true,
+        true,
+    )?;
+
+    emit!(LoanLiquidated {
+        loan: loan_key,
+        borrower: loan.borrower,
+        token_mint: loan.token_mint,
+        liquidator: auction.initiator,
+        reason: auction.reason,
+        partial: false,
+        collateral_sold,
+        sol_proceeds,
+        principal_repaid: sol_borrowed,
+        treasury_share,
+        operations_share,
+        borrower_surplus: surplus,
+        keeper_bonus: 0,
+    });
+
+    ReentrancyGuard::exit(protocol_state);
+
+    Ok(())
+}
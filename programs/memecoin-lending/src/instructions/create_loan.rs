@@ -3,6 +3,7 @@ use anchor_lang::system_program;
 use anchor_spl::token_interface::{self, TransferChecked, TokenAccount, Mint, TokenInterface};
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 use crate::utils::*;
 
 
@@ -26,6 +27,13 @@ pub struct CreateLoan<'info> {
     )]
     pub token_config: Box<Account<'info, TokenConfig>>,
 
+    /// Global risk limits (token overrides applied on top)
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
     #[account(
         init,
         payer = borrower,
@@ -52,6 +60,16 @@ pub struct CreateLoan<'info> {
     #[account(mut)]
     pub borrower: Signer<'info>,
 
+    /// Per-borrower exposure tracking (created on first use)
+    #[account(
+        init_if_needed,
+        payer = borrower,
+        space = UserExposure::LEN,
+        seeds = [USER_EXPOSURE_SEED, borrower.key().as_ref()],
+        bump
+    )]
+    pub user_exposure: Box<Account<'info, UserExposure>>,
+
     /// Borrower's token account (source of collateral)
     #[account(
         mut,
@@ -73,29 +91,34 @@ pub struct CreateLoan<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
-    /// Pool account for price reading
-    /// CHECK: Validated by token_config.pool_address constraint
+    /// Recorded pool prices - the approved price is sanity checked against their TWAP
     #[account(
-        constraint = pool_account.key() == token_config.pool_address @ LendingError::InvalidPoolAddress
+        seeds = [PRICE_HISTORY_SEED, token_mint.key().as_ref()],
+        bump = price_history.bump
     )]
-    pub pool_account: UncheckedAccount<'info>,
-
-    /// PumpSwap base token vault - required when pool_type is PumpSwap
-    /// CHECK: Validated in handler against pool data
-    pub pumpswap_base_vault: Option<UncheckedAccount<'info>>,
-
-    /// PumpSwap quote token vault (WSOL) - required when pool_type is PumpSwap  
-    /// CHECK: Validated in handler against pool data
-    pub pumpswap_quote_vault: Option<UncheckedAccount<'info>>,
+    pub price_history: Box<Account<'info, PriceHistory>>,
 
     pub token_mint: InterfaceAccount<'info, Mint>,
 
-    /// Price authority must co-sign to approve this loan
-    /// This proves the backend approved the price - SIMPLE AND SECURE
+    /// Price authority co-signing to approve this loan
+    /// Omit to approve the price with an Ed25519 attestation instead
     #[account(
         constraint = price_authority.key() == protocol_state.price_authority @ LendingError::InvalidPriceAuthority
     )]
-    pub price_authority: Signer<'info>,
+    pub price_authority: Option<Signer<'info>>,
+
+    /// Instructions sysvar - required when price_authority does not co-sign
+    /// CHECK: Address checked against the instructions sysvar id
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// M-of-N price authority set - the loan price is the median of its signers' Ed25519
+    /// attestations. Required once the set exists; before that the single price authority applies
+    #[account(
+        seeds = [PRICE_AUTHORITY_SET_SEED],
+        bump = price_authority_set.bump
+    )]
+    pub price_authority_set: Option<Box<Account<'info, PriceAuthoritySet>>>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -110,6 +133,7 @@ pub fn create_loan_handler(
 ) -> Result<()> {
     let protocol_state = &mut ctx.accounts.protocol_state;
     let token_config = &ctx.accounts.token_config;
+    let risk = ctx.accounts.risk_config.for_token(token_config);
     let loan = &mut ctx.accounts.loan;
     let clock = Clock::get()?;
 
@@ -122,120 +146,40 @@ pub fn create_loan_handler(
     // Validate loan duration
     ValidationUtils::validate_loan_duration(duration_seconds)?;
 
-    // Block loans for PumpFun tokens - they cannot be liquidated until migrated
-    require!(
-        token_config.pool_type != PoolType::Pumpfun,
-        LendingError::PumpfunNotMigrated
-    );
-
-    // Validate PumpSwap pool if pool_type is PumpSwap
-    if token_config.pool_type == PoolType::PumpSwap {
-        let (validated_base_vault, validated_quote_vault) = PumpSwapPoolValidator::validate_full(
-            &ctx.accounts.pool_account,
-            &ctx.accounts.token_mint.key(),
-        )?;
-        
-        // Verify the vault accounts passed match the ones in the pool
-        let base_vault_info = ctx.accounts.pumpswap_base_vault
-            .as_ref()
-            .ok_or(LendingError::MissingPumpSwapVaults)?;
-        let quote_vault_info = ctx.accounts.pumpswap_quote_vault
-            .as_ref()
-            .ok_or(LendingError::MissingPumpSwapVaults)?;
-        
-        require!(
-            base_vault_info.key == &validated_base_vault,
-            LendingError::InvalidPumpSwapVault
-        );
-        require!(
-            quote_vault_info.key == &validated_quote_vault,
-            LendingError::InvalidPumpSwapVault
-        );
-        
-        msg!("PumpSwap pool validated: base_mint and quote_mint verified");
-    }
-
     // ============================================================
     // SECURITY: Verify backend-approved price
     // ============================================================
-    // The price_authority account is a Signer, which means the backend
-    // MUST have signed this transaction. This proves the backend approved
-    // the price. No complex Ed25519 introspection needed!
-    
-    // Check price timestamp is recent (within 30 seconds)
-    let price_age = clock.unix_timestamp - price_timestamp;
-    require!(
-        price_age >= 0 && price_age <= MAX_PRICE_SIGNATURE_AGE_SECONDS,
-        LendingError::PriceSignatureExpired
-    );
-    
-    // Use the backend-approved price
-    let current_price = approved_price;
-    
-    require!(current_price > 0, LendingError::ZeroPrice);
-    
-    // Sanity check: compare against pool price (catches bugs/misconfigs)
-    let pool_data = ctx.accounts.pool_account.try_borrow_data()?;
-
-    let pool_price = match token_config.pool_type {
-        PoolType::PumpSwap => {
-            // PumpSwap requires vault accounts to read balances
-            let base_vault_info = ctx.accounts.pumpswap_base_vault
-                .as_ref()
-                .ok_or(LendingError::MissingPumpSwapVaults)?;
-            let quote_vault_info = ctx.accounts.pumpswap_quote_vault
-                .as_ref()
-                .ok_or(LendingError::MissingPumpSwapVaults)?;
-            
-            // Parse vault accounts as token accounts to get balances
-            let base_vault_data = base_vault_info.try_borrow_data()?;
-            let quote_vault_data = quote_vault_info.try_borrow_data()?;
-            
-            // Token account amount is at offset 64 (after mint, owner, amount)
-            let base_amount = u64::from_le_bytes(
-                base_vault_data[64..72].try_into().map_err(|_| LendingError::InvalidPriceFeed)?
-            );
-            let quote_amount = u64::from_le_bytes(
-                quote_vault_data[64..72].try_into().map_err(|_| LendingError::InvalidPriceFeed)?
-            );
-            
-            PriceFeedUtils::read_pumpswap_price(
-                &pool_data,
-                base_amount,
-                quote_amount,
-                base_vault_info.key,
-                quote_vault_info.key,
-            )?
-        },
-        _ => {
-            // For Raydium/Orca/PumpFun, read directly from pool
-            PriceFeedUtils::read_price_from_pool(
-                &ctx.accounts.pool_account,
-                token_config.pool_type,
-                &token_config.mint,
-            )?
-        }
-    };
-
-    drop(pool_data); // Release borrow before continuing
+    // Either the price_authority co-signed this transaction, the backend
+    // signed (prefix, action, mint, price, timestamp, borrower) off-line and the
+    // preceding Ed25519 instruction proves it, or a quorum of the price
+    // authority set attested and their median price is used
+    let current_price = PriceAttestationUtils::resolve_approved_price(
+        protocol_state.price_quorum_required,
+        ctx.accounts.price_authority_set.as_deref().map(|s| &**s),
+        ctx.accounts.price_authority.is_some(),
+        ctx.accounts.instructions_sysvar.as_ref().map(|s| s.as_ref()),
+        &protocol_state.price_authority,
+        PriceAction::CreateLoan,
+        &ctx.accounts.token_mint.key(),
+        &ctx.accounts.borrower.key(),
+        approved_price,
+        price_timestamp,
+        clock.unix_timestamp,
+    )?;
     
-    // 20% = 2000 bps - if larger, something is wrong
-    let deviation = if approved_price > pool_price {
-        SafeMath::mul_div(approved_price - pool_price, BPS_DIVISOR, pool_price)?
-    } else {
-        SafeMath::mul_div(pool_price - approved_price, BPS_DIVISOR, approved_price)?
-    };
+    // Sanity check: compare against the pool TWAP (catches bugs/misconfigs)
+    // TWAP instead of spot reserves, which can be moved inside a single transaction
+    let pool_price = PriceFeedUtils::calculate_twap(&ctx.accounts.price_history, clock.unix_timestamp)?;
     
-    require!(
-        deviation <= 2000,
-        LendingError::PriceDeviationTooHigh
-    );
+    // RiskConfig bound (20% by default) - if larger, something is wrong
+    ValidationUtils::validate_price_deviation(current_price, pool_price, risk.max_price_deviation_bps)?;
     
     // FIX 6: Validate minimum collateral value
-    let collateral_value = SafeMath::mul_div(
-        collateral_amount * 1000,
+    let token_decimals = ctx.accounts.token_mint.decimals;
+    let collateral_value = LoanCalculator::calculate_collateral_value(
+        collateral_amount,
         current_price,
-        PRICE_SCALE as u64,
+        token_decimals,
     )?;
 
     require!(
@@ -243,11 +187,12 @@ pub fn create_loan_handler(
         LendingError::CollateralValueTooLow
     );
 
-    // Calculate duration-adjusted LTV
-    let effective_ltv = LoanCalculator::calculate_duration_adjusted_ltv(
-        token_config.ltv_bps,
-        duration_seconds,
-    )?;
+    // Calculate duration-adjusted LTV - bonding curve tokens stay under their cap even with the short-loan bonus
+    let base_ltv = LoanCalculator::cap_ltv_for_pool(token_config.ltv_bps, token_config.pool_type);
+    let effective_ltv = LoanCalculator::cap_ltv_for_pool(
+        LoanCalculator::calculate_duration_adjusted_ltv(base_ltv, duration_seconds)?,
+        token_config.pool_type,
+    );
 
 
     // Calculate loan amount based on duration-adjusted LTV
@@ -255,6 +200,7 @@ pub fn create_loan_handler(
         collateral_amount,
         current_price,
         effective_ltv,
+        token_decimals,
     )?;
 
     // Validate loan amount against limits
@@ -272,11 +218,11 @@ pub fn create_loan_handler(
     }
 
     // ============================================================
-    // SECURITY CHECK 1: Dynamic Max Single Loan (10% of treasury)
+    // SECURITY CHECK 1: Dynamic Max Single Loan (RiskConfig share of treasury)
     // ============================================================
     let max_single_loan = SafeMath::mul_div(
         treasury_balance,
-        MAX_SINGLE_LOAN_BPS as u64,
+        risk.max_single_loan_bps as u64,
         BPS_DIVISOR
     )?;
 
@@ -287,11 +233,11 @@ pub fn create_loan_handler(
 
 
     // ============================================================
-    // SECURITY CHECK 2: Per-Token Exposure Limit (10% of treasury)
+    // SECURITY CHECK 2: Per-Token Exposure Limit (RiskConfig share of treasury)
     // ============================================================
     let max_token_exposure = SafeMath::mul_div(
         treasury_balance,
-        MAX_TOKEN_EXPOSURE_BPS as u64,
+        risk.max_token_exposure_bps as u64,
         BPS_DIVISOR
     )?;
 
@@ -306,8 +252,16 @@ pub fn create_loan_handler(
     );
 
 
-    // Note: User exposure tracking removed to fix stack overflow
-    // Can be re-implemented in a separate instruction if needed
+    // ============================================================
+    // SECURITY CHECK 3: Per-User Exposure Limit (30% of treasury)
+    // ============================================================
+    UserExposureTracker::record_borrow(
+        &mut ctx.accounts.user_exposure,
+        ctx.accounts.borrower.key(),
+        ctx.bumps.user_exposure,
+        sol_loan_amount,
+        treasury_balance,
+    )?;
 
     // ============================================================
     // SECURITY CHECK 4: Minimum Loan Amount (0.01 SOL)
@@ -317,12 +271,13 @@ pub fn create_loan_handler(
         LendingError::LoanAmountTooLow
     );
 
-    // Calculate liquidation price with updated buffer (40% drop triggers liquidation)
+    // Calculate liquidation price with the RiskConfig buffer (40% drop triggers liquidation by default)
     let liquidation_price = LoanCalculator::calculate_liquidation_price(
         sol_loan_amount,
         collateral_amount,
-        token_config.ltv_bps,
-        4000, // 40% liquidation buffer (was DEFAULT_LIQUIDATION_BUFFER_BPS = 3%)
+        base_ltv,
+        risk.liquidation_buffer_bps,
+        token_decimals,
     )?;
 
     // Transfer collateral tokens to loan's vault
@@ -362,8 +317,11 @@ pub fn create_loan_handler(
     loan.sol_borrowed = sol_loan_amount;
     loan.entry_price = current_price;
     loan.liquidation_price = liquidation_price;
+    loan.price_version = LOAN_PRICE_VERSION;
     loan.created_at = clock.unix_timestamp;
     loan.due_at = clock.unix_timestamp + duration_seconds as i64;
+    loan.original_duration = duration_seconds as i64;
+    loan.paused_seconds_at_open = protocol_state.total_paused_seconds;
     loan.status = LoanStatus::Active;
     loan.index = protocol_state.total_loans_created;
     loan.bump = ctx.bumps.loan;
@@ -390,8 +348,18 @@ pub fn create_loan_handler(
         sol_loan_amount
     )?;
 
-    // User exposure tracking removed for stack size optimization
-
+    let loan = &ctx.accounts.loan;
+    emit!(LoanCreated {
+        loan: loan.key(),
+        borrower: loan.borrower,
+        token_mint: loan.token_mint,
+        index: loan.index,
+        collateral_amount,
+        sol_borrowed: sol_loan_amount,
+        entry_price: loan.entry_price,
+        liquidation_price: loan.liquidation_price,
+        due_at: loan.due_at,
+    });
     
     // FIX 1: Exit reentrancy guard
     ReentrancyGuard::exit(protocol_state);
@@ -0,0 +1,273 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::Mint;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct ExtendLoan<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = !protocol_state.paused @ LendingError::ProtocolPaused
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_CONFIG_SEED, loan.token_mint.as_ref()],
+        bump = token_config.bump,
+        constraint = token_config.enabled @ LendingError::TokenDisabled,
+        constraint = !token_config.blacklisted @ LendingError::TokenBlacklisted
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    /// Global risk limits (token overrides applied on top)
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::Active @ LendingError::LoanAlreadyRepaid,
+        constraint = loan.borrower == borrower.key() @ LendingError::Unauthorized
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(constraint = token_mint.key() == loan.token_mint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Recorded pool prices - the approved price is sanity checked against their TWAP
+    #[account(
+        seeds = [PRICE_HISTORY_SEED, loan.token_mint.as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Operations wallet receives 25% of the extension fee
+    #[account(
+        mut,
+        constraint = operations_wallet.key() == protocol_state.operations_wallet @ LendingError::Unauthorized
+    )]
+    pub operations_wallet: AccountInfo<'info>,
+
+    /// CHECK: Staking reward vault receives 25% of the extension fee
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED],
+        bump
+    )]
+    pub staking_reward_vault: AccountInfo<'info>,
+
+    /// Insurance fund - receives its slice of the loan fee; required while the insurance fee is on
+    #[account(
+        mut,
+        seeds = [INSURANCE_FUND_SEED],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    /// Price authority co-signing the price used for the extension
+    /// Omit to approve the price with an Ed25519 attestation instead
+    #[account(
+        constraint = price_authority.key() == protocol_state.price_authority @ LendingError::InvalidPriceAuthority
+    )]
+    pub price_authority: Option<Signer<'info>>,
+
+    /// Instructions sysvar - required when price_authority does not co-sign
+    /// CHECK: Address checked against the instructions sysvar id
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// M-of-N price authority set - the loan price is the median of its signers' Ed25519
+    /// attestations. Required once the set exists; before that the single price authority applies
+    #[account(
+        seeds = [PRICE_AUTHORITY_SET_SEED],
+        bump = price_authority_set.bump
+    )]
+    pub price_authority_set: Option<Box<Account<'info, PriceAuthoritySet>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Extend an active loan's due date before it expires
+/// Charges the flat fee pro-rata to the extension and re-checks collateral
+/// against the duration-adjusted LTV for the new total duration
+pub fn extend_loan_handler(
+    ctx: Context<ExtendLoan>,
+    additional_seconds: u64,
+    approved_price: u64,
+    price_timestamp: i64,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+
+    ReentrancyGuard::enter(protocol_state)?;
+
+    require!(additional_seconds > 0, LendingError::InvalidLoanDuration);
+
+    let loan = &ctx.accounts.loan;
+
+    // Extensions must happen before the loan becomes liquidatable by time
+    require!(
+        !ValidationUtils::is_loan_liquidatable_by_time(loan, protocol_state, clock.unix_timestamp),
+        LendingError::LoanExpired
+    );
+
+    // New total duration must respect MAX_LOAN_DURATION
+    let current_duration = SafeMath::sub(loan.due_at as u64, loan.created_at as u64)?;
+    let new_duration = SafeMath::add(current_duration, additional_seconds)?;
+    // Fee base stays the opened term however many extensions came before
+    let original_duration = loan.original_term();
+    require!(new_duration <= MAX_LOAN_DURATION, LendingError::DurationTooLong);
+
+    let approved_price = PriceAttestationUtils::resolve_approved_price(
+        protocol_state.price_quorum_required,
+        ctx.accounts.price_authority_set.as_deref().map(|s| &**s),
+        ctx.accounts.price_authority.is_some(),
+        ctx.accounts.instructions_sysvar.as_ref().map(|s| s.as_ref()),
+        &protocol_state.price_authority,
+        PriceAction::ExtendLoan,
+        &loan.token_mint,
+        &loan.borrower,
+        approved_price,
+        price_timestamp,
+        clock.unix_timestamp,
+    )?;
+
+    // Same sanity check as create_loan: the approved price must sit near the pool TWAP
+    let pool_price = PriceFeedUtils::calculate_twap(&ctx.accounts.price_history, clock.unix_timestamp)?;
+    let risk = ctx.accounts.risk_config.for_token(&ctx.accounts.token_config);
+    ValidationUtils::validate_price_deviation(approved_price, pool_price, risk.max_price_deviation_bps)?;
+
+    // Longer duration = lower LTV, so the loan must still be covered at the new LTV
+    let effective_ltv = LoanCalculator::calculate_duration_adjusted_ltv(
+        ctx.accounts.token_config.ltv_bps,
+        new_duration,
+    )?;
+    let max_borrowable = LoanCalculator::calculate_loan_amount(
+        loan.collateral_amount,
+        approved_price,
+        effective_ltv,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    require!(
+        loan.sol_borrowed <= max_borrowable,
+        LendingError::InsufficientCollateral
+    );
+
+    // Pro-rata fee: flat fee scaled by extension length relative to the original term
+    let protocol_fee = SafeMath::mul_div(
+        SafeMath::mul_div(loan.sol_borrowed, PROTOCOL_FEE_BPS as u64, BPS_DIVISOR)?,
+        additional_seconds,
+        original_duration,
+    )?;
+
+    require!(
+        ctx.accounts.borrower.lamports() >= protocol_fee,
+        LendingError::InsufficientTreasuryBalance
+    );
+
+    let (treasury_fee_with_dust, staking_fee, operations_fee) =
+        LoanCalculator::split_loan_fee(protocol_fee)?;
+    let (treasury_fee_with_dust, insurance_fee) = LoanCalculator::split_insurance_fee(
+        protocol_fee,
+        treasury_fee_with_dust,
+        protocol_state.insurance_fee_bps,
+    )?;
+
+    // Update loan BEFORE transfers
+    let loan = &mut ctx.accounts.loan;
+    LoanCalculator::upgrade_loan_prices(
+        loan,
+        ctx.accounts.token_config.ltv_bps,
+        risk.liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    // Pin a legacy loan's term before due_at moves
+    loan.original_duration = original_duration as i64;
+    loan.due_at = loan.due_at
+        .checked_add(additional_seconds as i64)
+        .ok_or(LendingError::MathOverflow)?;
+
+    // === DISTRIBUTE FEE: Treasury (with dust) ===
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.borrower.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        ),
+        treasury_fee_with_dust,
+    )?;
+
+    // === DISTRIBUTE FEE: Staking ===
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.borrower.to_account_info(),
+                to: ctx.accounts.staking_reward_vault.to_account_info(),
+            },
+        ),
+        staking_fee,
+    )?;
+
+    // === DISTRIBUTE FEE: Operations ===
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.borrower.to_account_info(),
+                to: ctx.accounts.operations_wallet.to_account_info(),
+            },
+        ),
+        operations_fee,
+    )?;
+
+    // === DISTRIBUTE FEE: Insurance fund (carved from the treasury share) ===
+    InsuranceFundUtils::deposit(
+        ctx.accounts.insurance_fund.as_deref_mut(),
+        ctx.accounts.borrower.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        insurance_fee,
+    )?;
+
+    // Extension fee only - no principal comes back
+    RevenueTracker::record_repayment(protocol_state, &mut ctx.accounts.token_config, 0, protocol_fee)?;
+
+    emit!(LoanExtended {
+        loan: ctx.accounts.loan.key(),
+        borrower: ctx.accounts.borrower.key(),
+        additional_seconds,
+        extension_fee: protocol_fee,
+        due_at: ctx.accounts.loan.due_at,
+    });
+
+    ReentrancyGuard::exit(protocol_state);
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 use crate::utils::SafeMath;
 
 #[derive(Accounts)]
@@ -149,6 +150,12 @@ pub fn distribute_creator_fees_handler(ctx: Context<DistributeCreatorFees>) -> R
         distributable,
     )?;
     
+    emit!(CreatorFeesDistributed {
+        total: distributable,
+        treasury_amount,
+        staking_amount,
+        operations_amount,
+    });
     
     Ok(())
 }
\ No newline at end of file
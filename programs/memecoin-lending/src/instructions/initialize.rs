@@ -71,6 +71,7 @@ pub fn initialize_handler(
     protocol_state.treasury_fee_bps = 9000; // 90%
     protocol_state.buyback_fee_bps = 500; // 5%
     protocol_state.operations_fee_bps = 500; // 5%
+    protocol_state.liquidation_penalty_bps = DEFAULT_LIQUIDATION_PENALTY_BPS; // 5%
     protocol_state.authorized_liquidator = authorized_liquidator;
     protocol_state.price_authority = price_authority;
     protocol_state.bump = ctx.bumps.protocol_state;
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::InsuranceFundUtils;
+
+#[derive(Accounts)]
+pub struct InitializeInsuranceFund<'info> {
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = InsuranceFund::LEN,
+        seeds = [INSURANCE_FUND_SEED],
+        bump
+    )]
+    pub insurance_fund: Box<Account<'info, InsuranceFund>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundInsuranceFund<'info> {
+    #[account(
+        mut,
+        seeds = [INSURANCE_FUND_SEED],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Box<Account<'info, InsuranceFund>>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the insurance fund (admin only)
+pub fn initialize_insurance_fund_handler(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+    let insurance_fund = &mut ctx.accounts.insurance_fund;
+    insurance_fund.total_deposited = 0;
+    insurance_fund.total_paid_out = 0;
+    insurance_fund.bump = ctx.bumps.insurance_fund;
+    insurance_fund._reserved = [0u8; 32];
+    Ok(())
+}
+
+/// Top up the insurance fund (anyone)
+pub fn fund_insurance_fund_handler(ctx: Context<FundInsuranceFund>, amount: u64) -> Result<()> {
+    require!(amount > 0, LendingError::InvalidLoanAmount);
+
+    InsuranceFundUtils::deposit(
+        Some(&mut ctx.accounts.insurance_fund),
+        ctx.accounts.funder.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        amount,
+    )
+}
+
+/// Set the share of each loan fee routed to the insurance fund - queued through
+/// propose_change / execute_change; the fund must exist before fees can be routed to it
+pub fn apply_insurance_fee_update(
+    protocol_state: &mut ProtocolState,
+    insurance_fund: Option<&InsuranceFund>,
+    insurance_fee_bps: u16,
+) -> Result<()> {
+    require!(insurance_fee_bps <= MAX_INSURANCE_FEE_BPS, LendingError::InsuranceFeeTooHigh);
+    require!(
+        insurance_fee_bps == 0 || insurance_fund.is_some(),
+        LendingError::MissingInsuranceFund
+    );
+
+    protocol_state.insurance_fee_bps = insurance_fee_bps;
+
+    emit!(InsuranceFeeUpdated { insurance_fee_bps });
+
+    Ok(())
+}
@@ -1,16 +1,19 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token_interface::{self, CloseAccount, TokenAccount, Mint, TokenInterface};
 use anchor_spl::associated_token::AssociatedToken;
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 use crate::utils::*;
 use crate::swap::jupiter::execute_jupiter_swap;
+use crate::swap::pumpfun::{execute_pumpfun_sell, validate_pumpfun_sell_accounts};
+use crate::swap::pumpswap::{
+    execute_pumpswap_sell, validate_pumpswap_sell_accounts,
+    SELL_QUOTE_TOKEN_PROGRAM_INDEX, SELL_USER_QUOTE_TOKEN_INDEX,
+};
 use anchor_lang::solana_program::pubkey;
 
-/// Fee split constants
-const OPERATIONS_SPLIT_BPS: u64 = 500; // 5%
-const BPS_DENOMINATOR: u64 = 10000;
-
 /// Jupiter V6 Program ID
 pub const JUPITER_V6_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 
@@ -31,6 +34,13 @@ pub struct Liquidate<'info> {
     )]
     pub token_config: Account<'info, TokenConfig>,
 
+    /// Global risk limits (token overrides applied on top)
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
     #[account(
         mut,
         seeds = [
@@ -44,7 +54,7 @@ pub struct Liquidate<'info> {
     )]
     pub loan: Account<'info, Loan>,
 
-    /// Protocol treasury - receives 95% of proceeds
+    /// Protocol treasury - receives the principal and 95% of the debt recovered above it
     #[account(
         mut,
         seeds = [TREASURY_SEED],
@@ -52,13 +62,20 @@ pub struct Liquidate<'info> {
     )]
     pub treasury: SystemAccount<'info>,
 
-    /// Operations wallet - receives 5% of proceeds
+    /// Operations wallet - receives 5% of the debt recovered above principal
     #[account(
         mut,
         constraint = operations_wallet.key() == protocol_state.operations_wallet @ LendingError::Unauthorized
     )]
     pub operations_wallet: SystemAccount<'info>,
 
+    /// Borrower - receives any surplus proceeds above the debt
+    #[account(
+        mut,
+        address = loan.borrower @ LendingError::InvalidBorrower
+    )]
+    pub borrower: SystemAccount<'info>,
+
     /// Vault token account holding collateral
     #[account(
         mut,
@@ -79,44 +96,59 @@ pub struct Liquidate<'info> {
     #[account(constraint = token_mint.key() == loan.token_mint)]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK: Pool account for price verification
-    #[account(constraint = pool_account.key() == token_config.pool_address @ LendingError::InvalidPoolAddress)]
-    pub pool_account: UncheckedAccount<'info>,
-
-    /// PumpSwap base token vault - required when pool_type is PumpSwap
-    /// CHECK: Validated in handler against pool data
-    pub pumpswap_base_vault: Option<UncheckedAccount<'info>>,
+    /// Recorded pool prices - liquidation decisions use their TWAP
+    #[account(
+        seeds = [PRICE_HISTORY_SEED, loan.token_mint.as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
 
-    /// PumpSwap quote token vault (WSOL) - required when pool_type is PumpSwap
-    /// CHECK: Validated in handler against pool data  
-    pub pumpswap_quote_vault: Option<UncheckedAccount<'info>>,
+    /// Insurance fund - covers shortfalls; required while the insurance fee is on
+    #[account(
+        mut,
+        seeds = [INSURANCE_FUND_SEED],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
 
 
 
 
     // === Common Accounts ===
 
-    /// Payer for transaction fees - MUST be authorized liquidator
+    /// Per-borrower exposure tracking (created on first use)
     #[account(
-        mut,
-        constraint = payer.key() == protocol_state.authorized_liquidator @ LendingError::UnauthorizedLiquidator
+        init_if_needed,
+        payer = payer,
+        space = UserExposure::LEN,
+        seeds = [USER_EXPOSURE_SEED, loan.borrower.as_ref()],
+        bump
     )]
+    pub user_exposure: Box<Account<'info, UserExposure>>,
+
+    /// Liquidator - the authorized liquidator, or any signer once permissionless
+    /// liquidation is on and the exclusivity window has passed (receives the keeper bonus)
+    #[account(mut)]
     pub payer: Signer<'info>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     
-    // Remaining accounts: Jupiter route accounts (when using Jupiter)
+    // Remaining accounts: Jupiter route accounts (when using Jupiter),
+    // [pumpswap_program, sell accounts...] for a native PumpSwap sell, or
+    // [pumpfun_program, sell accounts...] for a bonding curve sell
 }
 
 pub fn liquidate_handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, Liquidate<'info>>,
     min_sol_output: u64,
     jupiter_swap_data: Option<Vec<u8>>,
+    partial: bool,
 ) -> Result<()> {
     let protocol_state = &mut ctx.accounts.protocol_state;
     let token_config = &mut ctx.accounts.token_config;
+    let risk = ctx.accounts.risk_config.for_token(token_config);
     let clock = Clock::get()?;
 
     // FIX 1: Reentrancy guard
@@ -124,91 +156,52 @@ pub fn liquidate_handler<'info>(
 
     // Extract values before mutable borrow
     let loan_key = ctx.accounts.loan.key();
-    let token_mint_key = ctx.accounts.loan.token_mint;
     
     let loan = &mut ctx.accounts.loan;
-
-    // Validate PumpSwap pool if pool_type is PumpSwap
-    if token_config.pool_type == PoolType::PumpSwap {
-        let (validated_base_vault, validated_quote_vault) = PumpSwapPoolValidator::validate_full(
-            &ctx.accounts.pool_account,
-            &token_mint_key,
-        )?;
-        
-        // Verify the vault accounts passed match the ones in the pool
-        let base_vault_info = ctx.accounts.pumpswap_base_vault
-            .as_ref()
-            .ok_or(LendingError::MissingPumpSwapVaults)?;
-        let quote_vault_info = ctx.accounts.pumpswap_quote_vault
-            .as_ref()
-            .ok_or(LendingError::MissingPumpSwapVaults)?;
-        
-        require!(
-            base_vault_info.key == &validated_base_vault,
-            LendingError::InvalidPumpSwapVault
-        );
-        require!(
-            quote_vault_info.key == &validated_quote_vault,
-            LendingError::InvalidPumpSwapVault
-        );
-        
-        msg!("PumpSwap pool validated: base_mint and quote_mint verified");
-    }
+    LoanCalculator::upgrade_loan_prices(
+        loan,
+        token_config.ltv_bps,
+        risk.liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
 
     // === Step 1: Verify loan is liquidatable ===
     
-    let pool_data = ctx.accounts.pool_account.try_borrow_data()?;
-
-    let current_price = match token_config.pool_type {
-        PoolType::PumpSwap => {
-            // PumpSwap requires vault accounts to read balances
-            let base_vault_info = ctx.accounts.pumpswap_base_vault
-                .as_ref()
-                .ok_or(LendingError::MissingPumpSwapVaults)?;
-            let quote_vault_info = ctx.accounts.pumpswap_quote_vault
-                .as_ref()
-                .ok_or(LendingError::MissingPumpSwapVaults)?;
-            
-            // Parse vault accounts as token accounts to get balances
-            let base_vault_data = base_vault_info.try_borrow_data()?;
-            let quote_vault_data = quote_vault_info.try_borrow_data()?;
-            
-            // Token account amount is at offset 64 (after mint, owner, amount)
-            let base_amount = u64::from_le_bytes(
-                base_vault_data[64..72].try_into().map_err(|_| LendingError::InvalidPriceFeed)?
-            );
-            let quote_amount = u64::from_le_bytes(
-                quote_vault_data[64..72].try_into().map_err(|_| LendingError::InvalidPriceFeed)?
-            );
-            
-            PriceFeedUtils::read_pumpswap_price(
-                &pool_data,
-                base_amount,
-                quote_amount,
-                base_vault_info.key,
-                quote_vault_info.key,
-            )?
-        },
-        _ => {
-            // For Raydium/Orca/PumpFun, read directly from pool
-            PriceFeedUtils::read_price_from_pool(
-                &ctx.accounts.pool_account,
-                token_config.pool_type,
-                &token_mint_key,
-            )?
-        }
-    };
-
-    drop(pool_data); // Release borrow before continuing
-
-    let liquidatable_by_time = ValidationUtils::is_loan_liquidatable_by_time(loan, clock.unix_timestamp);
-    let liquidatable_by_price = ValidationUtils::is_loan_liquidatable_by_price(loan, current_price);
+    let liquidatable_by_time = ValidationUtils::is_loan_liquidatable_by_time(loan, protocol_state, clock.unix_timestamp);
+
+    // TWAP instead of spot reserves, which can be moved inside a single transaction.
+    // Only the price check needs it - an expired loan is not held up by a thin window
+    let current_price = PriceFeedUtils::liquidation_twap(
+        &ctx.accounts.price_history,
+        clock.unix_timestamp,
+        liquidatable_by_time,
+    )?;
+    let liquidatable_by_price = current_price
+        .is_some_and(|price| ValidationUtils::is_loan_liquidatable_by_price(loan, price));
 
     require!(
         liquidatable_by_time || liquidatable_by_price,
         LendingError::LoanNotLiquidatable
     );
 
+    // Partial liquidation only restores health on price - expired loans must be closed out
+    if partial {
+        require!(
+            liquidatable_by_price && !liquidatable_by_time,
+            LendingError::PartialLiquidationNotAllowed
+        );
+    }
+
+    // Permissionless liquidators wait out the authorized liquidator's exclusivity window
+    let is_keeper = ValidationUtils::validate_liquidator(
+        protocol_state,
+        loan,
+        &ctx.accounts.payer.key(),
+        liquidatable_by_time,
+        liquidatable_by_price,
+        clock.unix_timestamp,
+    )?;
+
     let liquidation_reason = if liquidatable_by_price {
         LoanStatus::LiquidatedPrice
     } else {
@@ -218,29 +211,52 @@ pub fn liquidate_handler<'info>(
     // Store values
     let collateral_amount = loan.collateral_amount;
     let sol_borrowed = loan.sol_borrowed;
+    let liquidation_penalty_bps = protocol_state.liquidation_penalty_bps;
+    let vault_balance_before = ctx.accounts.vault_token_account.amount;
+    let token_decimals = ctx.accounts.token_mint.decimals;
+
+    // Partial mode may only sell enough collateral to reach the target health factor
+    let max_collateral_to_sell = if partial {
+        LoanCalculator::calculate_partial_liquidation_collateral(
+            collateral_amount,
+            sol_borrowed,
+            current_price.ok_or(LendingError::InsufficientTwapSamples)?,
+            LoanCalculator::calculate_liquidation_ltv(token_config.ltv_bps, risk.liquidation_buffer_bps)?,
+            liquidation_penalty_bps,
+            PARTIAL_LIQUIDATION_TARGET_HEALTH_BPS,
+            token_decimals,
+        )?
+    } else {
+        collateral_amount
+    };
 
     // FIX 9: Add on-chain minimum slippage validation to prevent malicious liquidators
-    let expected_sol_value = SafeMath::mul_div(
-        collateral_amount,
-        current_price,
-        PRICE_SCALE as u64,
-    )?;
-
-    // Minimum output must be at least (100% - MAX_SLIPPAGE)% of expected value
-    let min_acceptable_output = SafeMath::mul_div(
-        expected_sol_value,
-        BPS_DIVISOR - MAX_LIQUIDATION_SLIPPAGE_BPS,
-        BPS_DIVISOR,
-    )?;
+    // In partial mode this is checked after the swap against the collateral actually sold.
+    // An expired loan without a TWAP has no reference value, so only min_sol_output applies
+    if !partial {
+        if let Some(current_price) = current_price {
+            let expected_sol_value = LoanCalculator::calculate_collateral_value(
+                collateral_amount,
+                current_price,
+                token_decimals,
+            )?;
 
-    require!(
-        min_sol_output >= min_acceptable_output,
-        LendingError::SlippageTooHigh
-    );
+            // Minimum output must be at least (100% - MAX_SLIPPAGE)% of expected value
+            let min_acceptable_output = SafeMath::mul_div(
+                expected_sol_value,
+                BPS_DIVISOR - risk.max_liquidation_slippage_bps as u64,
+                BPS_DIVISOR,
+            )?;
 
+            require!(
+                min_sol_output >= min_acceptable_output,
+                LendingError::SlippageTooHigh
+            );
+        }
 
-    // Update loan status
-    loan.status = liquidation_reason;
+        // Update loan status
+        loan.status = liquidation_reason;
+    }
 
     // === Step 2: Build vault signer seeds ===
     
@@ -258,11 +274,104 @@ pub fn liquidate_handler<'info>(
 
     match token_config.pool_type {
         PoolType::Pumpfun => {
-            // PumpFun tokens not supported for liquidation - tokens must migrate first
-            return Err(LendingError::FeatureTemporarilyDisabled.into());
+            // Native bonding curve sell - SOL is paid straight to the vault authority
+            // Once the curve completes, sync_pumpfun_migration re-routes the token to PumpSwap
+            let (pumpfun_program, sell_accounts) = ctx.remaining_accounts
+                .split_first()
+                .ok_or(LendingError::MissingPumpfunAccounts)?;
+
+            let expected_output = validate_pumpfun_sell_accounts(
+                pumpfun_program,
+                sell_accounts,
+                &token_config.pool_address,
+                &token_config.mint,
+                &ctx.accounts.vault_authority.key(),
+                &ctx.accounts.vault_token_account.key(),
+                max_collateral_to_sell,
+            )?;
+            require!(expected_output >= min_sol_output, LendingError::SlippageExceeded);
+
+            execute_pumpfun_sell(
+                pumpfun_program,
+                sell_accounts,
+                max_collateral_to_sell,
+                min_sol_output,
+                vault_signer,
+            )?;
+        },
+
+        PoolType::PumpSwap if jupiter_swap_data.is_none() => {
+            // Native PumpSwap sell - no off-chain route needed
+            let (pumpswap_program, sell_accounts) = ctx.remaining_accounts
+                .split_first()
+                .ok_or(LendingError::MissingPumpSwapVaults)?;
+
+            let expected_output = validate_pumpswap_sell_accounts(
+                pumpswap_program,
+                sell_accounts,
+                &token_config.pool_address,
+                &token_config.mint,
+                &ctx.accounts.vault_authority.key(),
+                &ctx.accounts.vault_token_account.key(),
+                max_collateral_to_sell,
+            )?;
+            require!(expected_output >= min_sol_output, LendingError::SlippageExceeded);
+
+            let wsol_account = &sell_accounts[SELL_USER_QUOTE_TOKEN_INDEX];
+            let wsol_before = PriceFeedUtils::read_token_account_amount(wsol_account)?;
+
+            execute_pumpswap_sell(
+                pumpswap_program,
+                sell_accounts,
+                max_collateral_to_sell,
+                min_sol_output,
+                vault_signer,
+            )?;
+
+            let wsol_received = SafeMath::sub(
+                PriceFeedUtils::read_token_account_amount(wsol_account)?,
+                wsol_before,
+            )?;
+
+            // Unwrap proceeds into the vault authority; rent and any prior balance go back to the payer
+            let refund = SafeMath::sub(wsol_account.lamports(), wsol_received)?;
+            let quote_token_program = &sell_accounts[SELL_QUOTE_TOKEN_PROGRAM_INDEX];
+            require!(
+                quote_token_program.key() == anchor_spl::token::ID,
+                LendingError::InvalidTokenProgram
+            );
+            token_interface::close_account(CpiContext::new_with_signer(
+                quote_token_program.clone(),
+                CloseAccount {
+                    account: wsol_account.clone(),
+                    destination: ctx.accounts.vault_authority.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                vault_signer,
+            ))?;
+
+            if refund > 0 {
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.vault_authority.to_account_info(),
+                            to: ctx.accounts.payer.to_account_info(),
+                        },
+                        vault_signer,
+                    ),
+                    refund,
+                )?;
+            }
         },
 
-        PoolType::Raydium | PoolType::Orca | PoolType::PumpSwap => {
+        PoolType::Raydium
+        | PoolType::Orca
+        | PoolType::PumpSwap
+        | PoolType::RaydiumCpmm
+        | PoolType::RaydiumClmm
+        | PoolType::MeteoraDlmm
+        | PoolType::MeteoraDammV2 => {
             // Jupiter swap accounts provided via remaining_accounts
             
             let swap_data = jupiter_swap_data
@@ -305,54 +414,169 @@ pub fn liquidate_handler<'info>(
     // Verify minimum output
     require!(sol_proceeds >= min_sol_output, LendingError::SlippageExceeded);
 
-    // Calculate split
-    let operations_share = sol_proceeds
-        .checked_mul(OPERATIONS_SPLIT_BPS)
-        .ok_or(LendingError::MathOverflow)?
-        .checked_div(BPS_DENOMINATOR)
-        .ok_or(LendingError::DivisionByZero)?;
-    
-    let treasury_share = sol_proceeds
-        .checked_sub(operations_share)
-        .ok_or(LendingError::MathUnderflow)?;
+    let mut collateral_sold = collateral_amount;
+    if partial {
+        ctx.accounts.vault_token_account.reload()?;
+        collateral_sold = SafeMath::sub(vault_balance_before, ctx.accounts.vault_token_account.amount)?;
 
-    // Transfer SOL to treasury and operations wallet
+        require!(
+            collateral_sold > 0 && collateral_sold <= max_collateral_to_sell,
+            LendingError::PartialLiquidationTooLarge
+        );
+
+        // Slippage check against the collateral actually sold
+        let sold_value = LoanCalculator::calculate_collateral_value(
+            collateral_sold,
+            current_price.ok_or(LendingError::InsufficientTwapSamples)?,
+            token_decimals,
+        )?;
+        let min_acceptable_output = SafeMath::mul_div(
+            sold_value,
+            BPS_DIVISOR - risk.max_liquidation_slippage_bps as u64,
+            BPS_DIVISOR,
+        )?;
+        require!(
+            sol_proceeds >= min_acceptable_output,
+            LendingError::SlippageTooHigh
+        );
+    }
+
+    // Full: debt = principal + protocol fee + liquidation penalty, anything above belongs to the borrower
+    // Partial: all proceeds pay down debt, principal is reduced by its pro-rata share
+    let (debt_recovered, principal_repaid) = if partial {
+        let principal_repaid = LoanCalculator::calculate_principal_repaid(
+            sol_proceeds,
+            liquidation_penalty_bps,
+        )?;
+        require!(principal_repaid < sol_borrowed, LendingError::PartialLiquidationTooLarge);
+        (sol_proceeds, principal_repaid)
+    } else {
+        let debt = LoanCalculator::calculate_liquidation_debt(
+            sol_borrowed,
+            liquidation_penalty_bps,
+        )?;
+        (std::cmp::min(sol_proceeds, debt), sol_borrowed)
+    };
+    let surplus = SafeMath::sub(sol_proceeds, debt_recovered)?;
+
+    // Permissionless liquidators are paid out of the recovered penalty, never out of principal
+    let keeper_bonus = if is_keeper {
+        LoanCalculator::calculate_keeper_bonus(
+            sol_proceeds,
+            debt_recovered,
+            principal_repaid,
+            liquidation_penalty_bps,
+            protocol_state.keeper_bonus_bps,
+        )?
+    } else {
+        0
+    };
+    let protocol_recovered = SafeMath::sub(debt_recovered, keeper_bonus)?;
+
+    // Calculate split of recovered debt - a full liquidation below principal leaves a shortfall
+    let (treasury_share, operations_share, shortfall) =
+        LoanCalculator::split_liquidation_proceeds(protocol_recovered, principal_repaid)?;
+
+    // Transfer SOL to treasury, operations wallet, borrower (surplus) and keeper (bonus)
     **ctx.accounts.vault_authority.to_account_info().try_borrow_mut_lamports()? -= sol_proceeds;
     **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += treasury_share;
     **ctx.accounts.operations_wallet.to_account_info().try_borrow_mut_lamports()? += operations_share;
+    if surplus > 0 {
+        **ctx.accounts.borrower.to_account_info().try_borrow_mut_lamports()? += surplus;
+    }
+    if keeper_bonus > 0 {
+        **ctx.accounts.payer.to_account_info().try_borrow_mut_lamports()? += keeper_bonus;
+    }
 
-    // === Step 5: Close vault token account ===
-    
-    let close_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        CloseAccount {
-            account: ctx.accounts.vault_token_account.to_account_info(),
-            destination: ctx.accounts.payer.to_account_info(),
-            authority: ctx.accounts.vault_authority.to_account_info(),
-        },
-        vault_signer,
-    );
-    token_interface::close_account(close_ctx)?;
+    if partial {
+        // === Step 5 (partial): Shrink loan and refresh liquidation price ===
+
+        let loan = &mut ctx.accounts.loan;
+        loan.collateral_amount = SafeMath::sub(collateral_amount, collateral_sold)?;
+        loan.sol_borrowed = SafeMath::sub(sol_borrowed, principal_repaid)?;
+        loan.liquidation_price = LoanCalculator::calculate_liquidation_price(
+            loan.sol_borrowed,
+            loan.collateral_amount,
+            token_config.ltv_bps,
+            risk.liquidation_buffer_bps,
+            token_decimals,
+        )?;
+
+        // Loan stays Active, so it must no longer be liquidatable at the current price
+        require!(
+            !ValidationUtils::is_loan_liquidatable_by_price(
+                loan,
+                current_price.ok_or(LendingError::InsufficientTwapSamples)?,
+            ),
+            LendingError::HealthNotRestored
+        );
+        loan.liquidatable_since = 0;
+    } else {
+        // === Step 5: Close vault token account ===
+        
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.vault_token_account.to_account_info(),
+                destination: ctx.accounts.payer.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            vault_signer,
+        );
+        token_interface::close_account(close_ctx)?;
+
+        protocol_state.active_loans_count = SafeMath::sub(protocol_state.active_loans_count, 1)?;
+        token_config.active_loans_count = SafeMath::sub(token_config.active_loans_count, 1)?;
+    }
 
     // === Step 6: Update protocol state ===
+
+    // Book the shortfall as bad debt and let the insurance fund make the treasury whole
+    InsuranceFundUtils::settle_shortfall(
+        protocol_state,
+        token_config,
+        ctx.accounts.insurance_fund.as_deref_mut(),
+        &ctx.accounts.treasury.to_account_info(),
+        loan_key,
+        shortfall,
+    )?;
     
-    protocol_state.total_sol_borrowed = SafeMath::sub(protocol_state.total_sol_borrowed, sol_borrowed)?;
-    protocol_state.active_loans_count = SafeMath::sub(protocol_state.active_loans_count, 1)?;
-    protocol_state.total_fees_earned = SafeMath::add(protocol_state.total_fees_earned, treasury_share)?;
-    
-    token_config.active_loans_count = SafeMath::sub(token_config.active_loans_count, 1)?;
+    protocol_state.total_sol_borrowed = SafeMath::sub(protocol_state.total_sol_borrowed, principal_repaid)?;
+    RevenueTracker::record_liquidation(protocol_state, token_config, protocol_recovered, principal_repaid)?;
     
     // Update token exposure tracking - decrement borrowed amount  
     token_config.total_active_borrowed = SafeMath::sub(
         token_config.total_active_borrowed,
-        sol_borrowed
+        principal_repaid
     )?;
 
-    // User exposure tracking removed for stack size optimization
+    UserExposureTracker::record_principal_closed(
+        &mut ctx.accounts.user_exposure,
+        ctx.accounts.borrower.key(),
+        ctx.bumps.user_exposure,
+        principal_repaid,
+        !partial,
+        true,
+    )?;
 
+    emit!(LoanLiquidated {
+        loan: loan_key,
+        borrower: ctx.accounts.borrower.key(),
+        token_mint: token_config.mint,
+        liquidator: ctx.accounts.payer.key(),
+        reason: liquidation_reason,
+        partial,
+        collateral_sold,
+        sol_proceeds,
+        principal_repaid,
+        treasury_share,
+        operations_share,
+        borrower_surplus: surplus,
+        keeper_bonus,
+    });
     
     // FIX 1: Exit reentrancy guard
     ReentrancyGuard::exit(protocol_state);
     
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct MarkLiquidatable<'info> {
+    #[account(
+        seeds = [TOKEN_CONFIG_SEED, loan.token_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    /// Global risk limits - a legacy loan's liquidation price is recomputed with them
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::Active @ LendingError::LoanAlreadyRepaid
+    )]
+    pub loan: Account<'info, Loan>,
+
+    #[account(constraint = token_mint.key() == loan.token_mint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Recorded pool prices - the mark uses their TWAP, same as liquidate
+    #[account(
+        seeds = [PRICE_HISTORY_SEED, loan.token_mint.as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+}
+
+/// Start the exclusivity window for an underwater loan, or clear a stale mark once it recovers
+/// Permissionless - the outcome depends only on the TWAP
+pub fn mark_liquidatable_handler(ctx: Context<MarkLiquidatable>) -> Result<()> {
+    let clock = Clock::get()?;
+    let loan = &mut ctx.accounts.loan;
+    let token_config = &ctx.accounts.token_config;
+    LoanCalculator::upgrade_loan_prices(
+        loan,
+        token_config.ltv_bps,
+        ctx.accounts.risk_config.for_token(token_config).liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let twap_price = PriceFeedUtils::calculate_twap(&ctx.accounts.price_history, clock.unix_timestamp)?;
+    let underwater = ValidationUtils::is_loan_liquidatable_by_price(loan, twap_price);
+
+    if underwater {
+        // Keep the first mark so the window is not restarted
+        if loan.liquidatable_since == 0 {
+            loan.liquidatable_since = clock.unix_timestamp;
+        }
+    } else {
+        require!(loan.liquidatable_since != 0, LendingError::LoanNotLiquidatable);
+        loan.liquidatable_since = 0;
+    }
+
+    emit!(LoanLiquidatableMarked {
+        loan: loan.key(),
+        token_mint: loan.token_mint,
+        twap_price,
+        liquidation_price: loan.liquidation_price,
+        liquidatable_since: loan.liquidatable_since,
+    });
+
+    Ok(())
+}
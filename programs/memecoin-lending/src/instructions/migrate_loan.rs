@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::utils::LoanCalculator;
+
+#[derive(Accounts)]
+pub struct MigrateLoan<'info> {
+    #[account(
+        seeds = [TOKEN_CONFIG_SEED, loan.token_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    /// Global risk limits (token overrides applied on top)
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::Active @ LendingError::LoanAlreadyRepaid
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(
+        constraint = token_mint.key() == loan.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+}
+
+/// Recompute a loan opened before canonical prices in lamports per whole token
+/// Permissionless - the result depends only on the loan's terms and the token's risk settings
+pub fn migrate_loan_handler(ctx: Context<MigrateLoan>) -> Result<()> {
+    let token_config = &ctx.accounts.token_config;
+    LoanCalculator::upgrade_loan_prices(
+        &mut ctx.accounts.loan,
+        token_config.ltv_bps,
+        ctx.accounts.risk_config.for_token(token_config).liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )
+}
@@ -1,9 +1,22 @@
 pub mod admin;
+pub mod add_collateral;
+pub mod auction;
 pub mod create_loan;
+pub mod extend_loan;
 pub mod fund_treasury;
 pub mod initialize;
+pub mod insurance_fund;
 pub mod liquidate;
+pub mod mark_liquidatable;
+pub mod migrate_loan;
+pub mod multisig;
+pub mod partial_repay;
+pub mod price_authority_set;
+pub mod record_price;
 pub mod repay_loan;
+pub mod risk_config;
+pub mod sync_pumpfun_migration;
+pub mod timelock;
 pub mod update_fees;
 pub mod update_token_config;
 pub mod whitelist_token;
@@ -11,11 +24,24 @@ pub mod staking;
 pub mod fee_distribution;
 
 pub use admin::*;
+pub use add_collateral::*;
+pub use auction::*;
 pub use create_loan::*;
+pub use extend_loan::*;
 pub use fund_treasury::*;
 pub use initialize::*;
+pub use insurance_fund::*;
 pub use liquidate::*;
+pub use mark_liquidatable::*;
+pub use migrate_loan::*;
+pub use multisig::*;
+pub use partial_repay::*;
+pub use price_authority_set::*;
+pub use record_price::*;
 pub use repay_loan::*;
+pub use risk_config::*;
+pub use sync_pumpfun_migration::*;
+pub use timelock::*;
 pub use update_fees::*;
 pub use update_token_config::*;
 pub use whitelist_token::*;
@@ -0,0 +1,388 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::instructions::admin::{
+    apply_admin_transfer_initiation, apply_emergency_drain, apply_price_authority_update,
+    apply_treasury_withdrawal,
+};
+use crate::instructions::price_authority_set::{apply_price_quorum_update, apply_price_signer_rotation};
+use crate::utils::SafeMath;
+
+#[derive(Accounts)]
+pub struct InitializeMultisig<'info> {
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Multisig::LEN,
+        seeds = [MULTISIG_SEED],
+        bump
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeMultisigAction<'info> {
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        mut,
+        seeds = [MULTISIG_SEED],
+        bump = multisig.bump
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = MultisigProposal::LEN,
+        seeds = [MULTISIG_PROPOSAL_SEED, &multisig.proposal_nonce.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Box<Account<'info, MultisigProposal>>,
+
+    /// Multisig signer, or the treasurer for treasury withdrawals
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMultisigAction<'info> {
+    #[account(
+        seeds = [MULTISIG_SEED],
+        bump = multisig.bump
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [MULTISIG_PROPOSAL_SEED, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Box<Account<'info, MultisigProposal>>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMultisigAction<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    /// Mutable for UpdateMultisig, the only action that targets the multisig itself
+    #[account(
+        mut,
+        seeds = [MULTISIG_SEED],
+        bump = multisig.bump
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [MULTISIG_PROPOSAL_SEED, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Box<Account<'info, MultisigProposal>>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(
+        mut,
+        address = proposal.proposed_by @ LendingError::Unauthorized
+    )]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// SOL destination of an EmergencyDrain or WithdrawTreasury action
+    /// CHECK: Must match the recipient fixed in the proposal
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+
+    /// Target of a RotatePriceSigner or UpdatePriceQuorum action
+    #[account(
+        mut,
+        seeds = [PRICE_AUTHORITY_SET_SEED],
+        bump = price_authority_set.bump
+    )]
+    pub price_authority_set: Option<Box<Account<'info, PriceAuthoritySet>>>,
+
+    /// Anyone may execute once the threshold is met - the action was fixed at proposal
+    pub executor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelMultisigAction<'info> {
+    #[account(
+        seeds = [MULTISIG_SEED],
+        bump = multisig.bump
+    )]
+    pub multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [MULTISIG_PROPOSAL_SEED, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Box<Account<'info, MultisigProposal>>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(
+        mut,
+        address = proposal.proposed_by @ LendingError::Unauthorized
+    )]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Proposer or any multisig signer
+    pub authority: Signer<'info>,
+}
+
+/// Create the multisig (admin only, once)
+pub fn initialize_multisig_handler(
+    ctx: Context<InitializeMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    set_multisig_signers(multisig, &signers, threshold)?;
+    multisig.bump = ctx.bumps.multisig;
+
+    Ok(())
+}
+
+/// Validate and store a signer set; unused slots are reset to the default key
+fn set_multisig_signers(multisig: &mut Multisig, signers: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+        LendingError::InvalidMultisigConfig
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        LendingError::InvalidMultisigConfig
+    );
+
+    for (i, signer) in signers.iter().enumerate() {
+        require!(*signer != Pubkey::default(), LendingError::InvalidMultisigConfig);
+        require!(!signers[..i].contains(signer), LendingError::InvalidMultisigConfig);
+    }
+    multisig.signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+    multisig.signers[..signers.len()].copy_from_slice(signers);
+    multisig.signer_count = signers.len() as u8;
+    multisig.threshold = threshold;
+
+    Ok(())
+}
+
+/// Replace the signer set and threshold; open proposals become stale
+pub fn apply_multisig_update(
+    multisig: &mut Multisig,
+    signers: &[Pubkey; MAX_MULTISIG_SIGNERS],
+    signer_count: u8,
+    threshold: u8,
+    now: i64,
+) -> Result<()> {
+    require!(
+        signer_count as usize <= MAX_MULTISIG_SIGNERS,
+        LendingError::InvalidMultisigConfig
+    );
+    set_multisig_signers(multisig, &signers[..signer_count as usize], threshold)?;
+    multisig.updated_at = now;
+
+    Ok(())
+}
+
+/// Open a proposal for a critical action; a proposing signer's approval is counted
+pub fn propose_multisig_action_handler(
+    ctx: Context<ProposeMultisigAction>,
+    action: MultisigAction,
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let proposer = ctx.accounts.proposer.key();
+    let clock = Clock::get()?;
+
+    let signer_index = multisig.signer_index(&proposer);
+    let is_treasurer_withdrawal = matches!(action, MultisigAction::WithdrawTreasury { .. })
+        && ctx.accounts.protocol_state.has_role(&proposer, Role::Treasurer);
+    require!(
+        signer_index.is_some() || is_treasurer_withdrawal,
+        LendingError::NotMultisigSigner
+    );
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.id = multisig.proposal_nonce;
+    proposal.action = action;
+    proposal.proposed_by = proposer;
+    proposal.proposed_at = clock.unix_timestamp;
+    proposal.execute_after = clock.unix_timestamp
+        .checked_add(action.delay())
+        .ok_or(LendingError::MathOverflow)?;
+    proposal.approvals = signer_index.map_or(0, |i| 1 << i);
+    proposal.bump = ctx.bumps.proposal;
+
+    multisig.proposal_nonce = SafeMath::add(multisig.proposal_nonce, 1)?;
+
+    emit!(MultisigActionProposed {
+        id: proposal.id,
+        action,
+        proposed_by: proposer,
+        execute_after: proposal.execute_after,
+    });
+
+    Ok(())
+}
+
+/// Record a signer's approval
+pub fn approve_multisig_action_handler(ctx: Context<ApproveMultisigAction>) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    let proposal = &mut ctx.accounts.proposal;
+    let signer = ctx.accounts.signer.key();
+
+    require!(
+        Clock::get()?.unix_timestamp <= proposal.expires_at(),
+        LendingError::ChangeExpired
+    );
+    require!(multisig.is_current(proposal), LendingError::MultisigProposalStale);
+
+    let index = multisig.signer_index(&signer).ok_or(LendingError::NotMultisigSigner)?;
+    let bit = 1u16 << index;
+    require!(proposal.approvals & bit == 0, LendingError::AlreadyApproved);
+    proposal.approvals |= bit;
+
+    emit!(MultisigActionApproved {
+        id: proposal.id,
+        signer,
+        approvals: proposal.approval_count(),
+        threshold: multisig.threshold,
+    });
+
+    Ok(())
+}
+
+/// Run an approved action once the threshold (and any timelock) is met
+pub fn execute_multisig_action_handler(ctx: Context<ExecuteMultisigAction>) -> Result<()> {
+    let clock = Clock::get()?;
+    let proposal = &ctx.accounts.proposal;
+
+    require!(ctx.accounts.multisig.is_current(proposal), LendingError::MultisigProposalStale);
+    require!(
+        proposal.approval_count() >= ctx.accounts.multisig.threshold,
+        LendingError::MultisigThresholdNotMet
+    );
+    require!(clock.unix_timestamp >= proposal.execute_after, LendingError::ChangeNotReady);
+    require!(clock.unix_timestamp <= proposal.expires_at(), LendingError::ChangeExpired);
+
+    let id = proposal.id;
+    let action = proposal.action;
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let treasury = ctx.accounts.treasury.to_account_info();
+    let treasury_bump = ctx.bumps.treasury;
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    match action {
+        MultisigAction::EmergencyDrain { recipient } => {
+            let recipient_info = ctx.accounts.recipient.as_ref()
+                .filter(|r| r.key() == recipient)
+                .ok_or(LendingError::ChangeTargetMismatch)?;
+            apply_emergency_drain(
+                protocol_state,
+                &treasury,
+                treasury_bump,
+                recipient_info.as_ref(),
+                &system_program,
+            )?
+        },
+        MultisigAction::WithdrawTreasury { amount, recipient } => {
+            let recipient_info = ctx.accounts.recipient.as_ref()
+                .filter(|r| r.key() == recipient)
+                .ok_or(LendingError::ChangeTargetMismatch)?;
+            apply_treasury_withdrawal(
+                protocol_state,
+                &treasury,
+                treasury_bump,
+                recipient_info.as_ref(),
+                &system_program,
+                amount,
+            )?
+        },
+        MultisigAction::InitiateAdminTransfer { new_admin } => {
+            apply_admin_transfer_initiation(protocol_state, new_admin)?
+        },
+        MultisigAction::UpdatePriceAuthority { new_price_authority } => {
+            apply_price_authority_update(protocol_state, new_price_authority)?
+        },
+        MultisigAction::RotatePriceSigner { old_signer, new_signer } => {
+            let price_authority_set = ctx.accounts.price_authority_set.as_deref_mut()
+                .ok_or(LendingError::ChangeTargetMismatch)?;
+            apply_price_signer_rotation(price_authority_set, old_signer, new_signer)?
+        },
+        MultisigAction::UpdatePriceQuorum { threshold, max_deviation_bps } => {
+            let price_authority_set = ctx.accounts.price_authority_set.as_deref_mut()
+                .ok_or(LendingError::ChangeTargetMismatch)?;
+            apply_price_quorum_update(price_authority_set, threshold, max_deviation_bps)?
+        },
+        MultisigAction::UpdateMultisig { signers, signer_count, threshold } => {
+            apply_multisig_update(
+                &mut ctx.accounts.multisig,
+                &signers,
+                signer_count,
+                threshold,
+                clock.unix_timestamp,
+            )?
+        },
+    }
+
+    emit!(MultisigActionExecuted {
+        id,
+        action,
+        executed_by: ctx.accounts.executor.key(),
+    });
+
+    Ok(())
+}
+
+/// Drop a proposal (proposer or any multisig signer)
+pub fn cancel_multisig_action_handler(ctx: Context<CancelMultisigAction>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.proposal.proposed_by
+            || ctx.accounts.multisig.signer_index(&authority).is_some(),
+        LendingError::Unauthorized
+    );
+
+    emit!(MultisigActionCancelled {
+        id: ctx.accounts.proposal.id,
+        action: ctx.accounts.proposal.action,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, TransferChecked, TokenAccount, Mint, TokenInterface};
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct PartialRepay<'info> {
+    /// Not gated on `paused` - borrowers can always repay and reclaim collateral
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        mut,
+        seeds = [TOKEN_CONFIG_SEED, loan.token_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    /// Global risk limits (token overrides applied on top)
+    #[account(
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOAN_SEED,
+            loan.borrower.as_ref(),
+            loan.token_mint.as_ref(),
+            &loan.index.to_le_bytes()
+        ],
+        bump = loan.bump,
+        constraint = loan.status == LoanStatus::Active @ LendingError::LoanAlreadyRepaid,
+        constraint = loan.borrower == borrower.key() @ LendingError::Unauthorized
+    )]
+    pub loan: Box<Account<'info, Loan>>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Operations wallet receives 25% of the fee on the repaid amount
+    #[account(
+        mut,
+        constraint = operations_wallet.key() == protocol_state.operations_wallet @ LendingError::Unauthorized
+    )]
+    pub operations_wallet: AccountInfo<'info>,
+
+    /// CHECK: Staking reward vault receives 25% of the fee on the repaid amount
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED],
+        bump
+    )]
+    pub staking_reward_vault: AccountInfo<'info>,
+
+    /// Insurance fund - receives its slice of the loan fee; required while the insurance fee is on
+    #[account(
+        mut,
+        seeds = [INSURANCE_FUND_SEED],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = borrower_token_account.owner == borrower.key() @ LendingError::InvalidTokenAccountOwner,
+        constraint = borrower_token_account.mint == loan.token_mint
+    )]
+    pub borrower_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = loan,
+        seeds = [b"vault", loan.key().as_ref()],
+        bump
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == loan.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Per-borrower exposure tracking (created on first use)
+    #[account(
+        init_if_needed,
+        payer = borrower,
+        space = UserExposure::LEN,
+        seeds = [USER_EXPOSURE_SEED, borrower.key().as_ref()],
+        bump
+    )]
+    pub user_exposure: Box<Account<'info, UserExposure>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Repay part of an active loan's principal (plus the flat fee on that part)
+/// If `release_collateral` is true, a proportional slice of collateral is returned,
+/// otherwise all collateral stays in the vault to improve the loan's health
+pub fn partial_repay_handler(
+    ctx: Context<PartialRepay>,
+    amount: u64,
+    release_collateral: bool,
+) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+
+    ReentrancyGuard::enter(protocol_state)?;
+
+    // Store loan data before taking mutable borrow
+    let borrower = ctx.accounts.loan.borrower;
+    let token_mint = ctx.accounts.loan.token_mint;
+    let loan_index = ctx.accounts.loan.index;
+    let loan_bump = ctx.accounts.loan.bump;
+    let sol_borrowed = ctx.accounts.loan.sol_borrowed;
+    let collateral_amount = ctx.accounts.loan.collateral_amount;
+
+    // Full repayment must go through repay_loan so the loan is closed out
+    require!(amount > 0 && amount < sol_borrowed, LendingError::InvalidAmount);
+
+    let remaining_borrowed = SafeMath::sub(sol_borrowed, amount)?;
+    require!(
+        remaining_borrowed >= MIN_COLLATERAL_VALUE_LAMPORTS,
+        LendingError::LoanAmountTooLow
+    );
+
+    // Flat fee applies to the repaid portion only
+    let protocol_fee = SafeMath::mul_div(
+        amount,
+        PROTOCOL_FEE_BPS as u64,
+        BPS_DIVISOR
+    )?;
+    let total_owed = SafeMath::add(amount, protocol_fee)?;
+
+    require!(
+        ctx.accounts.borrower.lamports() >= total_owed,
+        LendingError::InsufficientTreasuryBalance
+    );
+
+    let (treasury_fee_with_dust, staking_fee, operations_fee) =
+        LoanCalculator::split_loan_fee(protocol_fee)?;
+    let (treasury_fee_with_dust, insurance_fee) = LoanCalculator::split_insurance_fee(
+        protocol_fee,
+        treasury_fee_with_dust,
+        protocol_state.insurance_fee_bps,
+    )?;
+
+    // Proportional slice of collateral for the repaid share of principal
+    let collateral_released = if release_collateral {
+        SafeMath::mul_div(collateral_amount, amount, sol_borrowed)?
+    } else {
+        0
+    };
+    let remaining_collateral = SafeMath::sub(collateral_amount, collateral_released)?;
+
+    let liquidation_price = LoanCalculator::calculate_liquidation_price(
+        remaining_borrowed,
+        remaining_collateral,
+        ctx.accounts.token_config.ltv_bps,
+        ctx.accounts.risk_config.for_token(&ctx.accounts.token_config).liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    // Update loan BEFORE transfers
+    let loan = &mut ctx.accounts.loan;
+    LoanCalculator::upgrade_loan_prices(
+        loan,
+        ctx.accounts.token_config.ltv_bps,
+        ctx.accounts.risk_config.for_token(&ctx.accounts.token_config).liquidation_buffer_bps,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    loan.sol_borrowed = remaining_borrowed;
+    loan.collateral_amount = remaining_collateral;
+    loan.liquidation_price = liquidation_price;
+
+    // === TRANSFER PRINCIPAL + TREASURY FEE SHARE TO TREASURY ===
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.borrower.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        ),
+        SafeMath::add(amount, treasury_fee_with_dust)?,
+    )?;
+
+    // === DISTRIBUTE FEE: Staking ===
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.borrower.to_account_info(),
+                to: ctx.accounts.staking_reward_vault.to_account_info(),
+            },
+        ),
+        staking_fee,
+    )?;
+
+    // === DISTRIBUTE FEE: Operations ===
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.borrower.to_account_info(),
+                to: ctx.accounts.operations_wallet.to_account_info(),
+            },
+        ),
+        operations_fee,
+    )?;
+
+    // === DISTRIBUTE FEE: Insurance fund (carved from the treasury share) ===
+    InsuranceFundUtils::deposit(
+        ctx.accounts.insurance_fund.as_deref_mut(),
+        ctx.accounts.borrower.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        insurance_fee,
+    )?;
+
+    // Release proportional collateral back to borrower
+    if collateral_released > 0 {
+        let loan_seeds: &[&[u8]] = &[
+            LOAN_SEED,
+            borrower.as_ref(),
+            token_mint.as_ref(),
+            &loan_index.to_le_bytes(),
+            &[loan_bump],
+        ];
+        let loan_signer_seeds = &[loan_seeds];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.borrower_token_account.to_account_info(),
+                authority: ctx.accounts.loan.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+            },
+            loan_signer_seeds,
+        );
+        token_interface::transfer_checked(transfer_ctx, collateral_released, ctx.accounts.token_mint.decimals)?;
+    }
+
+    // Update protocol state
+    protocol_state.total_sol_borrowed = SafeMath::sub(
+        protocol_state.total_sol_borrowed,
+        amount
+    )?;
+
+    // Update token exposure tracking - loan stays active so count is unchanged
+    let token_config = &mut ctx.accounts.token_config;
+    token_config.total_active_borrowed = SafeMath::sub(
+        token_config.total_active_borrowed,
+        amount
+    )?;
+    RevenueTracker::record_repayment(protocol_state, token_config, amount, protocol_fee)?;
+
+    UserExposureTracker::record_principal_closed(
+        &mut ctx.accounts.user_exposure,
+        borrower,
+        ctx.bumps.user_exposure,
+        amount,
+        false,
+        false,
+    )?;
+
+    emit!(LoanPartiallyRepaid {
+        loan: ctx.accounts.loan.key(),
+        borrower,
+        sol_repaid: amount,
+        protocol_fee,
+        collateral_released,
+        remaining_borrowed,
+        remaining_collateral,
+        liquidation_price,
+    });
+
+    ReentrancyGuard::exit(protocol_state);
+
+    Ok(())
+}
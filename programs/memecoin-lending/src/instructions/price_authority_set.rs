@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::LendingError;
+
+#[derive(Accounts)]
+pub struct InitializePriceAuthoritySet<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = PriceAuthoritySet::LEN,
+        seeds = [PRICE_AUTHORITY_SET_SEED],
+        bump
+    )]
+    pub price_authority_set: Box<Account<'info, PriceAuthoritySet>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Validate an M-of-N configuration
+fn validate_quorum(signer_count: usize, threshold: u8, max_deviation_bps: u16) -> Result<()> {
+    require!(
+        threshold > 0 && threshold as usize <= signer_count,
+        LendingError::InvalidPriceQuorum
+    );
+    require!(
+        max_deviation_bps > 0 && max_deviation_bps as u64 <= BPS_DIVISOR,
+        LendingError::InvalidPriceQuorum
+    );
+    Ok(())
+}
+
+/// Create the price authority set (admin only)
+pub fn initialize_price_authority_set_handler(
+    ctx: Context<InitializePriceAuthoritySet>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    max_deviation_bps: u16,
+) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_PRICE_SIGNERS,
+        LendingError::InvalidPriceQuorum
+    );
+    validate_quorum(signers.len(), threshold, max_deviation_bps)?;
+
+    let price_authority_set = &mut ctx.accounts.price_authority_set;
+    for (i, signer) in signers.iter().enumerate() {
+        require!(*signer != Pubkey::default(), LendingError::InvalidPriceAuthority);
+        require!(!signers[..i].contains(signer), LendingError::DuplicatePriceSigner);
+        price_authority_set.signers[i] = *signer;
+    }
+    price_authority_set.signer_count = signers.len() as u8;
+    price_authority_set.threshold = threshold;
+    price_authority_set.max_deviation_bps = max_deviation_bps;
+    price_authority_set.bump = ctx.bumps.price_authority_set;
+
+    // From here on the single price authority can no longer price loans on its own
+    ctx.accounts.protocol_state.price_quorum_required = true;
+
+    Ok(())
+}
+
+/// Replace a single signer in the price authority set - approved through the multisig,
+/// after PARAMETER_CHANGE_DELAY
+pub fn apply_price_signer_rotation(
+    price_authority_set: &mut PriceAuthoritySet,
+    old_signer: Pubkey,
+    new_signer: Pubkey,
+) -> Result<()> {
+    require!(new_signer != Pubkey::default(), LendingError::InvalidPriceAuthority);
+    require!(!price_authority_set.is_signer(&new_signer), LendingError::DuplicatePriceSigner);
+
+    let count = price_authority_set.signer_count as usize;
+    let index = price_authority_set.signers[..count]
+        .iter()
+        .position(|s| *s == old_signer)
+        .ok_or(LendingError::InvalidPriceAuthority)?;
+    price_authority_set.signers[index] = new_signer;
+
+    Ok(())
+}
+
+/// Update the quorum threshold and outlier deviation - approved through the multisig,
+/// after PARAMETER_CHANGE_DELAY
+pub fn apply_price_quorum_update(
+    price_authority_set: &mut PriceAuthoritySet,
+    threshold: Option<u8>,
+    max_deviation_bps: Option<u16>,
+) -> Result<()> {
+    let threshold = threshold.unwrap_or(price_authority_set.threshold);
+    let max_deviation_bps = max_deviation_bps.unwrap_or(price_authority_set.max_deviation_bps);
+    validate_quorum(price_authority_set.signer_count as usize, threshold, max_deviation_bps)?;
+
+    price_authority_set.threshold = threshold;
+    price_authority_set.max_deviation_bps = max_deviation_bps;
+
+    Ok(())
+}
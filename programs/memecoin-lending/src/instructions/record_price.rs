@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::utils::*;
+
+#[derive(Accounts)]
+pub struct RecordPrice<'info> {
+    #[account(
+        seeds = [TOKEN_CONFIG_SEED, token_config.mint.as_ref()],
+        bump = token_config.bump,
+        constraint = !token_config.blacklisted @ LendingError::TokenBlacklisted
+    )]
+    pub token_config: Box<Account<'info, TokenConfig>>,
+
+    /// Token mint - decimals are needed to normalise the pool price
+    #[account(address = token_config.mint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PriceHistory::LEN,
+        seeds = [PRICE_HISTORY_SEED, token_config.mint.as_ref()],
+        bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
+    /// CHECK: Validated by token_config.pool_address constraint
+    #[account(
+        constraint = pool_account.key() == token_config.pool_address @ LendingError::InvalidPoolAddress
+    )]
+    pub pool_account: UncheckedAccount<'info>,
+
+    /// First reserve vault of a vault-priced pool: the PumpSwap base vault or the Raydium CPMM
+    /// token_0 vault. Required for those pool types (MissingPumpSwapVaults / MissingCpmmVaults)
+    /// CHECK: Validated in handler against pool data
+    pub pumpswap_base_vault: Option<UncheckedAccount<'info>>,
+
+    /// Second reserve vault of a vault-priced pool: the PumpSwap quote vault (WSOL) or the Raydium
+    /// CPMM token_1 vault. Required for those pool types (MissingPumpSwapVaults / MissingCpmmVaults)
+    /// CHECK: Validated in handler against pool data
+    pub pumpswap_quote_vault: Option<UncheckedAccount<'info>>,
+
+    /// Instructions sysvar - record_price must not share its transaction with a swap
+    /// CHECK: Address checked against the instructions sysvar id
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Anyone can crank price checkpoints
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record the current pool price into the token's TWAP history
+/// Permissionless, but rate limited to one checkpoint per MIN_PRICE_RECORD_INTERVAL_SECONDS
+/// and only as a standalone top-level instruction
+pub fn record_price_handler(ctx: Context<RecordPrice>) -> Result<()> {
+    let clock = Clock::get()?;
+    PriceFeedUtils::validate_standalone_record(&ctx.accounts.instructions_sysvar)?;
+
+    let token_config = &ctx.accounts.token_config;
+    let price_history = &mut ctx.accounts.price_history;
+
+    if price_history.token_mint == Pubkey::default() {
+        price_history.token_mint = token_config.mint;
+        price_history.bump = ctx.bumps.price_history;
+    } else {
+        require!(
+            clock.unix_timestamp - price_history.last_recorded_at >= MIN_PRICE_RECORD_INTERVAL_SECONDS,
+            LendingError::PriceRecordTooFrequent
+        );
+    }
+
+    let price = PriceFeedUtils::read_spot_price(
+        &ctx.accounts.pool_account,
+        token_config.pool_type,
+        &token_config.mint,
+        ctx.accounts.token_mint.decimals,
+        ctx.accounts.pumpswap_base_vault.as_ref().map(|v| v.as_ref()),
+        ctx.accounts.pumpswap_quote_vault.as_ref().map(|v| v.as_ref()),
+    )?;
+
+    PriceFeedUtils::record_checkpoint(price_history, price, clock.unix_timestamp)?;
+
+    Ok(())
+}
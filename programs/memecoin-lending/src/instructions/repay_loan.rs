@@ -3,15 +3,16 @@ use anchor_lang::system_program;
 use anchor_spl::token_interface::{self, TransferChecked, TokenAccount, Mint, TokenInterface};
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 use crate::utils::*;
 
 #[derive(Accounts)]
 pub struct RepayLoan<'info> {
+    /// Not gated on `paused` - borrowers can always repay and reclaim collateral
     #[account(
         mut,
         seeds = [PROTOCOL_STATE_SEED],
-        bump = protocol_state.bump,
-        constraint = !protocol_state.paused @ LendingError::ProtocolPaused
+        bump = protocol_state.bump
     )]
     pub protocol_state: Box<Account<'info, ProtocolState>>,
 
@@ -60,6 +61,14 @@ pub struct RepayLoan<'info> {
     )]
     pub staking_reward_vault: AccountInfo<'info>,
 
+    /// Insurance fund - receives its slice of the loan fee; required while the insurance fee is on
+    #[account(
+        mut,
+        seeds = [INSURANCE_FUND_SEED],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
     #[account(mut)]
     pub borrower: Signer<'info>,
 
@@ -85,6 +94,16 @@ pub struct RepayLoan<'info> {
     pub token_mint: InterfaceAccount<'info, Mint>,
 
 
+    /// Per-borrower exposure tracking (created on first use)
+    #[account(
+        init_if_needed,
+        payer = borrower,
+        space = UserExposure::LEN,
+        seeds = [USER_EXPOSURE_SEED, borrower.key().as_ref()],
+        bump
+    )]
+    pub user_exposure: Box<Account<'info, UserExposure>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -123,29 +142,15 @@ pub fn repay_loan_handler(ctx: Context<RepayLoan>) -> Result<()> {
     );
 
     // === FIX 3: Calculate fee splits explicitly (all from the 2% protocol fee) ===
-    let treasury_fee = SafeMath::mul_div(
-        protocol_fee, 
-        LOAN_FEE_TREASURY_BPS as u64, 
-        BPS_DIVISOR
-    )?;
-
-    let staking_fee = SafeMath::mul_div(
-        protocol_fee, 
-        LOAN_FEE_STAKING_BPS as u64, 
-        BPS_DIVISOR
-    )?;
-
-    let operations_fee = SafeMath::mul_div(
+    // Rounding dust is sent to treasury
+    let (treasury_fee_with_dust, staking_fee, operations_fee) =
+        LoanCalculator::split_loan_fee(protocol_fee)?;
+    let (treasury_fee_with_dust, insurance_fee) = LoanCalculator::split_insurance_fee(
         protocol_fee,
-        LOAN_FEE_OPERATIONS_BPS as u64,
-        BPS_DIVISOR
+        treasury_fee_with_dust,
+        protocol_state.insurance_fee_bps,
     )?;
 
-    // Handle dust from rounding - send to treasury
-    let total_distributed = treasury_fee + staking_fee + operations_fee;
-    let dust = protocol_fee.saturating_sub(total_distributed);
-    let treasury_fee_with_dust = treasury_fee + dust;
-
     // Update loan status BEFORE transfers
     loan.status = LoanStatus::Repaid;
 
@@ -198,6 +203,14 @@ pub fn repay_loan_handler(ctx: Context<RepayLoan>) -> Result<()> {
         operations_fee,
     )?;
 
+    // === DISTRIBUTE FEE: Insurance fund (carved from the treasury share) ===
+    InsuranceFundUtils::deposit(
+        ctx.accounts.insurance_fund.as_deref_mut(),
+        ctx.accounts.borrower.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        insurance_fee,
+    )?;
+
     // Transfer collateral back to borrower
     let loan_seeds: &[&[u8]] = &[
         LOAN_SEED,
@@ -225,10 +238,6 @@ pub fn repay_loan_handler(ctx: Context<RepayLoan>) -> Result<()> {
         protocol_state.total_sol_borrowed, 
         sol_borrowed
     )?;
-    protocol_state.total_fees_earned = SafeMath::add(
-        protocol_state.total_fees_earned, 
-        protocol_fee
-    )?;
     protocol_state.active_loans_count = SafeMath::sub(
         protocol_state.active_loans_count, 
         1
@@ -246,9 +255,25 @@ pub fn repay_loan_handler(ctx: Context<RepayLoan>) -> Result<()> {
         token_config.total_active_borrowed,
         sol_borrowed
     )?;
+    RevenueTracker::record_repayment(protocol_state, token_config, sol_borrowed, protocol_fee)?;
 
-    // User exposure tracking removed for stack size optimization
+    UserExposureTracker::record_principal_closed(
+        &mut ctx.accounts.user_exposure,
+        borrower,
+        ctx.bumps.user_exposure,
+        sol_borrowed,
+        true,
+        false,
+    )?;
 
+    emit!(LoanRepaid {
+        loan: ctx.accounts.loan.key(),
+        borrower: ctx.accounts.borrower.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        sol_repaid: sol_borrowed,
+        protocol_fee,
+        collateral_returned: collateral_amount,
+    });
     
     // FIX 1: Exit reentrancy guard
     ReentrancyGuard::exit(protocol_state);
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::ValidationUtils;
+
+#[derive(Accounts)]
+pub struct InitializeRiskConfig<'info> {
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump,
+        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = RiskConfig::LEN,
+        seeds = [RISK_CONFIG_SEED],
+        bump
+    )]
+    pub risk_config: Box<Account<'info, RiskConfig>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the risk config with the compile-time defaults (admin only)
+pub fn initialize_risk_config_handler(ctx: Context<InitializeRiskConfig>) -> Result<()> {
+    let risk_config = &mut ctx.accounts.risk_config;
+    risk_config.params = RiskParams::DEFAULT;
+    risk_config.bump = ctx.bumps.risk_config;
+    risk_config._reserved = [0u8; 32];
+
+    emit!(RiskConfigUpdated { params: risk_config.params });
+
+    Ok(())
+}
+
+/// Replace the global risk limits - queued through propose_change / execute_change
+pub fn apply_risk_config_update(risk_config: &mut RiskConfig, params: RiskParams) -> Result<()> {
+    ValidationUtils::validate_risk_params(&params, false)?;
+
+    risk_config.params = params;
+
+    emit!(RiskConfigUpdated { params });
+
+    Ok(())
+}
+
+/// Set a token's risk overrides; zero fields inherit the global value - queued through
+/// propose_change / execute_change
+pub fn apply_token_risk_overrides_update(token_config: &mut TokenConfig, overrides: RiskParams) -> Result<()> {
+    ValidationUtils::validate_risk_params(&overrides, true)?;
+
+    token_config.risk_overrides = overrides;
+
+    emit!(TokenRiskOverridesUpdated {
+        mint: token_config.mint,
+        overrides,
+    });
+
+    Ok(())
+}
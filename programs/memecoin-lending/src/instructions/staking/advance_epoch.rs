@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct AdvanceEpoch<'info> {
@@ -53,6 +54,13 @@ pub fn advance_epoch_handler(ctx: Context<AdvanceEpoch>) -> Result<()> {
     pool.current_epoch_rewards = undistributed; // Roll over undistributed rewards
     pool.current_epoch_eligible_stake = pool.total_staked; // All stakers now eligible
     
+    emit!(EpochAdvanced {
+        epoch: pool.current_epoch,
+        epoch_start_time: pool.epoch_start_time,
+        last_epoch_rewards: pool.last_epoch_rewards,
+        last_epoch_eligible_stake: pool.last_epoch_eligible_stake,
+        rolled_over_rewards: undistributed,
+    });
     
     Ok(())
 }
\ No newline at end of file
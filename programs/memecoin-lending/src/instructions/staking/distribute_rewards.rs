@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct DistributeRewards<'info> {
@@ -218,6 +219,11 @@ pub fn distribute_rewards_handler<'info>(ctx: Context<'_, '_, '_, 'info, Distrib
         .checked_add(total_distributed_this_call)
         .ok_or(LendingError::MathOverflow)?;
     
+    emit!(RewardsDistributed {
+        epoch: distributable_epoch,
+        amount: total_distributed_this_call,
+        last_epoch_distributed: pool.last_epoch_distributed,
+    });
     
     Ok(())
 }
\ No newline at end of file
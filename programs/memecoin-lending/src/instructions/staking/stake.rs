@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 use crate::utils::MIN_STAKE_AMOUNT;
 
 #[derive(Accounts)]
@@ -106,6 +107,12 @@ pub fn stake_handler(ctx: Context<Stake>, amount: u64) -> Result<()> {
         amount,
     )?;
     
+    emit!(Staked {
+        user: ctx.accounts.user.key(),
+        amount,
+        staked_amount: ctx.accounts.user_stake.staked_amount,
+        total_staked: ctx.accounts.staking_pool.total_staked,
+    });
     
     Ok(())
 }
\ No newline at end of file
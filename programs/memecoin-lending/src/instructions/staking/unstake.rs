@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct Unstake<'info> {
@@ -95,6 +96,12 @@ pub fn unstake_handler(ctx: Context<Unstake>, amount: u64) -> Result<()> {
         amount,
     )?;
     
+    emit!(Unstaked {
+        user: ctx.accounts.user.key(),
+        amount,
+        staked_amount: ctx.accounts.user_stake.staked_amount,
+        total_staked: ctx.accounts.staking_pool.total_staked,
+    });
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::{PumpSwapPoolValidator, PumpfunCurveValidator};
+
+#[derive(Accounts)]
+pub struct SyncPumpfunMigration<'info> {
+    #[account(
+        mut,
+        seeds = [TOKEN_CONFIG_SEED, token_config.mint.as_ref()],
+        bump = token_config.bump,
+        constraint = token_config.pool_type == PoolType::Pumpfun @ LendingError::PoolTypeMismatch
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: Validated by token_config.pool_address constraint and in handler
+    #[account(
+        constraint = bonding_curve.key() == token_config.pool_address @ LendingError::InvalidPoolAddress
+    )]
+    pub bonding_curve: UncheckedAccount<'info>,
+
+    /// Canonical PumpSwap pool the curve migrated into
+    /// CHECK: Address derived and pool validated in handler
+    pub pumpswap_pool: UncheckedAccount<'info>,
+}
+
+/// Re-route a PumpFun token to its PumpSwap pool once the bonding curve completes
+/// Permissionless - the destination is fixed by the migration PDA, so anyone can crank it
+pub fn sync_pumpfun_migration_handler(ctx: Context<SyncPumpfunMigration>) -> Result<()> {
+    let token_config = &mut ctx.accounts.token_config;
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    let pumpswap_pool = &ctx.accounts.pumpswap_pool;
+
+    PumpfunCurveValidator::validate_full(bonding_curve, &token_config.mint)?;
+    {
+        let curve_data = bonding_curve.try_borrow_data()?;
+        require!(
+            PumpfunCurveValidator::is_complete(&curve_data)?,
+            LendingError::PumpfunCurveNotComplete
+        );
+    }
+
+    // Only the pool created by migration - a thin third-party pool for the same mint is rejected
+    require!(
+        pumpswap_pool.key() == PumpfunCurveValidator::derive_canonical_pumpswap_pool(&token_config.mint),
+        LendingError::InvalidPoolAddress
+    );
+    PumpSwapPoolValidator::validate_full(pumpswap_pool, &token_config.mint)?;
+
+    token_config.pool_type = PoolType::PumpSwap;
+    token_config.pool_address = pumpswap_pool.key();
+
+    emit!(TokenConfigUpdated {
+        mint: token_config.mint,
+        enabled: token_config.enabled,
+        ltv_bps: token_config.ltv_bps,
+        pool_address: token_config.pool_address,
+        pool_type: token_config.pool_type,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::LendingError;
+use crate::events::*;
+use crate::instructions::admin::{
+    apply_liquidation_settings_update, apply_liquidator_update, apply_role_update, apply_wallets_update,
+};
+use crate::instructions::insurance_fund::apply_insurance_fee_update;
+use crate::instructions::risk_config::{apply_risk_config_update, apply_token_risk_overrides_update};
+use crate::instructions::update_fees::apply_fee_update;
+use crate::instructions::update_token_config::apply_token_config_update;
+use crate::utils::SafeMath;
+
+#[derive(Accounts)]
+pub struct ProposeChange<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PendingChange::LEN,
+        seeds = [PENDING_CHANGE_SEED, &protocol_state.change_nonce.to_le_bytes()],
+        bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// Admin, or the risk manager for token config and risk override changes
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [PENDING_CHANGE_SEED, &pending_change.id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(
+        mut,
+        address = pending_change.proposed_by @ LendingError::Unauthorized
+    )]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Target of a TokenConfig or TokenRiskOverrides change
+    #[account(
+        mut,
+        seeds = [TOKEN_CONFIG_SEED, token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Option<Box<Account<'info, TokenConfig>>>,
+
+    /// Pool account - required when a TokenConfig change switches to a Raydium CPMM / CLMM, Meteora or PumpFun pool
+    /// CHECK: Validated against the new pool address when applied
+    pub pool_account: Option<UncheckedAccount<'info>>,
+
+    /// Target of a RiskConfig change
+    #[account(
+        mut,
+        seeds = [RISK_CONFIG_SEED],
+        bump = risk_config.bump
+    )]
+    pub risk_config: Option<Box<Account<'info, RiskConfig>>>,
+
+    /// Must exist before a non-zero InsuranceFee change can apply
+    #[account(
+        seeds = [INSURANCE_FUND_SEED],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Option<Box<Account<'info, InsuranceFund>>>,
+
+    /// Anyone may execute once the delay has passed - the change was fixed at proposal
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    #[account(
+        seeds = [PROTOCOL_STATE_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Box<Account<'info, ProtocolState>>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [PENDING_CHANGE_SEED, &pending_change.id.to_le_bytes()],
+        bump = pending_change.bump
+    )]
+    pub pending_change: Box<Account<'info, PendingChange>>,
+
+    /// CHECK: Rent refund destination, must be the proposer
+    #[account(
+        mut,
+        address = pending_change.proposed_by @ LendingError::Unauthorized
+    )]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Admin, or the risk manager for token config and risk override changes
+    pub authority: Signer<'info>,
+}
+
+/// Admin for any change; the risk manager also for token config and risk override changes
+fn require_change_authority(
+    protocol_state: &ProtocolState,
+    authority: &Pubkey,
+    change: &ParameterChange,
+) -> Result<()> {
+    let allowed = match change.required_role() {
+        Some(role) => protocol_state.has_role(authority, role),
+        None => *authority == protocol_state.admin,
+    };
+    require!(allowed, LendingError::Unauthorized);
+    Ok(())
+}
+
+/// Queue a parameter change that can be executed after PARAMETER_CHANGE_DELAY
+pub fn propose_change_handler(ctx: Context<ProposeChange>, change: ParameterChange) -> Result<()> {
+    let protocol_state = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+
+    require_change_authority(protocol_state, &ctx.accounts.authority.key(), &change)?;
+    if let ParameterChange::Role { holder, .. } = change {
+        require!(holder != Pubkey::default(), LendingError::RoleRevocationNotQueued);
+    }
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.id = protocol_state.change_nonce;
+    pending_change.change = change;
+    pending_change.proposed_by = ctx.accounts.authority.key();
+    pending_change.proposed_at = clock.unix_timestamp;
+    pending_change.execute_after = clock.unix_timestamp
+        .checked_add(PARAMETER_CHANGE_DELAY)
+        .ok_or(LendingError::MathOverflow)?;
+    pending_change.bump = ctx.bumps.pending_change;
+
+    protocol_state.change_nonce = SafeMath::add(protocol_state.change_nonce, 1)?;
+
+    emit!(ChangeProposed {
+        id: pending_change.id,
+        change,
+        proposed_by: pending_change.proposed_by,
+        execute_after: pending_change.execute_after,
+    });
+
+    Ok(())
+}
+
+/// Apply a queued change once its delay has passed and before its grace period ends
+pub fn execute_change_handler(ctx: Context<ExecuteChange>) -> Result<()> {
+    let clock = Clock::get()?;
+    let pending_change = &ctx.accounts.pending_change;
+
+    require!(clock.unix_timestamp >= pending_change.execute_after, LendingError::ChangeNotReady);
+    require!(clock.unix_timestamp <= pending_change.expires_at(), LendingError::ChangeExpired);
+
+    let id = pending_change.id;
+    let change = pending_change.change;
+    let protocol_state = &mut ctx.accounts.protocol_state;
+
+    match change {
+        ParameterChange::Fees {
+            protocol_fee_bps,
+            treasury_fee_bps,
+            buyback_fee_bps,
+            operations_fee_bps,
+            liquidation_penalty_bps,
+        } => apply_fee_update(
+            protocol_state,
+            protocol_fee_bps,
+            treasury_fee_bps,
+            buyback_fee_bps,
+            operations_fee_bps,
+            liquidation_penalty_bps,
+        )?,
+        ParameterChange::Wallets { buyback_wallet, operations_wallet } => {
+            apply_wallets_update(protocol_state, buyback_wallet, operations_wallet)?
+        },
+        ParameterChange::Liquidator { new_liquidator } => {
+            apply_liquidator_update(protocol_state, new_liquidator)?
+        },
+        ParameterChange::TokenConfig {
+            mint,
+            enabled,
+            ltv_bps,
+            pool_address,
+            pool_type,
+            min_loan_amount,
+            max_loan_amount,
+        } => {
+            let token_config = ctx.accounts.token_config.as_deref_mut()
+                .ok_or(LendingError::ChangeTargetMismatch)?;
+            require!(token_config.mint == mint, LendingError::ChangeTargetMismatch);
+            apply_token_config_update(
+                token_config,
+                enabled,
+                ltv_bps,
+                pool_address,
+                pool_type,
+                min_loan_amount,
+                max_loan_amount,
+                ctx.accounts.pool_account.as_ref().map(|p| p.as_ref()),
+            )?
+        },
+        ParameterChange::RiskConfig { params } => {
+            let risk_config = ctx.accounts.risk_config.as_deref_mut()
+                .ok_or(LendingError::ChangeTargetMismatch)?;
+            apply_risk_config_update(risk_config, params)?
+        },
+        ParameterChange::TokenRiskOverrides { mint, overrides } => {
+            let token_config = ctx.accounts.token_config.as_deref_mut()
+                .ok_or(LendingError::ChangeTargetMismatch)?;
+            require!(token_config.mint == mint, LendingError::ChangeTargetMismatch);
+            apply_token_risk_overrides_update(token_config, overrides)?
+        },
+        ParameterChange::LiquidationSettings {
+            permissionless_liquidation,
+            keeper_bonus_bps,
+            liquidator_exclusivity_seconds,
+        } => apply_liquidation_settings_update(
+            protocol_state,
+            permissionless_liquidation,
+            keeper_bonus_bps,
+            liquidator_exclusivity_seconds,
+        )?,
+        ParameterChange::InsuranceFee { insurance_fee_bps } => apply_insurance_fee_update(
+            protocol_state,
+            ctx.accounts.insurance_fund.as_deref().map(|f| &**f),
+            insurance_fee_bps,
+        )?,
+        ParameterChange::Role { role, holder } => apply_role_update(protocol_state, role, holder)?,
+    }
+
+    emit!(ChangeExecuted {
+        id,
+        change,
+        executed_by: ctx.accounts.executor.key(),
+    });
+
+    Ok(())
+}
+
+/// Drop a queued change
+pub fn cancel_change_handler(ctx: Context<CancelChange>) -> Result<()> {
+    require_change_authority(
+        &ctx.accounts.protocol_state,
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.pending_change.change,
+    )?;
+
+    emit!(ChangeCancelled {
+        id: ctx.accounts.pending_change.id,
+        change: ctx.accounts.pending_change.change,
+    });
+
+    Ok(())
+}
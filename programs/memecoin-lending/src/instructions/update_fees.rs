@@ -1,33 +1,26 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
 
-#[derive(Accounts)]
-pub struct UpdateFees<'info> {
-    #[account(
-        mut,
-        seeds = [PROTOCOL_STATE_SEED],
-        bump = protocol_state.bump,
-        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-    
-    pub admin: Signer<'info>,
-}
-
-pub fn update_fees_handler(
-    ctx: Context<UpdateFees>,
+/// Apply a fee change - queued through propose_change / execute_change
+pub fn apply_fee_update(
+    protocol_state: &mut ProtocolState,
     protocol_fee_bps: Option<u16>,
     treasury_fee_bps: Option<u16>,
     buyback_fee_bps: Option<u16>,
     operations_fee_bps: Option<u16>,
+    liquidation_penalty_bps: Option<u16>,
 ) -> Result<()> {
-    let protocol_state = &mut ctx.accounts.protocol_state;
-    
     if let Some(fee) = protocol_fee_bps {
         require!(fee <= 500, LendingError::InvalidFeeConfiguration); // Max 5%
         protocol_state.protocol_fee_bps = fee;
     }
+
+    if let Some(penalty) = liquidation_penalty_bps {
+        require!(penalty <= MAX_LIQUIDATION_PENALTY_BPS, LendingError::InvalidFeeConfiguration); // Max 20%
+        protocol_state.liquidation_penalty_bps = penalty;
+    }
     
     // If updating liquidation splits, validate they sum to 10000
     let treasury = treasury_fee_bps.unwrap_or(protocol_state.treasury_fee_bps);
@@ -43,6 +36,13 @@ pub fn update_fees_handler(
     protocol_state.buyback_fee_bps = buyback;
     protocol_state.operations_fee_bps = operations;
     
+    emit!(FeesUpdated {
+        protocol_fee_bps: protocol_state.protocol_fee_bps,
+        treasury_fee_bps: treasury,
+        buyback_fee_bps: buyback,
+        operations_fee_bps: operations,
+        liquidation_penalty_bps: protocol_state.liquidation_penalty_bps,
+    });
     
     Ok(())
 }
\ No newline at end of file
@@ -1,36 +1,22 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::error::LendingError;
+use crate::events::*;
+use crate::utils::{LoanCalculator, PriceFeedUtils};
 
-#[derive(Accounts)]
-pub struct UpdateTokenConfig<'info> {
-    #[account(
-        seeds = [PROTOCOL_STATE_SEED],
-        bump = protocol_state.bump,
-        constraint = !protocol_state.paused @ LendingError::ProtocolPaused,
-        constraint = protocol_state.admin == admin.key() @ LendingError::Unauthorized
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-
-    #[account(
-        mut,
-        seeds = [TOKEN_CONFIG_SEED, token_config.mint.as_ref()],
-        bump = token_config.bump
-    )]
-    pub token_config: Account<'info, TokenConfig>,
-
-    pub admin: Signer<'info>,
-}
-
-pub fn update_token_config_handler(
-    ctx: Context<UpdateTokenConfig>,
+/// Apply a token config change - queued through propose_change / execute_change
+/// `pool_account` is required when switching to a Raydium CPMM / CLMM, Meteora or PumpFun pool
+#[allow(clippy::too_many_arguments)]
+pub fn apply_token_config_update(
+    token_config: &mut TokenConfig,
     enabled: Option<bool>,
     ltv_bps: Option<u16>,
     pool_address: Option<Pubkey>,
     pool_type: Option<PoolType>,
+    min_loan_amount: Option<u64>,
+    max_loan_amount: Option<u64>,
+    pool_account: Option<&AccountInfo>,
 ) -> Result<()> {
-    let token_config = &mut ctx.accounts.token_config;
-    
     // Update enabled status
     if let Some(enabled_value) = enabled {
         token_config.enabled = enabled_value;
@@ -44,6 +30,17 @@ pub fn update_token_config_handler(
         token_config.ltv_bps = ltv_value;
     }
 
+    // Update loan limits - same rules as whitelist_token
+    if min_loan_amount.is_some() || max_loan_amount.is_some() {
+        let min = min_loan_amount.unwrap_or(token_config.min_loan_amount);
+        let max = max_loan_amount.unwrap_or(token_config.max_loan_amount);
+        if min == 0 || max == 0 || min >= max {
+            return Err(LendingError::InvalidLoanAmount.into());
+        }
+        token_config.min_loan_amount = min;
+        token_config.max_loan_amount = max;
+    }
+
     // Update pool address
     if let Some(pool) = pool_address {
         token_config.pool_address = pool;
@@ -54,6 +51,36 @@ pub fn update_token_config_handler(
         token_config.pool_type = ptype;
     }
 
+    // Raydium CPMM / CLMM and Meteora pools must be owned by their AMM program and pair our mint with SOL,
+    // PumpFun bonding curves must be the mint's curve PDA and not yet complete
+    if (pool_address.is_some() || pool_type.is_some())
+        && matches!(
+            token_config.pool_type,
+            PoolType::Pumpfun
+                | PoolType::RaydiumCpmm
+                | PoolType::RaydiumClmm
+                | PoolType::MeteoraDlmm
+                | PoolType::MeteoraDammV2
+        )
+    {
+        let pool_account = pool_account.ok_or(LendingError::InvalidPoolAddress)?;
+        require!(
+            pool_account.key() == token_config.pool_address,
+            LendingError::InvalidPoolAddress
+        );
+        PriceFeedUtils::validate_pool_account(pool_account, token_config.pool_type, &token_config.mint)?;
+    }
+
+    // Bonding curve tokens never exceed the conservative LTV cap
+    token_config.ltv_bps = LoanCalculator::cap_ltv_for_pool(token_config.ltv_bps, token_config.pool_type);
+
+    emit!(TokenConfigUpdated {
+        mint: token_config.mint,
+        enabled: token_config.enabled,
+        ltv_bps: token_config.ltv_bps,
+        pool_address: token_config.pool_address,
+        pool_type: token_config.pool_type,
+    });
     
     Ok(())
 }
\ No newline at end of file
@@ -2,7 +2,8 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
 use crate::state::*;
 use crate::error::LendingError;
-use crate::utils::PumpSwapPoolValidator;
+use crate::events::*;
+use crate::utils::{LoanCalculator, PriceFeedUtils, PumpSwapPoolValidator};
 
 #[derive(Accounts)]
 #[instruction(tier: u8)]
@@ -26,8 +27,8 @@ pub struct WhitelistToken<'info> {
 
     pub token_mint: InterfaceAccount<'info, Mint>,
 
-    /// Pool account - optional, only validated if pool_type is PumpSwap
-    /// CHECK: Validated in handler if present and pool_type is PumpSwap
+    /// Pool account - optional for PumpSwap, required for PumpFun, Raydium CPMM / CLMM and Meteora pools
+    /// CHECK: Validated in handler against pool_address and pool_type
     pub pool_account: Option<UncheckedAccount<'info>>,
 
     #[account(mut)]
@@ -57,20 +58,7 @@ pub fn whitelist_token_handler(
     };
 
     // Validate pool type
-    let pool_type = match pool_type {
-        0 => PoolType::Raydium,
-        1 => PoolType::Orca,
-        2 => PoolType::Pumpfun,
-        3 => PoolType::PumpSwap,
-        _ => return Err(LendingError::InvalidPoolType.into()),
-    };
-
-    // WARNING: PumpFun pool type should not be used in production
-    // Tokens must migrate to PumpSwap/Raydium for liquidation support
-    if pool_type == PoolType::Pumpfun {
-        msg!("WARNING: PumpFun pool type - liquidation will be disabled for this token");
-        msg!("PumpFun tokens cannot be liquidated until they migrate to Raydium or PumpSwap");
-    }
+    let pool_type = PoolType::from_u8(pool_type)?;
 
     // Validate pool address
     if pool_address == Pubkey::default() {
@@ -108,13 +96,35 @@ pub fn whitelist_token_handler(
         }
     }
 
+    // Raydium CPMM / CLMM, Meteora pools and PumpFun bonding curves are always validated - the pool account is required
+    if matches!(
+        pool_type,
+        PoolType::Pumpfun
+            | PoolType::RaydiumCpmm
+            | PoolType::RaydiumClmm
+            | PoolType::MeteoraDlmm
+            | PoolType::MeteoraDammV2
+    ) {
+        let pool_account_info = ctx.accounts.pool_account.as_ref()
+            .ok_or(LendingError::InvalidPoolAddress)?;
+        require!(
+            pool_account_info.key == &pool_address,
+            LendingError::InvalidPoolAddress
+        );
+        PriceFeedUtils::validate_pool_account(
+            pool_account_info,
+            pool_type,
+            &ctx.accounts.token_mint.key(),
+        )?;
+    }
+
     // Validate loan amounts
     if min_loan_amount == 0 || max_loan_amount == 0 || min_loan_amount >= max_loan_amount {
         return Err(LendingError::InvalidLoanAmount.into());
     }
 
-    // Set LTV based on tier or protocol token status
-    let ltv_bps = if is_protocol_token {
+    // Set LTV based on tier or protocol token status, capped for bonding curve tokens
+    let tier_ltv_bps = if is_protocol_token {
         5000 // Protocol token always gets 50% LTV
     } else {
         match token_tier {
@@ -123,6 +133,7 @@ pub fn whitelist_token_handler(
             TokenTier::Gold => 5000,   // 50% LTV
         }
     };
+    let ltv_bps = LoanCalculator::cap_ltv_for_pool(tier_ltv_bps, pool_type);
 
     // Initialize token config
     token_config.mint = ctx.accounts.token_mint.key();
@@ -141,6 +152,16 @@ pub fn whitelist_token_handler(
     token_config.blacklisted = false;
     token_config.bump = ctx.bumps.token_config;
 
+    emit!(TokenWhitelisted {
+        mint: token_config.mint,
+        tier: token_tier,
+        pool_address,
+        pool_type,
+        ltv_bps,
+        min_loan_amount,
+        max_loan_amount,
+        is_protocol_token,
+    });
     
     Ok(())
 }
\ No newline at end of file
@@ -1,15 +1,15 @@
 use anchor_lang::prelude::*;
 
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod utils;
 pub mod swap;
 
 use instructions::*;
-use instructions::admin::{UpdateLiquidator, BlacklistToken, UpdatePriceAuthority};
-use state::PoolType;
-use error::LendingError;
+use instructions::admin::BlacklistToken;
+use state::{MultisigAction, ParameterChange, Role};
 
 declare_id!("2NVfyczy1rWMdb7Y9kGmHCZkM72wyYiN5ry8dntzBK2S");
 
@@ -49,34 +49,6 @@ pub mod memecoin_lending {
         instructions::whitelist_token::whitelist_token_handler(ctx, tier, pool_address, pool_type, min_loan_amount, max_loan_amount, is_protocol_token)
     }
 
-    /// Update token configuration parameters
-    pub fn update_token_config(
-        ctx: Context<UpdateTokenConfig>,
-        enabled: Option<bool>,
-        ltv_bps: Option<u16>,
-        pool_address: Option<Pubkey>,
-        pool_type: Option<u8>,
-    ) -> Result<()> {
-        let pool_type_enum = match pool_type {
-            Some(pt) => match pt {
-                0 => Some(PoolType::Raydium),
-                1 => Some(PoolType::Orca),
-                2 => Some(PoolType::Pumpfun),
-                3 => Some(PoolType::PumpSwap),
-                _ => return Err(LendingError::InvalidPoolType.into()),
-            },
-            None => None,
-        };
-        
-        instructions::update_token_config::update_token_config_handler(
-            ctx, 
-            enabled, 
-            ltv_bps,
-            pool_address,
-            pool_type_enum,
-        )
-    }
-
     /// Create a new collateralized loan
     pub fn create_loan(
         ctx: Context<CreateLoan>,
@@ -99,19 +71,89 @@ pub mod memecoin_lending {
         instructions::repay_loan::repay_loan_handler(ctx)
     }
 
+    /// Repay part of an active loan, optionally releasing proportional collateral
+    pub fn partial_repay(
+        ctx: Context<PartialRepay>,
+        amount: u64,
+        release_collateral: bool,
+    ) -> Result<()> {
+        instructions::partial_repay::partial_repay_handler(ctx, amount, release_collateral)
+    }
+
+    /// Deposit additional collateral into an active loan's vault
+    pub fn add_collateral(ctx: Context<AddCollateral>, amount: u64) -> Result<()> {
+        instructions::add_collateral::add_collateral_handler(ctx, amount)
+    }
+
+    /// Extend an active loan's due date (price approved by price authority co-signature or Ed25519 attestation)
+    pub fn extend_loan(
+        ctx: Context<ExtendLoan>,
+        additional_seconds: u64,
+        approved_price: u64,
+        price_timestamp: i64,
+    ) -> Result<()> {
+        instructions::extend_loan::extend_loan_handler(
+            ctx,
+            additional_seconds,
+            approved_price,
+            price_timestamp,
+        )
+    }
+
+    /// Record a pool price checkpoint for a token's TWAP (permissionless)
+    pub fn record_price(ctx: Context<RecordPrice>) -> Result<()> {
+        instructions::record_price::record_price_handler(ctx)
+    }
+
+    /// Switch a PumpFun token to its PumpSwap pool once the bonding curve completes (permissionless)
+    pub fn sync_pumpfun_migration(ctx: Context<SyncPumpfunMigration>) -> Result<()> {
+        instructions::sync_pumpfun_migration::sync_pumpfun_migration_handler(ctx)
+    }
+
+    /// Recompute a pre-upgrade loan's liquidation price in the canonical unit (permissionless)
+    /// Legacy loans cannot be liquidated by price until this runs (or the loan is otherwise touched)
+    pub fn migrate_loan(ctx: Context<MigrateLoan>) -> Result<()> {
+        instructions::migrate_loan::migrate_loan_handler(ctx)
+    }
+
+    /// Start a Dutch auction of a liquidatable loan's collateral (liquidator rules as `liquidate`)
+    pub fn start_auction(ctx: Context<StartAuction>) -> Result<()> {
+        instructions::auction::start_auction_handler(ctx)
+    }
+
+    /// Buy auctioned collateral for SOL at the current auction price (permissionless)
+    pub fn bid(ctx: Context<Bid>, max_collateral: u64, max_price: u64) -> Result<()> {
+        instructions::auction::bid_handler(ctx, max_collateral, max_price)
+    }
+
+    /// Settle a finished auction and close the loan (permissionless)
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        instructions::auction::settle_auction_handler(ctx)
+    }
+
+    /// Record (or clear) when a loan's TWAP crossed its liquidation price (permissionless)
+    pub fn mark_liquidatable(ctx: Context<MarkLiquidatable>) -> Result<()> {
+        instructions::mark_liquidatable::mark_liquidatable_handler(ctx)
+    }
+
     /// Liquidate a loan - sells collateral and splits proceeds
-    /// For PumpFun tokens: uses PumpFun bonding curve
+    /// For PumpFun tokens: sells into the bonding curve (completed curves must be synced to PumpSwap first)
+    /// For PumpSwap tokens without swap data: sells directly into the PumpSwap pool
     /// For other tokens: uses Jupiter aggregator
+    /// With `partial`, only sells enough collateral to restore the loan's health
+    /// Any signer may liquidate when permissionless liquidation is on and the authorized
+    /// liquidator's exclusivity window has passed, earning the keeper bonus
     pub fn liquidate<'info>(
         ctx: Context<'_, '_, 'info, 'info, Liquidate<'info>>,
         min_sol_output: u64,
-        jupiter_swap_data: Option<Vec<u8>>, // None for PumpFun, Some for Jupiter
+        jupiter_swap_data: Option<Vec<u8>>, // None for native PumpSwap, Some for Jupiter
+        partial: bool,
     ) -> Result<()> {
-        instructions::liquidate::liquidate_handler(ctx, min_sol_output, jupiter_swap_data)
+        instructions::liquidate::liquidate_handler(ctx, min_sol_output, jupiter_swap_data, partial)
     }
 
-    /// Pause protocol operations (admin only)
-    pub fn pause_protocol(ctx: Context<AdminControl>) -> Result<()> {
+    /// Pause protocol operations (guardian or admin)
+    pub fn pause_protocol(ctx: Context<GuardianControl>) -> Result<()> {
         instructions::admin::pause_handler(ctx)
     }
 
@@ -121,9 +163,9 @@ pub mod memecoin_lending {
     }
 
 
-    /// Initiate admin transfer with 48h timelock
-    pub fn initiate_admin_transfer(ctx: Context<AdminControl>, new_admin: Pubkey) -> Result<()> {
-        instructions::admin::initiate_admin_transfer_handler(ctx, new_admin)
+    /// Revoke a role immediately (admin only); granting one stays timelocked
+    pub fn revoke_role(ctx: Context<AdminControl>, role: Role) -> Result<()> {
+        instructions::admin::revoke_role_handler(ctx, role)
     }
 
     /// Accept admin transfer after timelock
@@ -136,15 +178,53 @@ pub mod memecoin_lending {
         instructions::admin::cancel_admin_transfer_handler(ctx)
     }
 
-    /// Withdraw treasury funds (admin only)
-    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
-        instructions::admin::withdraw_treasury_handler(ctx, amount)
+    /// Create the M-of-N multisig that approves emergency drains, treasury withdrawals,
+    /// admin transfers and price authority / price signer changes (admin only, once)
+    pub fn initialize_multisig(
+        ctx: Context<InitializeMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::multisig::initialize_multisig_handler(ctx, signers, threshold)
+    }
+
+    /// Open a multisig proposal (multisig signer; the treasurer may propose withdrawals)
+    pub fn propose_multisig_action(
+        ctx: Context<ProposeMultisigAction>,
+        action: MultisigAction,
+    ) -> Result<()> {
+        instructions::multisig::propose_multisig_action_handler(ctx, action)
+    }
+
+    /// Approve a multisig proposal (multisig signer)
+    pub fn approve_multisig_action(ctx: Context<ApproveMultisigAction>) -> Result<()> {
+        instructions::multisig::approve_multisig_action_handler(ctx)
+    }
+
+    /// Execute a multisig proposal once it has `threshold` approvals (anyone)
+    pub fn execute_multisig_action(ctx: Context<ExecuteMultisigAction>) -> Result<()> {
+        instructions::multisig::execute_multisig_action_handler(ctx)
+    }
+
+    /// Drop a multisig proposal (proposer or multisig signer)
+    pub fn cancel_multisig_action(ctx: Context<CancelMultisigAction>) -> Result<()> {
+        instructions::multisig::cancel_multisig_action_handler(ctx)
+    }
+
+
+    /// Resize the protocol state account after an upgrade that adds fields (admin only)
+    pub fn migrate_protocol_state(ctx: Context<MigrateProtocolState>) -> Result<()> {
+        instructions::admin::migrate_protocol_state_handler(ctx)
     }
 
+    /// Resize a token config after an upgrade that adds fields (admin only)
+    pub fn migrate_token_config(ctx: Context<MigrateTokenConfig>) -> Result<()> {
+        instructions::admin::migrate_token_config_handler(ctx)
+    }
 
-    /// Emergency drain (admin only)
-    pub fn emergency_drain(ctx: Context<EmergencyDrain>) -> Result<()> {
-        instructions::admin::emergency_drain_handler(ctx)
+    /// Create the global risk config with default limits (admin only)
+    pub fn initialize_risk_config(ctx: Context<InitializeRiskConfig>) -> Result<()> {
+        instructions::risk_config::initialize_risk_config_handler(ctx)
     }
 
     /// Fund the treasury with SOL
@@ -152,41 +232,50 @@ pub mod memecoin_lending {
         instructions::fund_treasury::fund_treasury_handler(ctx, amount)
     }
 
-    /// Update fee configuration (admin only)
-    pub fn update_fees(
-        ctx: Context<UpdateFees>,
-        protocol_fee_bps: Option<u16>,
-        treasury_fee_bps: Option<u16>,
-        buyback_fee_bps: Option<u16>,
-        operations_fee_bps: Option<u16>,
-    ) -> Result<()> {
-        instructions::update_fees::update_fees_handler(ctx, protocol_fee_bps, treasury_fee_bps, buyback_fee_bps, operations_fee_bps)
+    /// Queue a fee, wallet, liquidator, token config, risk limit, liquidation settings,
+    /// insurance fee or role change (admin; the risk manager may queue token config and
+    /// risk override changes)
+    /// It can be executed after PARAMETER_CHANGE_DELAY
+    pub fn propose_change(ctx: Context<ProposeChange>, change: ParameterChange) -> Result<()> {
+        instructions::timelock::propose_change_handler(ctx, change)
     }
 
-    /// Update wallet addresses (admin only)
-    pub fn update_wallets(
-        ctx: Context<AdminControl>,
-        new_buyback_wallet: Option<Pubkey>,
-        new_operations_wallet: Option<Pubkey>,
-    ) -> Result<()> {
-        instructions::admin::update_wallets_handler(ctx, new_buyback_wallet, new_operations_wallet)
+    /// Apply a queued change once its delay has passed (anyone)
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        instructions::timelock::execute_change_handler(ctx)
     }
-    
-    /// Update authorized liquidator (admin only)
-    pub fn update_liquidator(
-        ctx: Context<UpdateLiquidator>,
-        new_liquidator: Pubkey,
-    ) -> Result<()> {
-        instructions::admin::update_liquidator_handler(ctx, new_liquidator)
+
+    /// Drop a queued change (same authority as propose_change)
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        instructions::timelock::cancel_change_handler(ctx)
     }
+
+    /// Create the insurance fund PDA (admin only)
+    pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+        instructions::insurance_fund::initialize_insurance_fund_handler(ctx)
+    }
+
+    /// Deposit SOL into the insurance fund (anyone)
+    pub fn fund_insurance_fund(ctx: Context<FundInsuranceFund>, amount: u64) -> Result<()> {
+        instructions::insurance_fund::fund_insurance_fund_handler(ctx, amount)
+    }
+
     
-    /// Update price authority (admin only)
-    pub fn update_price_authority(
-        ctx: Context<UpdatePriceAuthority>,
-        new_price_authority: Pubkey,
+    /// Create the M-of-N price authority set (admin only)
+    pub fn initialize_price_authority_set(
+        ctx: Context<InitializePriceAuthoritySet>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        max_deviation_bps: u16,
     ) -> Result<()> {
-        instructions::admin::update_price_authority_handler(ctx, new_price_authority)
+        instructions::price_authority_set::initialize_price_authority_set_handler(
+            ctx,
+            signers,
+            threshold,
+            max_deviation_bps,
+        )
     }
+
     /// Initialize epoch-based staking pool
     pub fn initialize_staking(
         ctx: Context<InitializeStaking>,
@@ -271,12 +360,12 @@ pub mod memecoin_lending {
         instructions::staking::emergency_drain_rewards::emergency_drain_rewards_handler(ctx)
     }
     
-    /// Blacklist a token - blocks new loans (admin only)
+    /// Blacklist a token - blocks new loans (risk manager or admin)
     pub fn blacklist_token(ctx: Context<BlacklistToken>) -> Result<()> {
         instructions::admin::blacklist_token_handler(ctx)
     }
 
-    /// Remove token from blacklist (admin only)
+    /// Remove token from blacklist (risk manager or admin)
     pub fn unblacklist_token(ctx: Context<BlacklistToken>) -> Result<()> {
         instructions::admin::unblacklist_token_handler(ctx)
     }
@@ -12,6 +12,14 @@ pub const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
 pub const USER_STAKE_SEED: &[u8] = b"user_stake";
 pub const FEE_RECEIVER_SEED: &[u8] = b"fee_receiver";
 pub const USER_EXPOSURE_SEED: &[u8] = b"user_exposure";  // NEW!
+pub const PRICE_HISTORY_SEED: &[u8] = b"price_history";
+pub const PRICE_AUTHORITY_SET_SEED: &[u8] = b"price_authority_set";
+pub const AUCTION_SEED: &[u8] = b"auction";
+pub const INSURANCE_FUND_SEED: &[u8] = b"insurance_fund";
+pub const RISK_CONFIG_SEED: &[u8] = b"risk_config";
+pub const PENDING_CHANGE_SEED: &[u8] = b"pending_change";
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+pub const MULTISIG_PROPOSAL_SEED: &[u8] = b"multisig_proposal";
 
 // === PROTOCOL PARAMETERS ===
 pub const PROTOCOL_FEE_BPS: u16 = 200;           // 2% flat fee
@@ -19,9 +27,11 @@ pub const DEFAULT_LTV_BPS: u16 = 5000;           // 50% LTV
 pub const LIQUIDATION_THRESHOLD_BPS: u16 = 4000; // 40% drop triggers liquidation
 
 // === EXPOSURE LIMITS ===
+// Token and single-loan limits are RiskConfig defaults - the live values are on-chain
 pub const MAX_TOKEN_EXPOSURE_BPS: u16 = 1000;    // 10% of treasury per token
 pub const MAX_USER_EXPOSURE_BPS: u16 = 3000;     // 30% of treasury per user
 pub const MAX_SINGLE_LOAN_BPS: u16 = 1000;       // 10% of treasury per loan
+pub const MAX_APPROVED_PRICE_DEVIATION_BPS: u16 = 2000; // 20% approved price vs TWAP
 
 // === SLIPPAGE ===
 pub const DEFAULT_SLIPPAGE_BPS: u16 = 300;       // 3% default slippage
@@ -45,6 +55,10 @@ pub const BPS_DIVISOR: u64 = 10000;
 // === ADMIN TRANSFER ===
 pub const ADMIN_TRANSFER_DELAY: i64 = 48 * 60 * 60; // 48 hours in seconds
 
+// === PARAMETER CHANGE TIMELOCK ===
+pub const PARAMETER_CHANGE_DELAY: i64 = ADMIN_TRANSFER_DELAY;
+pub const PARAMETER_CHANGE_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60; // executable for 7 days after the delay
+
 // === LOAN FEE DISTRIBUTION (out of 10000) ===
 // These define how the 2% loan fee is split
 pub const LOAN_FEE_TREASURY_BPS: u16 = 5000;     // 50% of fee → Treasury (1.0% of loan)
@@ -58,8 +72,33 @@ pub const CREATOR_FEE_STAKING_BPS: u16 = 4000;      // 40%
 pub const CREATOR_FEE_OPERATIONS_BPS: u16 = 2000;   // 20%
 
 // === LIQUIDATION FEE DISTRIBUTION (out of 10000) ===
-pub const LIQUIDATION_TREASURY_BPS: u16 = 9500;     // 95%
-pub const LIQUIDATION_OPERATIONS_BPS: u16 = 500;    // 5%
+pub const LIQUIDATION_TREASURY_BPS: u16 = 9500;     // 95% of recovery above principal
+pub const LIQUIDATION_OPERATIONS_BPS: u16 = 500;    // 5% of recovery above principal
+
+// === LIQUIDATION PENALTY (out of 10000, charged on principal) ===
+pub const DEFAULT_LIQUIDATION_PENALTY_BPS: u16 = 500;  // 5%
+pub const MAX_LIQUIDATION_PENALTY_BPS: u16 = 2000;     // 20%
+
+// === PERMISSIONLESS LIQUIDATION ===
+pub const MAX_KEEPER_BONUS_BPS: u16 = 500;                       // 5% of sale proceeds
+pub const MAX_LIQUIDATOR_EXCLUSIVITY_SECONDS: i64 = 24 * 60 * 60; // 24 hours
+
+// === DUTCH AUCTION LIQUIDATION (out of 10000, relative to the TWAP at start) ===
+pub const AUCTION_START_PRICE_BPS: u64 = 11000;  // 110% - opens above market
+pub const AUCTION_FLOOR_PRICE_BPS: u64 = 5000;   // 50% - price stops falling here
+pub const AUCTION_DURATION_SECONDS: i64 = 60 * 60; // 1 hour from start to floor
+pub const AUCTION_SETTLEMENT_GRACE_SECONDS: i64 = 60 * 60; // 1 hour at the floor before it expires
+
+// === INSURANCE FUND (out of 10000, share of the loan fee) ===
+// Taken from the treasury's share, so it can be at most LOAN_FEE_TREASURY_BPS
+pub const MAX_INSURANCE_FEE_BPS: u16 = LOAN_FEE_TREASURY_BPS;
+
+// === RISK CONFIG BOUNDS (out of 10000) ===
+pub const MAX_RISK_EXPOSURE_LIMIT_BPS: u16 = 5000;    // token / single loan: at most 50% of treasury
+pub const MAX_RISK_SLIPPAGE_BPS: u16 = 2000;          // liquidation slippage: at most 20%
+pub const MAX_RISK_PRICE_DEVIATION_BPS: u16 = 5000;   // approved price vs TWAP: at most 50%
+pub const MIN_RISK_LIQUIDATION_BUFFER_BPS: u16 = 500; // liquidation must sit at least 5% above the LTV
+pub const MAX_RISK_LIQUIDATION_BUFFER_BPS: u16 = 9000;
 
 /// Global protocol state
 #[account]
@@ -77,7 +116,7 @@ pub struct ProtocolState {
     pub total_loans_created: u64,
     /// Total SOL borrowed across all loans
     pub total_sol_borrowed: u64,
-    /// Total fees earned by protocol
+    /// Loan fee revenue (repay, partial repay and extension fees) - excludes returned principal
     pub total_fees_earned: u64,
     /// Number of currently active loans
     pub active_loans_count: u64,
@@ -100,14 +139,46 @@ pub struct ProtocolState {
     pub pending_admin: Pubkey,
     /// Timestamp when admin transfer was initiated
     pub admin_transfer_timestamp: i64,
-    /// Authorized liquidator address (only this address can call liquidate)
+    /// Authorized liquidator address (the only liquidator unless permissionless liquidation is on)
     pub authorized_liquidator: Pubkey,
     /// Backend public key authorized to sign price approvals
     pub price_authority: Pubkey,
     /// Bump seed for PDA
     pub bump: u8,
+    /// Liquidation penalty on principal in basis points (kept by protocol, rest refunded to borrower)
+    pub liquidation_penalty_bps: u16,
+    /// Whether any signer may liquidate once the exclusivity window has passed
+    pub permissionless_liquidation: bool,
+    /// Share of sale proceeds paid to a permissionless liquidator in basis points (capped at the recovered penalty)
+    pub keeper_bonus_bps: u16,
+    /// Seconds after a loan becomes liquidatable during which only the authorized liquidator may act
+    pub liquidator_exclusivity_seconds: i64,
+    /// Share of each loan fee routed to the insurance fund in basis points
+    pub insurance_fee_bps: u16,
+    /// Principal lost on liquidations whose proceeds fell short of it
+    pub total_bad_debt: u64,
+    /// Part of total_bad_debt the insurance fund paid back to the treasury
+    pub total_insurance_payouts: u64,
+    /// Principal returned to the protocol by repayments and liquidations
+    pub total_principal_recovered: u64,
+    /// Liquidation proceeds kept by the protocol above the principal (fee + penalty)
+    pub total_liquidation_profit: u64,
+    /// Id of the next queued parameter change
+    pub change_nonce: u64,
+    /// May pause the protocol (not resume); default = unassigned
+    pub guardian: Pubkey,
+    /// May change token LTV / loan limits / risk overrides and blacklist; default = unassigned
+    pub risk_manager: Pubkey,
+    /// May withdraw from the treasury; default = unassigned
+    pub treasurer: Pubkey,
+    /// When the current pause started (0 = not paused)
+    pub paused_at: i64,
+    /// Seconds spent paused across all completed pauses - loans' due dates move back by this
+    pub total_paused_seconds: i64,
+    /// Set once the price authority set exists - loan prices must then come from its quorum
+    pub price_quorum_required: bool,
     /// Reserved for future upgrades
-    pub _reserved: [u8; 32],
+    pub _reserved: [u8; 7],
 }
 
 impl ProtocolState {
@@ -132,7 +203,38 @@ impl ProtocolState {
         32 + // authorized_liquidator
         32 + // price_authority
         1 + // bump
-        32; // _reserved
+        2 + // liquidation_penalty_bps
+        1 + // permissionless_liquidation
+        2 + // keeper_bonus_bps
+        8 + // liquidator_exclusivity_seconds
+        2 + // insurance_fee_bps
+        8 + // total_bad_debt
+        8 + // total_insurance_payouts
+        8 + // total_principal_recovered
+        8 + // total_liquidation_profit
+        8 + // change_nonce
+        32 + // guardian
+        32 + // risk_manager
+        32 + // treasurer
+        8 + // paused_at
+        8 + // total_paused_seconds
+        1 + // price_quorum_required
+        7; // _reserved
+
+    /// Current holder of a role (Pubkey::default() when unassigned)
+    pub fn role_holder(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Guardian => self.guardian,
+            Role::RiskManager => self.risk_manager,
+            Role::Treasurer => self.treasurer,
+        }
+    }
+
+    /// The admin holds every role; otherwise only the assigned holder does
+    pub fn has_role(&self, key: &Pubkey, role: Role) -> bool {
+        let holder = self.role_holder(role);
+        *key == self.admin || (holder != Pubkey::default() && *key == holder)
+    }
 }
 
 /// Token configuration for whitelisted tokens
@@ -169,6 +271,16 @@ pub struct TokenConfig {
     pub blacklisted: bool,
     /// Bump seed for PDA
     pub bump: u8,
+    /// Principal lost on this token's shortfall liquidations
+    pub total_bad_debt: u64,
+    /// Principal returned by this token's repayments and liquidations
+    pub total_principal_recovered: u64,
+    /// Liquidation proceeds kept above the principal on this token's loans
+    pub total_liquidation_profit: u64,
+    /// Loan fee revenue earned on this token's loans
+    pub total_fee_revenue: u64,
+    /// Per-token risk limits; zero fields fall back to RiskConfig
+    pub risk_overrides: RiskParams,
     /// Reserved for future use
     pub _reserved: [u8; 32],
 }
@@ -190,9 +302,18 @@ impl TokenConfig {
         1 + // is_protocol_token
         1 + // blacklisted
         1 + // bump
+        8 + // total_bad_debt
+        8 + // total_principal_recovered
+        8 + // total_liquidation_profit
+        8 + // total_fee_revenue
+        RiskParams::LEN + // risk_overrides
         32; // _reserved
 }
 
+/// Loan prices in lamports per whole token x PRICE_SCALE
+/// Loans opened before have version 0 (mixed legacy units) until their prices are recomputed
+pub const LOAN_PRICE_VERSION: u8 = 1;
+
 /// Individual loan account
 #[account]
 #[derive(Default)]
@@ -205,7 +326,7 @@ pub struct Loan {
     pub collateral_amount: u64,
     /// Amount of SOL borrowed
     pub sol_borrowed: u64,
-    /// Token price when loan was created (in SOL per token)
+    /// Token price when loan was created (in SOL per token, 0 = unknown for a migrated legacy loan)
     pub entry_price: u64,
     /// Price at which liquidation is triggered
     pub liquidation_price: u64,
@@ -219,8 +340,16 @@ pub struct Loan {
     pub index: u64,
     /// Bump seed for PDA
     pub bump: u8,
+    /// When the TWAP was first seen at or below liquidation_price (0 = not marked)
+    pub liquidatable_since: i64,
+    /// ProtocolState::total_paused_seconds when the loan was opened
+    pub paused_seconds_at_open: i64,
+    /// Unit of entry_price / liquidation_price (0 = legacy units, see LOAN_PRICE_VERSION)
+    pub price_version: u8,
+    /// Term the loan was opened for, the base of extension fees (0 = legacy, see original_term)
+    pub original_duration: i64,
     /// Reserved for future use
-    pub _reserved: [u8; 32],
+    pub _reserved: [u8; 7],
 }
 
 impl Loan {
@@ -236,7 +365,26 @@ impl Loan {
         1 + // status
         8 + // index
         1 + // bump
-        32; // _reserved
+        8 + // liquidatable_since
+        8 + // paused_seconds_at_open
+        1 + // price_version
+        8 + // original_duration
+        7; // _reserved
+
+    /// due_at pushed back by every protocol pause that ended since the loan was opened
+    pub fn effective_due_at(&self, protocol_state: &ProtocolState) -> i64 {
+        self.due_at.saturating_add(
+            protocol_state.total_paused_seconds.saturating_sub(self.paused_seconds_at_open),
+        )
+    }
+
+    /// Term the loan was opened for - legacy loans fall back to the current due_at - created_at
+    pub fn original_term(&self) -> u64 {
+        if self.original_duration > 0 {
+            return self.original_duration as u64;
+        }
+        self.due_at.saturating_sub(self.created_at) as u64
+    }
 }
 
 /// User exposure tracking account
@@ -274,10 +422,504 @@ impl UserExposure {
         32;  // _reserved
 }
 
+/// Number of checkpoints kept in each token's price history ring buffer
+pub const PRICE_HISTORY_CAPACITY: usize = 16;
+
+/// Price checkpoint for TWAP calculation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PriceCheckpoint {
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+/// Per-token price history
+/// Ring buffer of pool price checkpoints recorded by the permissionless record_price crank
+#[account]
+#[derive(Default)]
+pub struct PriceHistory {
+    /// Token mint this history belongs to
+    pub token_mint: Pubkey,
+    /// Ring buffer of checkpoints
+    pub checkpoints: [PriceCheckpoint; PRICE_HISTORY_CAPACITY],
+    /// Index the next checkpoint will be written to
+    pub head: u8,
+    /// Number of valid checkpoints (saturates at PRICE_HISTORY_CAPACITY)
+    pub count: u8,
+    /// Timestamp of the latest checkpoint
+    pub last_recorded_at: i64,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// Reserved for future use
+    pub _reserved: [u8; 32],
+}
+
+impl PriceHistory {
+    pub const LEN: usize = 8 +  // discriminator
+        32 + // token_mint
+        16 * PRICE_HISTORY_CAPACITY + // checkpoints
+        1 +  // head
+        1 +  // count
+        8 +  // last_recorded_at
+        1 +  // bump
+        32;  // _reserved
+}
+
+/// Maximum number of signers in the price authority set
+pub const MAX_PRICE_SIGNERS: usize = 8;
+
+/// M-of-N price authority set
+/// Loan prices are the median of at least `threshold` Ed25519 attestations from `signers`
+#[account]
+#[derive(Default)]
+pub struct PriceAuthoritySet {
+    /// Registered price signers (first `signer_count` entries are valid)
+    pub signers: [Pubkey; MAX_PRICE_SIGNERS],
+    /// Number of registered signers (N)
+    pub signer_count: u8,
+    /// Minimum number of agreeing attestations (M)
+    pub threshold: u8,
+    /// Attestations further than this from the median are discarded as outliers
+    pub max_deviation_bps: u16,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// Reserved for future use
+    pub _reserved: [u8; 32],
+}
+
+impl PriceAuthoritySet {
+    pub const LEN: usize = 8 +  // discriminator
+        32 * MAX_PRICE_SIGNERS + // signers
+        1 +  // signer_count
+        1 +  // threshold
+        2 +  // max_deviation_bps
+        1 +  // bump
+        32;  // _reserved
+
+    pub fn is_signer(&self, key: &Pubkey) -> bool {
+        self.signers[..self.signer_count as usize].contains(key)
+    }
+}
+
+/// Insurance fund - holds SOL (above its rent) that makes the treasury whole on shortfall liquidations
+#[account]
+#[derive(Default)]
+pub struct InsuranceFund {
+    /// SOL received from loan fees and direct deposits
+    pub total_deposited: u64,
+    /// SOL paid to the treasury to cover bad debt
+    pub total_paid_out: u64,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// Reserved for future use
+    pub _reserved: [u8; 32],
+}
+
+impl InsuranceFund {
+    pub const LEN: usize = 8 +  // discriminator
+        8 +  // total_deposited
+        8 +  // total_paid_out
+        1 +  // bump
+        32;  // _reserved
+}
+
+/// Tunable risk limits, all in basis points
+/// On TokenConfig a zero field means "use the global RiskConfig value"
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct RiskParams {
+    /// Max SOL borrowed against one token, share of treasury
+    pub max_token_exposure_bps: u16,
+    /// Max size of one loan, share of treasury
+    pub max_single_loan_bps: u16,
+    /// Max shortfall of liquidation proceeds below collateral value
+    pub max_liquidation_slippage_bps: u16,
+    /// Max gap between the approved price and the pool TWAP on create_loan
+    pub max_price_deviation_bps: u16,
+    /// Added to the LTV to place the liquidation price
+    pub liquidation_buffer_bps: u16,
+}
+
+impl RiskParams {
+    pub const LEN: usize = 2 * 5;
+
+    /// Compile-time defaults a fresh RiskConfig starts from
+    pub const DEFAULT: Self = Self {
+        max_token_exposure_bps: MAX_TOKEN_EXPOSURE_BPS,
+        max_single_loan_bps: MAX_SINGLE_LOAN_BPS,
+        max_liquidation_slippage_bps: crate::utils::MAX_LIQUIDATION_SLIPPAGE_BPS as u16,
+        max_price_deviation_bps: MAX_APPROVED_PRICE_DEVIATION_BPS,
+        liquidation_buffer_bps: crate::utils::LOAN_LIQUIDATION_BUFFER_BPS,
+    };
+
+    /// Apply a token's overrides on top of these values
+    pub fn with_overrides(&self, overrides: &RiskParams) -> RiskParams {
+        let pick = |global: u16, local: u16| if local == 0 { global } else { local };
+        RiskParams {
+            max_token_exposure_bps: pick(self.max_token_exposure_bps, overrides.max_token_exposure_bps),
+            max_single_loan_bps: pick(self.max_single_loan_bps, overrides.max_single_loan_bps),
+            max_liquidation_slippage_bps: pick(
+                self.max_liquidation_slippage_bps,
+                overrides.max_liquidation_slippage_bps,
+            ),
+            max_price_deviation_bps: pick(self.max_price_deviation_bps, overrides.max_price_deviation_bps),
+            liquidation_buffer_bps: pick(self.liquidation_buffer_bps, overrides.liquidation_buffer_bps),
+        }
+    }
+}
+
+/// Global risk limits - replaces compile-time exposure, slippage and buffer constants
+#[account]
+#[derive(Default)]
+pub struct RiskConfig {
+    pub params: RiskParams,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// Reserved for future use
+    pub _reserved: [u8; 32],
+}
+
+impl RiskConfig {
+    pub const LEN: usize = 8 +  // discriminator
+        RiskParams::LEN +  // params
+        1 +  // bump
+        32;  // _reserved
+
+    /// Effective limits for a token
+    pub fn for_token(&self, token_config: &TokenConfig) -> RiskParams {
+        self.params.with_overrides(&token_config.risk_overrides)
+    }
+}
+
+/// Admin parameter change that only takes effect after PARAMETER_CHANGE_DELAY
+/// `None` fields are left unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParameterChange {
+    Fees {
+        protocol_fee_bps: Option<u16>,
+        treasury_fee_bps: Option<u16>,
+        buyback_fee_bps: Option<u16>,
+        operations_fee_bps: Option<u16>,
+        liquidation_penalty_bps: Option<u16>,
+    },
+    Wallets {
+        buyback_wallet: Option<Pubkey>,
+        operations_wallet: Option<Pubkey>,
+    },
+    Liquidator {
+        new_liquidator: Pubkey,
+    },
+    TokenConfig {
+        mint: Pubkey,
+        enabled: Option<bool>,
+        ltv_bps: Option<u16>,
+        pool_address: Option<Pubkey>,
+        pool_type: Option<PoolType>,
+        min_loan_amount: Option<u64>,
+        max_loan_amount: Option<u64>,
+    },
+    RiskConfig {
+        params: RiskParams,
+    },
+    TokenRiskOverrides {
+        mint: Pubkey,
+        overrides: RiskParams,
+    },
+    LiquidationSettings {
+        permissionless_liquidation: Option<bool>,
+        keeper_bonus_bps: Option<u16>,
+        liquidator_exclusivity_seconds: Option<i64>,
+    },
+    InsuranceFee {
+        insurance_fee_bps: u16,
+    },
+    Role {
+        role: Role,
+        /// New holder - revocations skip the timelock through revoke_role
+        holder: Pubkey,
+    },
+}
+
+impl ParameterChange {
+    /// Largest variant: TokenConfig
+    pub const MAX_LEN: usize = 1 + // variant
+        32 + // mint
+        2 + // enabled
+        3 + // ltv_bps
+        33 + // pool_address
+        2 + // pool_type
+        9 + // min_loan_amount
+        9; // max_loan_amount
+
+    /// Token config and risk override changes are within the risk manager's remit; everything else is admin only
+    pub fn required_role(&self) -> Option<Role> {
+        match self {
+            ParameterChange::TokenConfig { .. } | ParameterChange::TokenRiskOverrides { .. } => {
+                Some(Role::RiskManager)
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A queued parameter change, executable between execute_after and the end of the grace period
+#[account]
+pub struct PendingChange {
+    /// Sequential id from ProtocolState::change_nonce (PDA seed)
+    pub id: u64,
+    pub change: ParameterChange,
+    /// Signer that queued the change - receives the rent back
+    pub proposed_by: Pubkey,
+    pub proposed_at: i64,
+    pub execute_after: i64,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PendingChange {
+    pub const LEN: usize = 8 +  // discriminator
+        8 +  // id
+        ParameterChange::MAX_LEN +  // change
+        32 + // proposed_by
+        8 +  // proposed_at
+        8 +  // execute_after
+        1;   // bump
+
+    /// Last timestamp at which the change may still be executed
+    pub fn expires_at(&self) -> i64 {
+        self.execute_after.saturating_add(PARAMETER_CHANGE_GRACE_PERIOD)
+    }
+}
+
+/// Maximum number of multisig signers
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+/// M-of-N signer set that must approve critical admin actions
+#[account]
+#[derive(Default)]
+pub struct Multisig {
+    /// Registered signers (first `signer_count` entries are valid)
+    pub signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+    /// Number of registered signers (N)
+    pub signer_count: u8,
+    /// Approvals required to execute a proposal (M)
+    pub threshold: u8,
+    /// Id of the next proposal (PDA seed)
+    pub proposal_nonce: u64,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// When the signer set last changed - older proposals can no longer be approved or executed
+    pub updated_at: i64,
+    /// Reserved for future use
+    pub _reserved: [u8; 24],
+}
+
+impl Multisig {
+    pub const LEN: usize = 8 +  // discriminator
+        32 * MAX_MULTISIG_SIGNERS + // signers
+        1 +  // signer_count
+        1 +  // threshold
+        8 +  // proposal_nonce
+        1 +  // bump
+        8 +  // updated_at
+        24;  // _reserved
+
+    pub fn signer_index(&self, key: &Pubkey) -> Option<usize> {
+        self.signers[..self.signer_count as usize].iter().position(|s| s == key)
+    }
+
+    /// Approval bits index the signer set, so they only count against the set they were cast for
+    pub fn is_current(&self, proposal: &MultisigProposal) -> bool {
+        proposal.proposed_at > self.updated_at
+    }
+}
+
+/// Critical admin action that only runs once the multisig threshold approves it
+// Stored inline in a fixed-size proposal account, so the large UpdateMultisig variant is not boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MultisigAction {
+    /// Pause and send the whole treasury to `recipient`
+    EmergencyDrain { recipient: Pubkey },
+    WithdrawTreasury { amount: u64, recipient: Pubkey },
+    InitiateAdminTransfer { new_admin: Pubkey },
+    UpdatePriceAuthority { new_price_authority: Pubkey },
+    RotatePriceSigner { old_signer: Pubkey, new_signer: Pubkey },
+    UpdatePriceQuorum { threshold: Option<u8>, max_deviation_bps: Option<u16> },
+    /// Replace the multisig's own signers (first `signer_count` entries) and threshold
+    UpdateMultisig { signers: [Pubkey; MAX_MULTISIG_SIGNERS], signer_count: u8, threshold: u8 },
+}
+
+impl MultisigAction {
+    /// Largest variant: UpdateMultisig
+    pub const MAX_LEN: usize = 1 + // variant
+        32 * MAX_MULTISIG_SIGNERS + // signers
+        1 + // signer_count
+        1; // threshold
+
+    /// Price authority, quorum and signer set changes keep the parameter change timelock on top of the approvals
+    pub fn delay(&self) -> i64 {
+        match self {
+            MultisigAction::UpdatePriceAuthority { .. }
+            | MultisigAction::RotatePriceSigner { .. }
+            | MultisigAction::UpdatePriceQuorum { .. }
+            | MultisigAction::UpdateMultisig { .. } => PARAMETER_CHANGE_DELAY,
+            _ => 0,
+        }
+    }
+}
+
+/// A multisig proposal collecting approvals across transactions
+#[account]
+pub struct MultisigProposal {
+    /// Sequential id from Multisig::proposal_nonce (PDA seed)
+    pub id: u64,
+    pub action: MultisigAction,
+    /// Signer that opened the proposal - receives the rent back
+    pub proposed_by: Pubkey,
+    pub proposed_at: i64,
+    pub execute_after: i64,
+    /// Bitmask over Multisig::signers indices
+    pub approvals: u16,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl MultisigProposal {
+    pub const LEN: usize = 8 +  // discriminator
+        8 +  // id
+        MultisigAction::MAX_LEN +  // action
+        32 + // proposed_by
+        8 +  // proposed_at
+        8 +  // execute_after
+        2 +  // approvals
+        1;   // bump
+
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.count_ones() as u8
+    }
+
+    /// Last timestamp at which the proposal may still be executed
+    pub fn expires_at(&self) -> i64 {
+        self.execute_after.saturating_add(PARAMETER_CHANGE_GRACE_PERIOD)
+    }
+}
+
+/// Descending-price auction of a liquidatable loan's collateral
+/// Holds bidders' SOL until settlement
+#[account]
+#[derive(Default)]
+pub struct LiquidationAuction {
+    /// Loan being liquidated
+    pub loan: Pubkey,
+    /// Collateral mint
+    pub token_mint: Pubkey,
+    /// Receives the account rent back at settlement
+    pub initiator: Pubkey,
+    /// LiquidatedTime or LiquidatedPrice - applied to the loan at settlement
+    pub reason: LoanStatus,
+    /// Opening price (lamports per whole token x PRICE_SCALE)
+    pub start_price: u64,
+    /// Price the auction decays to and then holds
+    pub floor_price: u64,
+    /// When the auction started
+    pub started_at: i64,
+    /// Seconds from start_price to floor_price
+    pub duration: i64,
+    /// Principal + protocol fee + liquidation penalty to be covered
+    pub debt: u64,
+    /// Collateral still in the vault
+    pub collateral_remaining: u64,
+    /// SOL paid by bidders so far
+    pub sol_raised: u64,
+    /// Bump seed for PDA
+    pub bump: u8,
+    /// ProtocolState::total_paused_seconds when the auction started
+    pub paused_seconds_at_start: i64,
+    /// Reserved for future use
+    pub _reserved: [u8; 24],
+}
+
+impl LiquidationAuction {
+    pub const LEN: usize = 8 +  // discriminator
+        32 + // loan
+        32 + // token_mint
+        32 + // initiator
+        1 +  // reason
+        8 +  // start_price
+        8 +  // floor_price
+        8 +  // started_at
+        8 +  // duration
+        8 +  // debt
+        8 +  // collateral_remaining
+        8 +  // sol_raised
+        1 +  // bump
+        8 +  // paused_seconds_at_start
+        24;  // _reserved
+
+    /// Seconds since the start, not counting protocol pauses (including one in progress)
+    pub fn elapsed(&self, protocol_state: &ProtocolState, now: i64) -> i64 {
+        let ongoing_pause = if protocol_state.paused_at > 0 {
+            now.saturating_sub(protocol_state.paused_at).max(0)
+        } else {
+            0
+        };
+        let paused = protocol_state
+            .total_paused_seconds
+            .saturating_sub(self.paused_seconds_at_start)
+            .saturating_add(ongoing_pause);
+        now.saturating_sub(self.started_at).saturating_sub(paused).max(0)
+    }
+
+    /// Linear decay from start_price to floor_price over `duration`, then flat.
+    /// The clock stops while the protocol is paused, since nobody can bid then
+    pub fn current_price(&self, protocol_state: &ProtocolState, now: i64) -> u64 {
+        let elapsed = self.elapsed(protocol_state, now);
+        if elapsed >= self.duration {
+            return self.floor_price;
+        }
+        let drop = (self.start_price - self.floor_price) as u128 * elapsed as u128 / self.duration as u128;
+        self.start_price - drop as u64
+    }
+
+    /// Debt still to be covered by bids
+    pub fn remaining_debt(&self) -> u64 {
+        self.debt.saturating_sub(self.sol_raised)
+    }
+
+    /// Split bidders' SOL into (debt recovered, surplus owed to the borrower)
+    pub fn settlement_proceeds(&self) -> (u64, u64) {
+        let debt_recovered = self.sol_raised.min(self.debt);
+        (debt_recovered, self.sol_raised - debt_recovered)
+    }
+
+    /// Bidding closes once the price has sat at the floor for AUCTION_SETTLEMENT_GRACE_SECONDS
+    pub fn is_expired(&self, protocol_state: &ProtocolState, now: i64) -> bool {
+        self.elapsed(protocol_state, now)
+            >= self.duration.saturating_add(AUCTION_SETTLEMENT_GRACE_SECONDS)
+    }
+
+    /// Settlement is possible once the debt is covered, the collateral is gone or the auction expired
+    pub fn is_settleable(&self, protocol_state: &ProtocolState, now: i64) -> bool {
+        self.remaining_debt() == 0
+            || self.collateral_remaining == 0
+            || self.is_expired(protocol_state, now)
+    }
+}
+
 /// UserStake account discriminator (first 8 bytes)
 /// This is SHA256("account:UserStake")[..8]
 pub const USER_STAKE_DISCRIMINATOR: [u8; 8] = [102, 53, 163, 107, 9, 138, 87, 153];
 
+/// Delegated admin roles
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Pause only
+    Guardian,
+    /// Token LTV / loan limits / risk overrides and blacklist
+    RiskManager,
+    /// Treasury withdrawals
+    Treasurer,
+}
+
 /// Pool type enum for different AMM protocols
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum PoolType {
@@ -285,6 +927,28 @@ pub enum PoolType {
     Orca = 1,
     Pumpfun = 2,
     PumpSwap = 3,
+    RaydiumCpmm = 4,
+    RaydiumClmm = 5,
+    MeteoraDlmm = 6,
+    /// Meteora DAMM v2 only - DAMM v1 pools are not supported
+    MeteoraDammV2 = 7,
+}
+
+impl PoolType {
+    /// Parse the u8 pool type used in instruction arguments
+    pub fn from_u8(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(PoolType::Raydium),
+            1 => Ok(PoolType::Orca),
+            2 => Ok(PoolType::Pumpfun),
+            3 => Ok(PoolType::PumpSwap),
+            4 => Ok(PoolType::RaydiumCpmm),
+            5 => Ok(PoolType::RaydiumClmm),
+            6 => Ok(PoolType::MeteoraDlmm),
+            7 => Ok(PoolType::MeteoraDammV2),
+            _ => Err(crate::error::LendingError::InvalidPoolType.into()),
+        }
+    }
 }
 
 impl Default for PoolType {
@@ -314,6 +978,8 @@ pub enum LoanStatus {
     Repaid = 1,
     LiquidatedTime = 2,
     LiquidatedPrice = 3,
+    /// Collateral is being sold by a Dutch auction - settles to LiquidatedTime / LiquidatedPrice
+    InAuction = 4,
 }
 
 impl Default for LoanStatus {
@@ -1,4 +1,7 @@
 pub mod jupiter;
-// pub mod pumpfun; // Disabled - not supported for liquidation
+pub mod pumpfun;
+pub mod pumpswap;
 
-pub use jupiter::*;
\ No newline at end of file
+pub use jupiter::*;
+pub use pumpfun::*;
+pub use pumpswap::*;
\ No newline at end of file
@@ -1,2 +1,121 @@
-//! PumpFun swap - disabled (tokens must migrate to Raydium/Jupiter first)
-//! This module is a placeholder for potential future PumpFun integration.
\ No newline at end of file
+//! PumpFun native sell - liquidates tokens that are still on their bonding curve
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+use crate::error::LendingError;
+use crate::state::BPS_DIVISOR;
+use crate::utils::{PumpfunCurveValidator, SafeMath, PUMPFUN_PROGRAM_ID};
+
+/// SHA256("global:sell")[..8]
+pub const PUMPFUN_SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// Protocol + creator fee taken from the SOL output of a curve sell
+pub const PUMPFUN_SELL_FEE_BPS: u64 = 125;
+
+// === SELL ACCOUNT LAYOUT (from IDL) ===
+// Indices into the sell accounts (remaining_accounts after the PumpFun program)
+// 0 global, 1 fee_recipient, 2 mint, 3 bonding_curve, 4 associated_bonding_curve,
+// 5 associated_user, 6 user, 7 system_program, 8 creator_vault,
+// 9 token_program, 10 event_authority, 11 program,
+// 12.. fee config and any accounts added by later program versions (passed through)
+pub const CURVE_SELL_MINT_INDEX: usize = 2;
+pub const CURVE_SELL_BONDING_CURVE_INDEX: usize = 3;
+pub const CURVE_SELL_USER_TOKEN_INDEX: usize = 5;
+pub const CURVE_SELL_USER_INDEX: usize = 6;
+pub const PUMPFUN_SELL_MIN_ACCOUNTS: usize = 12;
+
+/// Expected SOL out of a bonding curve sell, after PumpFun fees
+pub fn calculate_curve_sell_output(virtual_token: u64, virtual_sol: u64, token_amount_in: u64) -> Result<u64> {
+    require!(virtual_token > 0 && virtual_sol > 0, LendingError::InvalidPriceFeed);
+
+    // sol_out = virtual_sol * amount_in / (virtual_token + amount_in)
+    let gross = SafeMath::mul_div_u128(
+        virtual_sol as u128,
+        token_amount_in as u128,
+        (virtual_token as u128)
+            .checked_add(token_amount_in as u128)
+            .ok_or(LendingError::MathOverflow)?,
+    )? as u64;
+
+    SafeMath::mul_div(gross, BPS_DIVISOR - PUMPFUN_SELL_FEE_BPS, BPS_DIVISOR)
+}
+
+/// Validate the sell accounts against the loan's bonding curve, collateral vault and vault authority
+/// Returns the expected SOL output for `token_amount_in` at the current curve reserves
+pub fn validate_pumpfun_sell_accounts(
+    pumpfun_program: &AccountInfo,
+    sell_accounts: &[AccountInfo],
+    bonding_curve_address: &Pubkey,
+    token_mint: &Pubkey,
+    vault_authority: &Pubkey,
+    vault_token_account: &Pubkey,
+    token_amount_in: u64,
+) -> Result<u64> {
+    require!(
+        pumpfun_program.key() == PUMPFUN_PROGRAM_ID && pumpfun_program.executable,
+        LendingError::InvalidPoolOwner
+    );
+    require!(sell_accounts.len() >= PUMPFUN_SELL_MIN_ACCOUNTS, LendingError::MissingPumpfunAccounts);
+
+    let bonding_curve = &sell_accounts[CURVE_SELL_BONDING_CURVE_INDEX];
+    require!(bonding_curve.key() == *bonding_curve_address, LendingError::InvalidPoolAddress);
+    PumpfunCurveValidator::validate_full(bonding_curve, token_mint)?;
+
+    require!(sell_accounts[CURVE_SELL_MINT_INDEX].key() == *token_mint, LendingError::PoolTokenMismatch);
+
+    // Sell from the loan's collateral vault; SOL is paid straight to the vault authority
+    require!(sell_accounts[CURVE_SELL_USER_INDEX].key() == *vault_authority, LendingError::InvalidPDA);
+    require!(
+        sell_accounts[CURVE_SELL_USER_TOKEN_INDEX].key() == *vault_token_account,
+        LendingError::InvalidAccountData
+    );
+
+    let curve_data = bonding_curve.try_borrow_data()?;
+    // Completed curves reject sells - the token config must be synced to its PumpSwap pool
+    require!(
+        !PumpfunCurveValidator::is_complete(&curve_data)?,
+        LendingError::PumpfunCurveComplete
+    );
+    let (virtual_token, virtual_sol) = PumpfunCurveValidator::read_virtual_reserves(&curve_data)?;
+
+    calculate_curve_sell_output(virtual_token, virtual_sol, token_amount_in)
+}
+
+/// Execute PumpFun sell via CPI, signed by the vault authority
+pub fn execute_pumpfun_sell<'info>(
+    pumpfun_program: &AccountInfo<'info>,
+    sell_accounts: &[AccountInfo<'info>],
+    token_amount_in: u64,
+    min_sol_output: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let accounts_meta: Vec<AccountMeta> = sell_accounts
+        .iter()
+        .enumerate()
+        .map(|(i, acc)| {
+            let is_signer = acc.is_signer || i == CURVE_SELL_USER_INDEX;
+            if acc.is_writable {
+                AccountMeta::new(acc.key(), is_signer)
+            } else {
+                AccountMeta::new_readonly(acc.key(), is_signer)
+            }
+        })
+        .collect();
+
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&PUMPFUN_SELL_DISCRIMINATOR);
+    data.extend_from_slice(&token_amount_in.to_le_bytes());
+    data.extend_from_slice(&min_sol_output.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: pumpfun_program.key(),
+        accounts: accounts_meta,
+        data,
+    };
+
+    let mut account_infos = vec![pumpfun_program.clone()];
+    account_infos.extend(sell_accounts.iter().cloned());
+
+    invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
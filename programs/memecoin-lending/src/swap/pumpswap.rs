@@ -0,0 +1,142 @@
+//! PumpSwap native sell - liquidates migrated pump.fun tokens without an off-chain Jupiter route
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+use crate::error::LendingError;
+use crate::state::BPS_DIVISOR;
+use crate::utils::{PriceFeedUtils, PumpSwapPoolValidator, SafeMath, PUMPSWAP_PROGRAM_ID, WSOL_MINT};
+
+/// SHA256("global:sell")[..8]
+pub const PUMPSWAP_SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+/// LP + protocol + coin creator fee taken from the quote output of a sell
+pub const PUMPSWAP_SELL_FEE_BPS: u64 = 30;
+
+// === SELL ACCOUNT LAYOUT (from IDL) ===
+// Indices into the sell accounts (remaining_accounts after the PumpSwap program)
+// 0 pool, 1 user, 2 global_config, 3 base_mint, 4 quote_mint,
+// 5 user_base_token_account, 6 user_quote_token_account,
+// 7 pool_base_token_account, 8 pool_quote_token_account,
+// 9 protocol_fee_recipient, 10 protocol_fee_recipient_token_account,
+// 11 base_token_program, 12 quote_token_program, 13 system_program,
+// 14 associated_token_program, 15 event_authority, 16 program,
+// 17.. coin creator vault and any accounts added by later program versions (passed through)
+pub const SELL_POOL_INDEX: usize = 0;
+pub const SELL_USER_INDEX: usize = 1;
+pub const SELL_BASE_MINT_INDEX: usize = 3;
+pub const SELL_QUOTE_MINT_INDEX: usize = 4;
+pub const SELL_USER_BASE_TOKEN_INDEX: usize = 5;
+pub const SELL_USER_QUOTE_TOKEN_INDEX: usize = 6;
+pub const SELL_POOL_BASE_VAULT_INDEX: usize = 7;
+pub const SELL_POOL_QUOTE_VAULT_INDEX: usize = 8;
+pub const SELL_QUOTE_TOKEN_PROGRAM_INDEX: usize = 12;
+pub const PUMPSWAP_SELL_MIN_ACCOUNTS: usize = 17;
+
+/// Expected SOL out of a constant-product sell, after PumpSwap fees
+pub fn calculate_sell_output(base_reserve: u64, quote_reserve: u64, base_amount_in: u64) -> Result<u64> {
+    require!(base_reserve > 0 && quote_reserve > 0, LendingError::InvalidPriceFeed);
+
+    // quote_out = quote_reserve * amount_in / (base_reserve + amount_in)
+    let gross = SafeMath::mul_div_u128(
+        quote_reserve as u128,
+        base_amount_in as u128,
+        (base_reserve as u128)
+            .checked_add(base_amount_in as u128)
+            .ok_or(LendingError::MathOverflow)?,
+    )? as u64;
+
+    SafeMath::mul_div(gross, BPS_DIVISOR - PUMPSWAP_SELL_FEE_BPS, BPS_DIVISOR)
+}
+
+/// Validate the sell accounts against the loan's pool, collateral vault and vault authority
+/// Returns the expected SOL output for `base_amount_in` at the current pool reserves
+pub fn validate_pumpswap_sell_accounts(
+    pumpswap_program: &AccountInfo,
+    sell_accounts: &[AccountInfo],
+    pool_address: &Pubkey,
+    token_mint: &Pubkey,
+    vault_authority: &Pubkey,
+    vault_token_account: &Pubkey,
+    base_amount_in: u64,
+) -> Result<u64> {
+    require!(
+        pumpswap_program.key() == PUMPSWAP_PROGRAM_ID && pumpswap_program.executable,
+        LendingError::InvalidPoolOwner
+    );
+    require!(sell_accounts.len() >= PUMPSWAP_SELL_MIN_ACCOUNTS, LendingError::MissingPumpSwapVaults);
+
+    let pool = &sell_accounts[SELL_POOL_INDEX];
+    require!(pool.key() == *pool_address, LendingError::InvalidPoolAddress);
+
+    let (base_vault, quote_vault) = PumpSwapPoolValidator::validate_full(pool, token_mint)?;
+    let pool_base_vault = &sell_accounts[SELL_POOL_BASE_VAULT_INDEX];
+    let pool_quote_vault = &sell_accounts[SELL_POOL_QUOTE_VAULT_INDEX];
+    require!(
+        pool_base_vault.key() == base_vault && pool_quote_vault.key() == quote_vault,
+        LendingError::InvalidPumpSwapVault
+    );
+
+    require!(sell_accounts[SELL_BASE_MINT_INDEX].key() == *token_mint, LendingError::PoolTokenMismatch);
+    require!(sell_accounts[SELL_QUOTE_MINT_INDEX].key() == WSOL_MINT, LendingError::InvalidQuoteMint);
+
+    // Sell from the loan's collateral vault, signed by the vault authority
+    require!(sell_accounts[SELL_USER_INDEX].key() == *vault_authority, LendingError::InvalidPDA);
+    require!(
+        sell_accounts[SELL_USER_BASE_TOKEN_INDEX].key() == *vault_token_account,
+        LendingError::InvalidAccountData
+    );
+
+    // WSOL proceeds must land in a token account the vault authority controls
+    let user_quote = &sell_accounts[SELL_USER_QUOTE_TOKEN_INDEX];
+    {
+        let data = user_quote.try_borrow_data()?;
+        require!(data.len() >= 72, LendingError::InvalidAccountData);
+        require!(data[0..32] == WSOL_MINT.to_bytes(), LendingError::InvalidQuoteMint);
+        require!(data[32..64] == vault_authority.to_bytes(), LendingError::InvalidTokenAccountOwner);
+    }
+
+    calculate_sell_output(
+        PriceFeedUtils::read_token_account_amount(pool_base_vault)?,
+        PriceFeedUtils::read_token_account_amount(pool_quote_vault)?,
+        base_amount_in,
+    )
+}
+
+/// Execute PumpSwap sell via CPI, signed by the vault authority
+pub fn execute_pumpswap_sell<'info>(
+    pumpswap_program: &AccountInfo<'info>,
+    sell_accounts: &[AccountInfo<'info>],
+    base_amount_in: u64,
+    min_quote_amount_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let accounts_meta: Vec<AccountMeta> = sell_accounts
+        .iter()
+        .enumerate()
+        .map(|(i, acc)| {
+            let is_signer = acc.is_signer || i == SELL_USER_INDEX;
+            if acc.is_writable {
+                AccountMeta::new(acc.key(), is_signer)
+            } else {
+                AccountMeta::new_readonly(acc.key(), is_signer)
+            }
+        })
+        .collect();
+
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&PUMPSWAP_SELL_DISCRIMINATOR);
+    data.extend_from_slice(&base_amount_in.to_le_bytes());
+    data.extend_from_slice(&min_quote_amount_out.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: pumpswap_program.key(),
+        accounts: accounts_meta,
+        data,
+    };
+
+    let mut account_infos = vec![pumpswap_program.clone()];
+    account_infos.extend(sell_accounts.iter().cloned());
+
+    invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::TokenAccount;
 use crate::error::LendingError;
+use crate::events::BadDebtRecorded;
 use crate::state::*;
 use anchor_lang::solana_program::pubkey;
 
@@ -27,7 +28,8 @@ pub const PRICE_STALENESS_THRESHOLD: i64 = 60; // 60 seconds
 pub const MAX_PRICE_DEVIATION_BPS: u64 = 500; // 5%
 
 // === PRICE CONSTANTS ===
-/// Price scaling factor (10^9 for lamport precision)
+/// Price scaling factor (10^6)
+/// Canonical prices are lamports per whole token (10^decimals raw units) * PRICE_SCALE
 pub const PRICE_SCALE: u128 = 1_000_000;
 
 /// Price scaling for calculations (10^6)
@@ -54,7 +56,13 @@ pub const MAX_LTV_PENALTY_BPS: u64 = 2500;
 /// Default liquidation buffer in basis points (3%)
 pub const DEFAULT_LIQUIDATION_BUFFER_BPS: u16 = 300;
 
-/// Maximum allowed slippage for liquidations in basis points (5%)
+/// Default liquidation buffer for RiskConfig in basis points (40% drop triggers liquidation)
+pub const LOAN_LIQUIDATION_BUFFER_BPS: u16 = 4000;
+
+/// Health factor a partial liquidation restores the loan to (1.25 = 12500 bps)
+pub const PARTIAL_LIQUIDATION_TARGET_HEALTH_BPS: u64 = 12500;
+
+/// Default maximum slippage for liquidations in basis points (5%) - live value in RiskConfig
 pub const MAX_LIQUIDATION_SLIPPAGE_BPS: u64 = 500;
 
 /// Minimum collateral value in lamports (0.01 SOL = 10_000_000 lamports)
@@ -66,11 +74,16 @@ pub const MIN_STAKE_AMOUNT: u64 = 1_000_000;
 /// Maximum effective LTV + buffer to ensure liquidation profit (90% = 9000 bps)
 pub const MAX_EFFECTIVE_LIQUIDATION_LTV_BPS: u64 = 9000;
 
+/// LTV cap for tokens still on a pump.fun bonding curve (20% = 2000 bps)
+/// Curve liquidity is thin and the price is set by virtual reserves, so they borrow less
+pub const PUMPFUN_MAX_LTV_BPS: u16 = 2000;
+
 /// Maximum age for price signatures (30 seconds)
 pub const MAX_PRICE_SIGNATURE_AGE_SECONDS: i64 = 30;
 
 /// Price signature message prefix for domain separation
-pub const PRICE_SIGNATURE_PREFIX: &[u8] = b"MCLEND_PRICE_V1";
+/// V2 adds the action tag after the prefix
+pub const PRICE_SIGNATURE_PREFIX: &[u8] = b"MCLEND_PRICE_V2";
 
 // === POOL DATA OFFSETS (Raydium AMM V4) ===
 pub const RAYDIUM_TOKEN_A_AMOUNT_OFFSET: usize = 224;
@@ -80,9 +93,31 @@ pub const RAYDIUM_TOKEN_B_MINT_OFFSET: usize = 432;
 pub const RAYDIUM_MIN_DATA_LEN: usize = 464;
 
 // === POOL DATA OFFSETS (PumpFun) ===
+// BondingCurve Layout (from IDL)
+// Discriminator: 8 bytes
+// virtual_token_reserves: u64 (offset 8)
+// virtual_sol_reserves: u64 (offset 16)
+// real_token_reserves: u64 (offset 24)
+// real_sol_reserves: u64 (offset 32)
+// token_total_supply: u64 (offset 40)
+// complete: bool (offset 48)
 pub const PUMPFUN_VIRTUAL_TOKEN_OFFSET: usize = 8;
 pub const PUMPFUN_VIRTUAL_SOL_OFFSET: usize = 16;
 pub const PUMPFUN_MIN_DATA_LEN: usize = 24;
+pub const PUMPFUN_COMPLETE_OFFSET: usize = 48;
+pub const PUMPFUN_CURVE_MIN_LEN: usize = 49;
+
+pub const PUMPFUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+
+/// SHA256("account:BondingCurve")[..8]
+pub const PUMPFUN_BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+pub const PUMPFUN_BONDING_CURVE_SEED: &[u8] = b"bonding-curve";
+
+/// pump.fun PDA that creates the token's PumpSwap pool when the curve completes
+pub const PUMPFUN_POOL_AUTHORITY_SEED: &[u8] = b"pool-authority";
+pub const PUMPSWAP_POOL_SEED: &[u8] = b"pool";
+/// Index of the canonical pool created by migration
+pub const PUMPSWAP_CANONICAL_POOL_INDEX: u16 = 0;
 
 // === POOL DATA OFFSETS (PumpSwap) ===
 // PumpSwap Pool Layout (from IDL)
@@ -109,16 +144,140 @@ pub const PUMPSWAP_POOL_BASE_VAULT_OFFSET: usize = 139;
 pub const PUMPSWAP_POOL_QUOTE_VAULT_OFFSET: usize = 171;
 pub const PUMPSWAP_POOL_MIN_LEN: usize = 211;
 
-/// TWAP configuration
+// === POOL DATA OFFSETS (Orca Whirlpool) ===
+// Whirlpool Layout (from IDL)
+// Discriminator: 8 bytes
+// whirlpools_config: Pubkey (offset 8)
+// whirlpool_bump: [u8; 1] (offset 40)
+// tick_spacing: u16 (offset 41)
+// fee_tier_index_seed: [u8; 2] (offset 43)
+// fee_rate: u16 (offset 45)
+// protocol_fee_rate: u16 (offset 47)
+// liquidity: u128 (offset 49)
+// sqrt_price: u128 Q64.64 (offset 65)
+// tick_current_index: i32 (offset 81)
+// protocol_fee_owed_a/b: u64 (offset 85/93)
+// token_mint_a: Pubkey (offset 101)
+// token_vault_a: Pubkey (offset 133)
+// fee_growth_global_a: u128 (offset 165)
+// token_mint_b: Pubkey (offset 181)
+// token_vault_b: Pubkey (offset 213)
+pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// SHA256("account:Whirlpool")[..8]
+pub const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+pub const WHIRLPOOL_SQRT_PRICE_OFFSET: usize = 65;
+pub const WHIRLPOOL_TOKEN_MINT_A_OFFSET: usize = 101;
+pub const WHIRLPOOL_TOKEN_VAULT_A_OFFSET: usize = 133;
+pub const WHIRLPOOL_TOKEN_MINT_B_OFFSET: usize = 181;
+pub const WHIRLPOOL_TOKEN_VAULT_B_OFFSET: usize = 213;
+pub const WHIRLPOOL_MIN_LEN: usize = 245;
+
+// === POOL DATA OFFSETS (Raydium CPMM) ===
+// CPMM PoolState Layout (from IDL)
+// Discriminator: 8 bytes
+// amm_config: Pubkey (offset 8)
+// pool_creator: Pubkey (offset 40)
+// token_0_vault: Pubkey (offset 72)
+// token_1_vault: Pubkey (offset 104)
+// lp_mint: Pubkey (offset 136)
+// token_0_mint: Pubkey (offset 168)
+// token_1_mint: Pubkey (offset 200)
+// token_0_program / token_1_program / observation_key: Pubkey (offset 232/264/296)
+// auth_bump, status, lp_mint_decimals, mint_0_decimals, mint_1_decimals: u8 (offset 328..333)
+// lp_supply: u64 (offset 333)
+// protocol_fees_token_0/1: u64 (offset 341/349)
+// fund_fees_token_0/1: u64 (offset 357/365)
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+pub const RAYDIUM_CPMM_TOKEN_0_VAULT_OFFSET: usize = 72;
+pub const RAYDIUM_CPMM_TOKEN_1_VAULT_OFFSET: usize = 104;
+pub const RAYDIUM_CPMM_TOKEN_0_MINT_OFFSET: usize = 168;
+pub const RAYDIUM_CPMM_TOKEN_1_MINT_OFFSET: usize = 200;
+pub const RAYDIUM_CPMM_PROTOCOL_FEES_0_OFFSET: usize = 341;
+pub const RAYDIUM_CPMM_PROTOCOL_FEES_1_OFFSET: usize = 349;
+pub const RAYDIUM_CPMM_FUND_FEES_0_OFFSET: usize = 357;
+pub const RAYDIUM_CPMM_FUND_FEES_1_OFFSET: usize = 365;
+pub const RAYDIUM_CPMM_MIN_LEN: usize = 373;
+
+// === POOL DATA OFFSETS (Raydium CLMM) ===
+// CLMM PoolState Layout (from IDL)
+// Discriminator: 8 bytes
+// bump: [u8; 1] (offset 8)
+// amm_config: Pubkey (offset 9)
+// owner: Pubkey (offset 41)
+// token_mint_0: Pubkey (offset 73)
+// token_mint_1: Pubkey (offset 105)
+// token_vault_0 / token_vault_1: Pubkey (offset 137/169)
+// observation_key: Pubkey (offset 201)
+// mint_decimals_0 / mint_decimals_1: u8 (offset 233/234)
+// tick_spacing: u16 (offset 235)
+// liquidity: u128 (offset 237)
+// sqrt_price_x64: u128 Q64.64 (offset 253)
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+pub const RAYDIUM_CLMM_TOKEN_MINT_0_OFFSET: usize = 73;
+pub const RAYDIUM_CLMM_TOKEN_MINT_1_OFFSET: usize = 105;
+pub const RAYDIUM_CLMM_SQRT_PRICE_OFFSET: usize = 253;
+pub const RAYDIUM_CLMM_MIN_LEN: usize = 269;
+
+/// SHA256("account:PoolState")[..8] - shared by Raydium CPMM and CLMM, so the owner must be checked too
+pub const RAYDIUM_POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+// === POOL DATA OFFSETS (Meteora DLMM) ===
+// LbPair Layout (from IDL)
+// Discriminator: 8 bytes
+// parameters: StaticParameters (offset 8, 32 bytes)
+// v_parameters: VariableParameters (offset 40, 32 bytes)
+// bump_seed: [u8; 1] (offset 72)
+// bin_step_seed: [u8; 2] (offset 73)
+// pair_type: u8 (offset 75)
+// active_id: i32 (offset 76)
+// bin_step: u16 (offset 80)
+// status, require_base_factor_seed: u8 (offset 82/83)
+// base_factor_seed: [u8; 2] (offset 84)
+// activation_type, creator_pool_on_off_control: u8 (offset 86/87)
+// token_x_mint: Pubkey (offset 88)
+// token_y_mint: Pubkey (offset 120)
+// reserve_x / reserve_y: Pubkey (offset 152/184)
+pub const METEORA_DLMM_PROGRAM_ID: Pubkey = pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+
+/// SHA256("account:LbPair")[..8]
+pub const METEORA_DLMM_LB_PAIR_DISCRIMINATOR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+pub const METEORA_DLMM_ACTIVE_ID_OFFSET: usize = 76;
+pub const METEORA_DLMM_BIN_STEP_OFFSET: usize = 80;
+pub const METEORA_DLMM_TOKEN_X_MINT_OFFSET: usize = 88;
+pub const METEORA_DLMM_TOKEN_Y_MINT_OFFSET: usize = 120;
+pub const METEORA_DLMM_MIN_LEN: usize = 216;
+
+// === POOL DATA OFFSETS (Meteora DAMM v2, program cpamdp...) ===
+// DAMM v1 (Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB) prices from vault LP shares and is not supported
+// Pool Layout (from IDL)
+// Discriminator: 8 bytes
+// pool_fees: PoolFeesStruct (offset 8, 160 bytes)
+// token_a_mint: Pubkey (offset 168)
+// token_b_mint: Pubkey (offset 200)
+// token_a_vault / token_b_vault: Pubkey (offset 232/264)
+// whitelisted_vault / partner: Pubkey (offset 296/328)
+// liquidity: u128 (offset 360)
+// _padding: u128 (offset 376)
+// protocol_a_fee / protocol_b_fee / partner_a_fee / partner_b_fee: u64 (offset 392..424)
+// sqrt_min_price / sqrt_max_price: u128 (offset 424/440)
+// sqrt_price: u128 Q64.64 (offset 456)
+pub const METEORA_DAMM_V2_PROGRAM_ID: Pubkey = pubkey!("cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG");
+
+/// SHA256("account:Pool")[..8] - same as PumpSwap, so the owner must be checked too
+pub const METEORA_DAMM_V2_POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+pub const METEORA_DAMM_V2_TOKEN_A_MINT_OFFSET: usize = 168;
+pub const METEORA_DAMM_V2_TOKEN_B_MINT_OFFSET: usize = 200;
+pub const METEORA_DAMM_V2_SQRT_PRICE_OFFSET: usize = 456;
+pub const METEORA_DAMM_V2_MIN_LEN: usize = 472;
+
+/// TWAP configuration - the reference price is the median checkpoint inside the window
 pub const TWAP_WINDOW_SECONDS: i64 = 300; // 5 minute window
-pub const MIN_TWAP_SAMPLES: u8 = 3;
+pub const MIN_TWAP_SAMPLES: u8 = 5;
+pub const MIN_PRICE_RECORD_INTERVAL_SECONDS: i64 = 30;
 
-/// Price checkpoint for TWAP calculation
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
-pub struct PriceCheckpoint {
-    pub price: u64,
-    pub timestamp: i64,
-}
+/// Compute budget instructions are the only ones allowed next to record_price
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
 
 /// Math utilities with overflow protection
 pub struct SafeMath;
@@ -188,6 +347,39 @@ impl SafeMath {
     pub fn sub_u128(a: u128, b: u128) -> Result<u128> {
         a.checked_sub(b).ok_or(LendingError::MathUnderflow.into())
     }
+
+    /// Full 256-bit product of two u128 values as (high, low) halves
+    pub fn full_mul_u128(a: u128, b: u128) -> (u128, u128) {
+        const LOW_MASK: u128 = u64::MAX as u128;
+
+        let (a_hi, a_lo) = (a >> 64, a & LOW_MASK);
+        let (b_hi, b_lo) = (b >> 64, b & LOW_MASK);
+
+        let lo_lo = a_lo * b_lo;
+        let lo_hi = a_lo * b_hi;
+        let hi_lo = a_hi * b_lo;
+        let hi_hi = a_hi * b_hi;
+
+        let mid = (lo_lo >> 64) + (lo_hi & LOW_MASK) + (hi_lo & LOW_MASK);
+        let low = (lo_lo & LOW_MASK) | (mid << 64);
+        let high = hi_hi + (lo_hi >> 64) + (hi_lo >> 64) + (mid >> 64);
+
+        (high, low)
+    }
+
+    /// (a * b) >> shift with a 256-bit intermediate, shift in 1..=128
+    /// Errors if the result does not fit in a u128
+    pub fn mul_shift_u128(a: u128, b: u128, shift: u32) -> Result<u128> {
+        require!(shift > 0 && shift <= 128, LendingError::MathOverflow);
+
+        let (high, low) = Self::full_mul_u128(a, b);
+        if shift == 128 {
+            return Ok(high);
+        }
+
+        require!(high >> shift == 0, LendingError::MathOverflow);
+        Ok((high << (128 - shift)) | (low >> shift))
+    }
 }
 
 /// Loan calculation utilities
@@ -195,32 +387,15 @@ pub struct LoanCalculator;
 
 impl LoanCalculator {
     /// Calculate SOL amount to lend based on collateral and LTV
+    /// `token_price` is the canonical price, `token_decimals` the collateral mint's decimals
     pub fn calculate_loan_amount(
         collateral_amount: u64,
         token_price: u64,
         ltv_bps: u16,
+        token_decimals: u8,
     ) -> Result<u64> {
-        // Use u128 for the entire calculation to prevent overflow
-        const PRICE_SCALE: u128 = 1_000_000; // 10^6 - matches read_pumpfun_price scaling // 10^6
-        
-        let collateral_u128 = collateral_amount as u128;
-        let price_u128 = token_price as u128;
-        let ltv_u128 = ltv_bps as u128;
-        let bps_divisor_u128 = BPS_DIVISOR as u128;
-        
-        // Calculate: (collateral_amount * token_price * ltv_bps) / (PRICE_SCALE * BPS_DIVISOR)
-        let loan_amount = collateral_u128
-            .checked_mul(price_u128).ok_or(LendingError::MathOverflow)?
-            .checked_mul(ltv_u128).ok_or(LendingError::MathOverflow)?
-            .checked_mul(1000).ok_or(LendingError::MathOverflow)?
-            .checked_div(PRICE_SCALE).ok_or(LendingError::DivisionByZero)?
-            .checked_div(bps_divisor_u128).ok_or(LendingError::DivisionByZero)?;
-        
-        if loan_amount > u64::MAX as u128 {
-            return Err(LendingError::MathOverflow.into());
-        }
-        
-        Ok(loan_amount as u64)
+        let collateral_value = Self::calculate_collateral_value(collateral_amount, token_price, token_decimals)?;
+        SafeMath::mul_div(collateral_value, ltv_bps as u64, BPS_DIVISOR)
     }
 
     /// Calculate total amount owed (principal + protocol fee only)
@@ -232,26 +407,234 @@ impl LoanCalculator {
         SafeMath::add(principal, protocol_fee)
     }
 
+    /// Calculate debt settled at liquidation (principal + protocol fee + liquidation penalty)
+    pub fn calculate_liquidation_debt(
+        principal: u64,
+        liquidation_penalty_bps: u16,
+    ) -> Result<u64> {
+        let total_owed = Self::calculate_total_owed(principal, PROTOCOL_FEE_BPS)?;
+        let penalty = SafeMath::mul_div(principal, liquidation_penalty_bps as u64, BPS_DIVISOR)?;
+        SafeMath::add(total_owed, penalty)
+    }
+
+    /// Split a loan fee into (treasury, staking, operations) shares
+    /// Rounding dust is added to the treasury share
+    pub fn split_loan_fee(protocol_fee: u64) -> Result<(u64, u64, u64)> {
+        let treasury_fee = SafeMath::mul_div(protocol_fee, LOAN_FEE_TREASURY_BPS as u64, BPS_DIVISOR)?;
+        let staking_fee = SafeMath::mul_div(protocol_fee, LOAN_FEE_STAKING_BPS as u64, BPS_DIVISOR)?;
+        let operations_fee = SafeMath::mul_div(protocol_fee, LOAN_FEE_OPERATIONS_BPS as u64, BPS_DIVISOR)?;
+
+        let total_distributed = SafeMath::add(SafeMath::add(treasury_fee, staking_fee)?, operations_fee)?;
+        let dust = protocol_fee.saturating_sub(total_distributed);
+
+        Ok((SafeMath::add(treasury_fee, dust)?, staking_fee, operations_fee))
+    }
+
+    /// Carve the insurance fund's slice of a loan fee out of the treasury's share
+    /// Returns (treasury_fee, insurance_fee)
+    pub fn split_insurance_fee(
+        protocol_fee: u64,
+        treasury_fee: u64,
+        insurance_fee_bps: u16,
+    ) -> Result<(u64, u64)> {
+        let insurance_fee = std::cmp::min(
+            SafeMath::mul_div(protocol_fee, insurance_fee_bps as u64, BPS_DIVISOR)?,
+            treasury_fee,
+        );
+        Ok((SafeMath::sub(treasury_fee, insurance_fee)?, insurance_fee))
+    }
+
+    /// Split debt recovered by a full liquidation into (treasury, operations, shortfall)
+    /// The treasury gets the principal back first - operations only take their share of the
+    /// fee and penalty recovered above it, and any missing principal is bad debt
+    pub fn split_liquidation_proceeds(debt_recovered: u64, principal: u64) -> Result<(u64, u64, u64)> {
+        if debt_recovered < principal {
+            return Ok((debt_recovered, 0, SafeMath::sub(principal, debt_recovered)?));
+        }
+        let operations_share = SafeMath::mul_div(
+            SafeMath::sub(debt_recovered, principal)?,
+            LIQUIDATION_OPERATIONS_BPS as u64,
+            BPS_DIVISOR,
+        )?;
+        Ok((SafeMath::sub(debt_recovered, operations_share)?, operations_share, 0))
+    }
+
+    /// Keeper bonus: `keeper_bonus_bps` of the sale proceeds, paid out of the liquidation
+    /// penalty. Recovered debt covers principal and protocol fee first, so the bonus is capped
+    /// at the penalty actually recovered and a shortfall liquidation pays none
+    pub fn calculate_keeper_bonus(
+        sol_proceeds: u64,
+        debt_recovered: u64,
+        principal_repaid: u64,
+        liquidation_penalty_bps: u16,
+        keeper_bonus_bps: u16,
+    ) -> Result<u64> {
+        let penalty = SafeMath::mul_div(principal_repaid, liquidation_penalty_bps as u64, BPS_DIVISOR)?;
+        let penalty_recovered = std::cmp::min(
+            debt_recovered.saturating_sub(Self::calculate_total_owed(principal_repaid, PROTOCOL_FEE_BPS)?),
+            penalty,
+        );
+        let bonus = SafeMath::mul_div(sol_proceeds, keeper_bonus_bps as u64, BPS_DIVISOR)?;
+        Ok(std::cmp::min(bonus, penalty_recovered))
+    }
+
+    /// Calculate effective LTV at which a loan becomes liquidatable
+    /// Capped at 90% to ensure protocol always profits at liquidation (before slippage)
+    pub fn calculate_liquidation_ltv(
+        ltv_bps: u16,
+        liquidation_buffer_bps: u16,
+    ) -> Result<u64> {
+        let raw_effective_ltv = SafeMath::add(ltv_bps as u64, liquidation_buffer_bps as u64)?;
+        Ok(std::cmp::min(raw_effective_ltv, MAX_EFFECTIVE_LIQUIDATION_LTV_BPS))
+    }
+
     /// Calculate liquidation price with safety cap
     /// Ensures effective LTV never exceeds 90% for protocol safety
+    /// Returned as a canonical price, comparable with pool and approved prices
     pub fn calculate_liquidation_price(
         sol_borrowed: u64,
         collateral_amount: u64,
         ltv_bps: u16,
         liquidation_buffer_bps: u16,
+        token_decimals: u8,
     ) -> Result<u64> {
-        let raw_effective_ltv = SafeMath::add(ltv_bps as u64, liquidation_buffer_bps as u64)?;
-        
-        // Cap at 90% to ensure protocol always profits at liquidation (before slippage)
-        let effective_ltv = std::cmp::min(raw_effective_ltv, MAX_EFFECTIVE_LIQUIDATION_LTV_BPS);
-        
-        SafeMath::mul_div(
-            sol_borrowed, 
-            BPS_DIVISOR, 
-            SafeMath::mul_div(collateral_amount, effective_ltv, BPS_DIVISOR)?
-        )
+        let effective_ltv = Self::calculate_liquidation_ltv(ltv_bps, liquidation_buffer_bps)?;
+
+        // price = sol_borrowed * scale / (collateral * effective_ltv / BPS)
+        let covered_collateral = SafeMath::mul_div(collateral_amount, effective_ltv, BPS_DIVISOR)?;
+        let price = SafeMath::mul_div_u128(
+            sol_borrowed as u128,
+            PriceFeedUtils::price_scale_for_decimals(token_decimals)?,
+            covered_collateral as u128,
+        )?;
+
+        require!(price <= u64::MAX as u128, LendingError::MathOverflow);
+        Ok(price as u64)
+    }
+
+    /// Move a legacy loan to canonical prices; a no-op once current
+    /// Legacy prices mixed several units (BPS-scaled liquidation prices, PumpSwap's /1000 entry
+    /// price) and cannot be rescaled, so the liquidation price is recomputed from the loan's
+    /// terms and the entry price is cleared as unknown
+    pub fn upgrade_loan_prices(
+        loan: &mut Loan,
+        ltv_bps: u16,
+        liquidation_buffer_bps: u16,
+        token_decimals: u8,
+    ) -> Result<()> {
+        if loan.price_version >= LOAN_PRICE_VERSION {
+            return Ok(());
+        }
+
+        loan.liquidation_price = Self::calculate_liquidation_price(
+            loan.sol_borrowed,
+            loan.collateral_amount,
+            ltv_bps,
+            liquidation_buffer_bps,
+            token_decimals,
+        )?;
+        loan.entry_price = 0;
+        loan.price_version = LOAN_PRICE_VERSION;
+
+        Ok(())
+    }
+
+    /// Calculate SOL value (lamports) of a raw collateral amount at a canonical price
+    pub fn calculate_collateral_value(
+        collateral_amount: u64,
+        token_price: u64,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        let value = SafeMath::mul_div_u128(
+            collateral_amount as u128,
+            token_price as u128,
+            PriceFeedUtils::price_scale_for_decimals(token_decimals)?,
+        )?;
+
+        require!(value <= u64::MAX as u128, LendingError::MathOverflow);
+        Ok(value as u64)
+    }
+
+    /// Collateral needed to be worth at least `sol_value` at `token_price` (rounds up)
+    pub fn calculate_collateral_for_value(
+        sol_value: u64,
+        token_price: u64,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        require!(token_price > 0, LendingError::ZeroPrice);
+        let scaled = (sol_value as u128)
+            .checked_mul(PriceFeedUtils::price_scale_for_decimals(token_decimals)?)
+            .ok_or(LendingError::MathOverflow)?;
+        let amount = scaled.div_ceil(token_price as u128);
+
+        require!(amount <= u64::MAX as u128, LendingError::MathOverflow);
+        Ok(amount as u64)
+    }
+
+    /// Calculate principal covered by liquidation proceeds
+    /// Proceeds pay principal, protocol fee and liquidation penalty pro-rata
+    pub fn calculate_principal_repaid(
+        sol_proceeds: u64,
+        liquidation_penalty_bps: u16,
+    ) -> Result<u64> {
+        let debt_bps = SafeMath::add(
+            SafeMath::add(BPS_DIVISOR, PROTOCOL_FEE_BPS as u64)?,
+            liquidation_penalty_bps as u64,
+        )?;
+        SafeMath::mul_div(sol_proceeds, BPS_DIVISOR, debt_bps)
     }
 
+    /// Calculate how much collateral a partial liquidation may sell
+    /// Returns the amount that brings the loan back to `target_health_bps` at `token_price`,
+    /// capped at the full collateral amount
+    ///
+    /// Selling value v reduces debt by v * k (k = principal share of proceeds), so
+    /// (V - v) * L >= H * (D - v * k)  =>  v >= (H * D - V * L) / (H * k - L)
+    pub fn calculate_partial_liquidation_collateral(
+        collateral_amount: u64,
+        sol_borrowed: u64,
+        token_price: u64,
+        liquidation_ltv_bps: u64,
+        liquidation_penalty_bps: u16,
+        target_health_bps: u64,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        let collateral_value = Self::calculate_collateral_value(collateral_amount, token_price, token_decimals)?;
+        require!(collateral_value > 0, LendingError::ZeroPrice);
+
+        let bps = BPS_DIVISOR as u128;
+        let principal_share_bps = Self::calculate_principal_repaid(BPS_DIVISOR, liquidation_penalty_bps)? as u128;
+
+        let required = (target_health_bps as u128)
+            .checked_mul(sol_borrowed as u128)
+            .ok_or(LendingError::MathOverflow)?;
+        let covered = (collateral_value as u128)
+            .checked_mul(liquidation_ltv_bps as u128)
+            .ok_or(LendingError::MathOverflow)?;
+        if covered >= required {
+            return Ok(0); // Already at target health
+        }
+
+        let denominator = (target_health_bps as u128)
+            .checked_mul(principal_share_bps)
+            .ok_or(LendingError::MathOverflow)?
+            / bps;
+        let denominator = SafeMath::sub_u128(denominator, liquidation_ltv_bps as u128)?;
+        require!(denominator > 0, LendingError::DivisionByZero);
+
+        let value_to_sell = (required - covered) / denominator;
+
+        // Convert value to tokens (round up) and cap at full collateral
+        let tokens = SafeMath::mul_div_u128(
+            value_to_sell,
+            collateral_amount as u128,
+            collateral_value as u128,
+        )?
+        .checked_add(1)
+        .ok_or(LendingError::MathOverflow)?;
+
+        Ok(std::cmp::min(tokens, collateral_amount as u128) as u64)
+    }
 
     /// Calculate loan health factor (>1 = healthy, <1 = liquidatable)
     /// Returns value in basis points (10000 = 1.0)
@@ -333,6 +716,15 @@ impl LoanCalculator {
             Ok(effective_ltv as u16)
         }
     }
+
+    /// Cap an LTV for the pool the token trades on - bonding curve tokens borrow at most PUMPFUN_MAX_LTV_BPS
+    pub fn cap_ltv_for_pool(ltv_bps: u16, pool_type: PoolType) -> u16 {
+        if pool_type == PoolType::Pumpfun {
+            std::cmp::min(ltv_bps, PUMPFUN_MAX_LTV_BPS)
+        } else {
+            ltv_bps
+        }
+    }
 }
 
 /// Price feed utilities with real on-chain price reading
@@ -340,7 +732,12 @@ pub struct PriceFeedUtils;
 
 impl PriceFeedUtils {
     /// Read price from Raydium AMM pool
-    pub fn read_raydium_price(pool_data: &[u8], token_mint: &Pubkey, sol_mint: &Pubkey) -> Result<u64> {
+    pub fn read_raydium_price(
+        pool_data: &[u8],
+        token_mint: &Pubkey,
+        sol_mint: &Pubkey,
+        token_decimals: u8,
+    ) -> Result<u64> {
         // Validate minimum data length
         require!(pool_data.len() >= RAYDIUM_MIN_DATA_LEN, LendingError::InvalidPriceFeed);
         
@@ -383,157 +780,583 @@ impl PriceFeedUtils {
         let other_mint = if token_a_mint == *sol_mint { token_b_mint } else { token_a_mint };
         require!(other_mint == *token_mint, LendingError::PoolTypeMismatch);
         
-        // Calculate price with overflow protection
-        let price = (sol_amount as u128)
-            .checked_mul(PRICE_SCALE)
-            .ok_or(LendingError::MathOverflow)?
-            .checked_div(token_amount as u128)
-            .ok_or(LendingError::DivisionByZero)?;
-        
-        require!(price <= u64::MAX as u128, LendingError::MathOverflow);
-        
-        Ok(price as u64)
+        Self::price_from_reserves(sol_amount, token_amount, token_decimals)
     }
 
-    /// Read price from Pumpfun bonding curve
-    pub fn read_pumpfun_price(pool_data: &[u8]) -> Result<u64> {
-        require!(pool_data.len() >= PUMPFUN_MIN_DATA_LEN, LendingError::InvalidPriceFeed);
-        
-        // Validate data is not all zeros
-        let is_initialized = pool_data[..PUMPFUN_MIN_DATA_LEN].iter().any(|&b| b != 0);
-        require!(is_initialized, LendingError::InvalidPriceFeed);
-        
-        let virtual_sol = u64::from_le_bytes(
-            pool_data[PUMPFUN_VIRTUAL_SOL_OFFSET..PUMPFUN_VIRTUAL_SOL_OFFSET + 8]
-                .try_into()
-                .map_err(|_| LendingError::InvalidPriceFeed)?
-        );
-        let virtual_token = u64::from_le_bytes(
-            pool_data[PUMPFUN_VIRTUAL_TOKEN_OFFSET..PUMPFUN_VIRTUAL_TOKEN_OFFSET + 8]
-                .try_into()
-                .map_err(|_| LendingError::InvalidPriceFeed)?
-        );
-        
-        // Validate reserves are non-zero
-        require!(virtual_sol > 0 && virtual_token > 0, LendingError::InvalidPriceFeed);
-        
-        let price = (virtual_sol as u128)
-            .checked_mul(PRICE_SCALE)
-            .ok_or(LendingError::MathOverflow)?
-            .checked_div(virtual_token as u128)
-            .ok_or(LendingError::DivisionByZero)?;
-        
+    /// PRICE_SCALE * 10^decimals - converts lamports per raw token unit into the canonical price
+    pub fn price_scale_for_decimals(token_decimals: u8) -> Result<u128> {
+        10u128
+            .checked_pow(token_decimals as u32)
+            .and_then(|unit| unit.checked_mul(PRICE_SCALE))
+            .ok_or(LendingError::MathOverflow.into())
+    }
+
+    /// Canonical price from SOL and token reserves, both in raw units
+    pub fn price_from_reserves(sol_amount: u64, token_amount: u64, token_decimals: u8) -> Result<u64> {
+        require!(sol_amount > 0 && token_amount > 0, LendingError::InvalidPriceFeed);
+
+        let price = SafeMath::mul_div_u128(
+            sol_amount as u128,
+            Self::price_scale_for_decimals(token_decimals)?,
+            token_amount as u128,
+        )?;
+
         require!(price <= u64::MAX as u128, LendingError::MathOverflow);
         require!(price > 0, LendingError::ZeroPrice);
-        
+
         Ok(price as u64)
     }
 
-    /// Read price from PumpSwap pool using vault account balances
-    pub fn read_pumpswap_price(
-        pool_data: &[u8],
-        base_vault_amount: u64,
-        quote_vault_amount: u64,
-        expected_base_vault: &Pubkey,
-        expected_quote_vault: &Pubkey,
+    /// Convert a Q64.64 sqrt price (sqrt of token_b / token_a in raw units) into
+    /// the canonical price
+    /// `sol_is_token_b` selects which side of the pair is SOL
+    pub fn price_from_sqrt_price_x64(
+        sqrt_price_x64: u128,
+        sol_is_token_b: bool,
+        token_decimals: u8,
     ) -> Result<u64> {
-        // Validate pool data length
-        require!(pool_data.len() >= PUMPSWAP_POOL_MIN_LEN, LendingError::InvalidPriceFeed);
-        
-        // Extract vault addresses from pool data
-        let stored_base_vault = Pubkey::try_from(
-            &pool_data[PUMPSWAP_POOL_BASE_VAULT_OFFSET..PUMPSWAP_POOL_BASE_VAULT_OFFSET + 32]
-        ).map_err(|_| LendingError::InvalidPriceFeed)?;
-        
-        let stored_quote_vault = Pubkey::try_from(
-            &pool_data[PUMPSWAP_POOL_QUOTE_VAULT_OFFSET..PUMPSWAP_POOL_QUOTE_VAULT_OFFSET + 32]
-        ).map_err(|_| LendingError::InvalidPriceFeed)?;
-        
-        // Validate passed vault accounts match pool's stored vaults
-        require!(expected_base_vault == &stored_base_vault, LendingError::InvalidPumpSwapVault);
-        require!(expected_quote_vault == &stored_quote_vault, LendingError::InvalidPumpSwapVault);
-        
-        // Validate reserves are non-zero
-        require!(base_vault_amount > 0 && quote_vault_amount > 0, LendingError::InvalidPriceFeed);
-        
-        // Calculate price: quote_amount * PRICE_SCALE / base_amount / 1000
-        // quote is SOL (WSOL with 9 decimals), base is the memecoin (6 decimals)
-        // Need to divide by 1000 to normalize the decimal difference (9 - 6 = 3)
-        let price = (quote_vault_amount as u128)
-            .checked_mul(PRICE_SCALE)
-            .ok_or(LendingError::MathOverflow)?
-            .checked_div(base_vault_amount as u128)
-            .ok_or(LendingError::DivisionByZero)?
-            .checked_div(1000) // Normalize for decimal difference between WSOL (9) and token (6)
-            .ok_or(LendingError::DivisionByZero)?;
-        
+        require!(sqrt_price_x64 > 0, LendingError::InvalidPriceFeed);
+
+        // Token/SOL ordering: SOL as token_a means the pool quotes token per SOL,
+        // so invert the sqrt price first (2^128 / sqrt_price, within one ulp)
+        let sqrt_price = if sol_is_token_b {
+            sqrt_price_x64
+        } else {
+            u128::MAX / sqrt_price_x64
+        };
+
+        // price_x64 = sqrt_price^2 >> 64, then scale and drop the remaining 64 fractional bits
+        let price_x64 = SafeMath::mul_shift_u128(sqrt_price, sqrt_price, 64)?;
+        let price = SafeMath::mul_shift_u128(price_x64, Self::price_scale_for_decimals(token_decimals)?, 64)?;
+
         require!(price <= u64::MAX as u128, LendingError::MathOverflow);
         require!(price > 0, LendingError::ZeroPrice);
-        
+
         Ok(price as u64)
     }
 
-    /// Read price from pool - ALWAYS validates freshness
-    /// This is the ONLY function that should be used for price reading
-    pub fn read_price_from_pool(
-        pool_account: &AccountInfo,
-        pool_type: PoolType,
+    /// Read price from an Orca Whirlpool (concentrated liquidity) account
+    pub fn read_whirlpool_price(
+        pool_data: &[u8],
         token_mint: &Pubkey,
+        sol_mint: &Pubkey,
+        token_decimals: u8,
     ) -> Result<u64> {
-        let pool_data = pool_account.try_borrow_data()?;
-        let sol_mint = pubkey!("So11111111111111111111111111111111111111112");
-        
-        let price = match pool_type {
-            PoolType::Raydium | PoolType::Orca => Self::read_raydium_price(&pool_data, token_mint, &sol_mint)?,
-            PoolType::Pumpfun | PoolType::PumpSwap => Self::read_pumpfun_price(&pool_data)?,
+        require!(pool_data.len() >= WHIRLPOOL_MIN_LEN, LendingError::InvalidPriceFeed);
+        require!(
+            pool_data[0..8] == WHIRLPOOL_DISCRIMINATOR,
+            LendingError::InvalidPoolData
+        );
+
+        let sqrt_price = u128::from_le_bytes(
+            pool_data[WHIRLPOOL_SQRT_PRICE_OFFSET..WHIRLPOOL_SQRT_PRICE_OFFSET + 16]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        );
+
+        let token_mint_a = Pubkey::try_from(
+            &pool_data[WHIRLPOOL_TOKEN_MINT_A_OFFSET..WHIRLPOOL_TOKEN_MINT_A_OFFSET + 32]
+        ).map_err(|_| LendingError::InvalidPriceFeed)?;
+
+        let token_mint_b = Pubkey::try_from(
+            &pool_data[WHIRLPOOL_TOKEN_MINT_B_OFFSET..WHIRLPOOL_TOKEN_MINT_B_OFFSET + 32]
+        ).map_err(|_| LendingError::InvalidPriceFeed)?;
+
+        // Whirlpools order mints by key, so SOL can be either side
+        let (sol_is_token_b, other_mint) = if token_mint_b == *sol_mint {
+            (true, token_mint_a)
+        } else if token_mint_a == *sol_mint {
+            (false, token_mint_b)
+        } else {
+            return Err(LendingError::InvalidPriceFeed.into());
         };
-        
-        // Validate price is non-zero
-        require!(price > 0, LendingError::ZeroPrice);
-        
-        Ok(price)
+        require!(other_mint == *token_mint, LendingError::PoolTypeMismatch);
+
+        Self::price_from_sqrt_price_x64(sqrt_price, sol_is_token_b, token_decimals)
     }
 
-    /// Validate price freshness
-    pub fn is_price_fresh(price_timestamp: i64, current_timestamp: i64) -> bool {
-        current_timestamp - price_timestamp < PRICE_STALENESS_THRESHOLD
+    /// Read a little-endian u64 from pool data
+    fn read_u64_at(pool_data: &[u8], offset: usize) -> Result<u64> {
+        Ok(u64::from_le_bytes(
+            pool_data[offset..offset + 8]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        ))
     }
 
-    /// Validate price deviation is within acceptable range
-    pub fn validate_price_deviation(old_price: u64, new_price: u64) -> Result<()> {
-        if old_price == 0 {
-            return Ok(()); // First price reading
-        }
+    /// Read a Pubkey from pool data
+    fn read_pubkey_at(pool_data: &[u8], offset: usize) -> Result<Pubkey> {
+        Pubkey::try_from(&pool_data[offset..offset + 32]).map_err(|_| LendingError::InvalidPriceFeed.into())
+    }
 
-        let price_diff = if new_price > old_price {
-            new_price - old_price
+    /// Work out which side of a two-mint pool is SOL and check the other side is our token
+    /// Returns true if SOL is token 1 (token_b)
+    fn sol_is_token_1(mint_0: &Pubkey, mint_1: &Pubkey, token_mint: &Pubkey, sol_mint: &Pubkey) -> Result<bool> {
+        let (sol_is_token_1, other_mint) = if mint_1 == sol_mint {
+            (true, mint_0)
+        } else if mint_0 == sol_mint {
+            (false, mint_1)
         } else {
-            old_price - new_price
+            return Err(LendingError::InvalidPriceFeed.into());
         };
+        require!(other_mint == token_mint, LendingError::PoolTypeMismatch);
+        Ok(sol_is_token_1)
+    }
 
-        let deviation_bps = SafeMath::mul_div(price_diff, BPS_DIVISOR, old_price)?;
-        
-        if deviation_bps > MAX_PRICE_DEVIATION_BPS {
-            return Err(LendingError::PriceDeviationTooHigh.into());
-        }
+    /// Read price from a Raydium CPMM pool using its vault balances
+    /// Vault balances include protocol and fund fees that are not part of the reserves
+    #[allow(clippy::too_many_arguments)]
+    pub fn read_raydium_cpmm_price(
+        pool_data: &[u8],
+        token_mint: &Pubkey,
+        sol_mint: &Pubkey,
+        token_0_vault: &Pubkey,
+        token_0_vault_amount: u64,
+        token_1_vault: &Pubkey,
+        token_1_vault_amount: u64,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        require!(pool_data.len() >= RAYDIUM_CPMM_MIN_LEN, LendingError::InvalidPriceFeed);
+        require!(
+            pool_data[0..8] == RAYDIUM_POOL_STATE_DISCRIMINATOR,
+            LendingError::InvalidPoolData
+        );
 
-        Ok(())
+        // Validate passed vault accounts match pool's stored vaults
+        require!(
+            *token_0_vault == Self::read_pubkey_at(pool_data, RAYDIUM_CPMM_TOKEN_0_VAULT_OFFSET)?
+                && *token_1_vault == Self::read_pubkey_at(pool_data, RAYDIUM_CPMM_TOKEN_1_VAULT_OFFSET)?,
+            LendingError::InvalidVault
+        );
+
+        let sol_is_token_1 = Self::sol_is_token_1(
+            &Self::read_pubkey_at(pool_data, RAYDIUM_CPMM_TOKEN_0_MINT_OFFSET)?,
+            &Self::read_pubkey_at(pool_data, RAYDIUM_CPMM_TOKEN_1_MINT_OFFSET)?,
+            token_mint,
+            sol_mint,
+        )?;
+
+        let reserve_0 = token_0_vault_amount
+            .saturating_sub(Self::read_u64_at(pool_data, RAYDIUM_CPMM_PROTOCOL_FEES_0_OFFSET)?)
+            .saturating_sub(Self::read_u64_at(pool_data, RAYDIUM_CPMM_FUND_FEES_0_OFFSET)?);
+        let reserve_1 = token_1_vault_amount
+            .saturating_sub(Self::read_u64_at(pool_data, RAYDIUM_CPMM_PROTOCOL_FEES_1_OFFSET)?)
+            .saturating_sub(Self::read_u64_at(pool_data, RAYDIUM_CPMM_FUND_FEES_1_OFFSET)?);
+
+        let (sol_amount, token_amount) = if sol_is_token_1 {
+            (reserve_1, reserve_0)
+        } else {
+            (reserve_0, reserve_1)
+        };
+
+        Self::price_from_reserves(sol_amount, token_amount, token_decimals)
     }
 
-    /// Get token price - wrapper for read_price_from_pool
-    /// Used when you have the pool account info available
-    pub fn get_token_price(
-        pool_account: &AccountInfo,
-        pool_type: PoolType,
+    /// Read price from a Raydium CLMM pool's sqrt_price_x64
+    pub fn read_raydium_clmm_price(
+        pool_data: &[u8],
         token_mint: &Pubkey,
+        sol_mint: &Pubkey,
+        token_decimals: u8,
     ) -> Result<u64> {
-        Self::read_price_from_pool(pool_account, pool_type, token_mint)
+        require!(pool_data.len() >= RAYDIUM_CLMM_MIN_LEN, LendingError::InvalidPriceFeed);
+        require!(
+            pool_data[0..8] == RAYDIUM_POOL_STATE_DISCRIMINATOR,
+            LendingError::InvalidPoolData
+        );
+
+        let sol_is_token_1 = Self::sol_is_token_1(
+            &Self::read_pubkey_at(pool_data, RAYDIUM_CLMM_TOKEN_MINT_0_OFFSET)?,
+            &Self::read_pubkey_at(pool_data, RAYDIUM_CLMM_TOKEN_MINT_1_OFFSET)?,
+            token_mint,
+            sol_mint,
+        )?;
+
+        let sqrt_price = u128::from_le_bytes(
+            pool_data[RAYDIUM_CLMM_SQRT_PRICE_OFFSET..RAYDIUM_CLMM_SQRT_PRICE_OFFSET + 16]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        );
+
+        Self::price_from_sqrt_price_x64(sqrt_price, sol_is_token_1, token_decimals)
     }
 
-    /// Validate price against recent checkpoint (anti-manipulation)
-    pub fn validate_price_safety(
-        current_price: u64,
-        last_checkpoint_price: u64,
+    /// Validate a Raydium CPMM / CLMM pool's owner program, discriminator and mints
+    pub fn validate_raydium_pool(
+        pool_account: &AccountInfo,
+        pool_type: PoolType,
+        token_mint: &Pubkey,
+    ) -> Result<()> {
+        let (program_id, min_len, mint_0_offset, mint_1_offset) = match pool_type {
+            PoolType::RaydiumCpmm => (
+                RAYDIUM_CPMM_PROGRAM_ID,
+                RAYDIUM_CPMM_MIN_LEN,
+                RAYDIUM_CPMM_TOKEN_0_MINT_OFFSET,
+                RAYDIUM_CPMM_TOKEN_1_MINT_OFFSET,
+            ),
+            PoolType::RaydiumClmm => (
+                RAYDIUM_CLMM_PROGRAM_ID,
+                RAYDIUM_CLMM_MIN_LEN,
+                RAYDIUM_CLMM_TOKEN_MINT_0_OFFSET,
+                RAYDIUM_CLMM_TOKEN_MINT_1_OFFSET,
+            ),
+            _ => return Err(LendingError::InvalidPoolType.into()),
+        };
+
+        require!(pool_account.owner == &program_id, LendingError::InvalidPoolOwner);
+
+        let pool_data = pool_account.try_borrow_data()?;
+        require!(pool_data.len() >= min_len, LendingError::InvalidPoolData);
+        require!(
+            pool_data[0..8] == RAYDIUM_POOL_STATE_DISCRIMINATOR,
+            LendingError::InvalidPoolData
+        );
+
+        Self::sol_is_token_1(
+            &Self::read_pubkey_at(&pool_data, mint_0_offset)?,
+            &Self::read_pubkey_at(&pool_data, mint_1_offset)?,
+            token_mint,
+            &WSOL_MINT,
+        )?;
+
+        Ok(())
+    }
+
+    /// Raise a Q64.64 value to an integer power by repeated squaring
+    pub fn pow_q64(base_x64: u128, exponent: u32) -> Result<u128> {
+        let mut result: u128 = 1u128 << 64;
+        let mut base = base_x64;
+        let mut exp = exponent;
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = SafeMath::mul_shift_u128(result, base, 64)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = SafeMath::mul_shift_u128(base, base, 64)?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Convert a DLMM active bin into the canonical price
+    /// Bin price is (1 + bin_step / 10_000)^active_id, quoted as token_y per token_x in raw units
+    pub fn price_from_dlmm_bin(
+        active_id: i32,
+        bin_step: u16,
+        sol_is_token_y: bool,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        require!(bin_step > 0, LendingError::InvalidPriceFeed);
+
+        // SOL as token_x quotes token per SOL, which is the same bin walked the other way
+        let exponent = if sol_is_token_y { active_id as i64 } else { -(active_id as i64) };
+
+        // Negative exponents use the reciprocal base so intermediate values shrink instead of overflowing
+        let step_x64 = ((bin_step as u128) << 64) / BPS_DIVISOR as u128;
+        let base_x64 = if exponent >= 0 {
+            (1u128 << 64) + step_x64
+        } else {
+            u128::MAX / ((1u128 << 64) + step_x64)
+        };
+
+        let price_x64 = Self::pow_q64(base_x64, exponent.unsigned_abs() as u32)?;
+        let price = SafeMath::mul_shift_u128(price_x64, Self::price_scale_for_decimals(token_decimals)?, 64)?;
+
+        require!(price <= u64::MAX as u128, LendingError::MathOverflow);
+        require!(price > 0, LendingError::ZeroPrice);
+
+        Ok(price as u64)
+    }
+
+    /// Read price from a Meteora DLMM pair's active bin
+    pub fn read_meteora_dlmm_price(
+        pool_data: &[u8],
+        token_mint: &Pubkey,
+        sol_mint: &Pubkey,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        require!(pool_data.len() >= METEORA_DLMM_MIN_LEN, LendingError::InvalidPriceFeed);
+        require!(
+            pool_data[0..8] == METEORA_DLMM_LB_PAIR_DISCRIMINATOR,
+            LendingError::InvalidPoolData
+        );
+
+        let sol_is_token_y = Self::sol_is_token_1(
+            &Self::read_pubkey_at(pool_data, METEORA_DLMM_TOKEN_X_MINT_OFFSET)?,
+            &Self::read_pubkey_at(pool_data, METEORA_DLMM_TOKEN_Y_MINT_OFFSET)?,
+            token_mint,
+            sol_mint,
+        )?;
+
+        let active_id = i32::from_le_bytes(
+            pool_data[METEORA_DLMM_ACTIVE_ID_OFFSET..METEORA_DLMM_ACTIVE_ID_OFFSET + 4]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        );
+        let bin_step = u16::from_le_bytes(
+            pool_data[METEORA_DLMM_BIN_STEP_OFFSET..METEORA_DLMM_BIN_STEP_OFFSET + 2]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        );
+
+        Self::price_from_dlmm_bin(active_id, bin_step, sol_is_token_y, token_decimals)
+    }
+
+    /// Read price from a Meteora DAMM v2 pool's sqrt_price
+    /// Positions can be concentrated, so vault balances alone do not give the marginal price
+    pub fn read_meteora_damm_v2_price(
+        pool_data: &[u8],
+        token_mint: &Pubkey,
+        sol_mint: &Pubkey,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        require!(pool_data.len() >= METEORA_DAMM_V2_MIN_LEN, LendingError::InvalidPriceFeed);
+        require!(
+            pool_data[0..8] == METEORA_DAMM_V2_POOL_DISCRIMINATOR,
+            LendingError::InvalidPoolData
+        );
+
+        let sol_is_token_b = Self::sol_is_token_1(
+            &Self::read_pubkey_at(pool_data, METEORA_DAMM_V2_TOKEN_A_MINT_OFFSET)?,
+            &Self::read_pubkey_at(pool_data, METEORA_DAMM_V2_TOKEN_B_MINT_OFFSET)?,
+            token_mint,
+            sol_mint,
+        )?;
+
+        let sqrt_price = u128::from_le_bytes(
+            pool_data[METEORA_DAMM_V2_SQRT_PRICE_OFFSET..METEORA_DAMM_V2_SQRT_PRICE_OFFSET + 16]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        );
+
+        Self::price_from_sqrt_price_x64(sqrt_price, sol_is_token_b, token_decimals)
+    }
+
+    /// Validate a Meteora DLMM / DAMM v2 pool's owner program, discriminator and mints
+    pub fn validate_meteora_pool(
+        pool_account: &AccountInfo,
+        pool_type: PoolType,
+        token_mint: &Pubkey,
+    ) -> Result<()> {
+        let (program_id, discriminator, min_len, mint_0_offset, mint_1_offset) = match pool_type {
+            PoolType::MeteoraDlmm => (
+                METEORA_DLMM_PROGRAM_ID,
+                METEORA_DLMM_LB_PAIR_DISCRIMINATOR,
+                METEORA_DLMM_MIN_LEN,
+                METEORA_DLMM_TOKEN_X_MINT_OFFSET,
+                METEORA_DLMM_TOKEN_Y_MINT_OFFSET,
+            ),
+            PoolType::MeteoraDammV2 => (
+                METEORA_DAMM_V2_PROGRAM_ID,
+                METEORA_DAMM_V2_POOL_DISCRIMINATOR,
+                METEORA_DAMM_V2_MIN_LEN,
+                METEORA_DAMM_V2_TOKEN_A_MINT_OFFSET,
+                METEORA_DAMM_V2_TOKEN_B_MINT_OFFSET,
+            ),
+            _ => return Err(LendingError::InvalidPoolType.into()),
+        };
+
+        require!(pool_account.owner == &program_id, LendingError::InvalidPoolOwner);
+
+        let pool_data = pool_account.try_borrow_data()?;
+        require!(pool_data.len() >= min_len, LendingError::InvalidPoolData);
+        require!(pool_data[0..8] == discriminator, LendingError::InvalidPoolData);
+
+        Self::sol_is_token_1(
+            &Self::read_pubkey_at(&pool_data, mint_0_offset)?,
+            &Self::read_pubkey_at(&pool_data, mint_1_offset)?,
+            token_mint,
+            &WSOL_MINT,
+        )?;
+
+        Ok(())
+    }
+
+    /// Validate owner program, discriminator and mints for pool types that are checked at whitelist time
+    pub fn validate_pool_account(
+        pool_account: &AccountInfo,
+        pool_type: PoolType,
+        token_mint: &Pubkey,
+    ) -> Result<()> {
+        match pool_type {
+            PoolType::RaydiumCpmm | PoolType::RaydiumClmm => {
+                Self::validate_raydium_pool(pool_account, pool_type, token_mint)
+            },
+            PoolType::MeteoraDlmm | PoolType::MeteoraDammV2 => {
+                Self::validate_meteora_pool(pool_account, pool_type, token_mint)
+            },
+            PoolType::Pumpfun => {
+                PumpfunCurveValidator::validate_full(pool_account, token_mint)?;
+                let pool_data = pool_account.try_borrow_data()?;
+                require!(
+                    !PumpfunCurveValidator::is_complete(&pool_data)?,
+                    LendingError::PumpfunCurveComplete
+                );
+                Ok(())
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Read price from Pumpfun bonding curve
+    pub fn read_pumpfun_price(pool_data: &[u8], token_decimals: u8) -> Result<u64> {
+        require!(pool_data.len() >= PUMPFUN_MIN_DATA_LEN, LendingError::InvalidPriceFeed);
+        
+        // Validate data is not all zeros
+        let is_initialized = pool_data[..PUMPFUN_MIN_DATA_LEN].iter().any(|&b| b != 0);
+        require!(is_initialized, LendingError::InvalidPriceFeed);
+        
+        let virtual_sol = u64::from_le_bytes(
+            pool_data[PUMPFUN_VIRTUAL_SOL_OFFSET..PUMPFUN_VIRTUAL_SOL_OFFSET + 8]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        );
+        let virtual_token = u64::from_le_bytes(
+            pool_data[PUMPFUN_VIRTUAL_TOKEN_OFFSET..PUMPFUN_VIRTUAL_TOKEN_OFFSET + 8]
+                .try_into()
+                .map_err(|_| LendingError::InvalidPriceFeed)?
+        );
+        
+        Self::price_from_reserves(virtual_sol, virtual_token, token_decimals)
+    }
+
+    /// Read price from PumpSwap pool using vault account balances
+    pub fn read_pumpswap_price(
+        pool_data: &[u8],
+        base_vault_amount: u64,
+        quote_vault_amount: u64,
+        expected_base_vault: &Pubkey,
+        expected_quote_vault: &Pubkey,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        // Validate pool data length
+        require!(pool_data.len() >= PUMPSWAP_POOL_MIN_LEN, LendingError::InvalidPriceFeed);
+        
+        // Extract vault addresses from pool data
+        let stored_base_vault = Pubkey::try_from(
+            &pool_data[PUMPSWAP_POOL_BASE_VAULT_OFFSET..PUMPSWAP_POOL_BASE_VAULT_OFFSET + 32]
+        ).map_err(|_| LendingError::InvalidPriceFeed)?;
+        
+        let stored_quote_vault = Pubkey::try_from(
+            &pool_data[PUMPSWAP_POOL_QUOTE_VAULT_OFFSET..PUMPSWAP_POOL_QUOTE_VAULT_OFFSET + 32]
+        ).map_err(|_| LendingError::InvalidPriceFeed)?;
+        
+        // Validate passed vault accounts match pool's stored vaults
+        require!(expected_base_vault == &stored_base_vault, LendingError::InvalidPumpSwapVault);
+        require!(expected_quote_vault == &stored_quote_vault, LendingError::InvalidPumpSwapVault);
+        
+        // quote is SOL (lamports), base is the memecoin in raw units of its own decimals
+        Self::price_from_reserves(quote_vault_amount, base_vault_amount, token_decimals)
+    }
+
+    /// Read price from pool - ALWAYS validates freshness
+    /// This is the ONLY function that should be used for price reading
+    pub fn read_price_from_pool(
+        pool_account: &AccountInfo,
+        pool_type: PoolType,
+        token_mint: &Pubkey,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        let pool_data = pool_account.try_borrow_data()?;
+        let sol_mint = pubkey!("So11111111111111111111111111111111111111112");
+        
+        let price = match pool_type {
+            PoolType::Raydium => Self::read_raydium_price(&pool_data, token_mint, &sol_mint, token_decimals)?,
+            PoolType::Orca => {
+                require!(
+                    pool_account.owner == &ORCA_WHIRLPOOL_PROGRAM_ID,
+                    LendingError::InvalidPoolOwner
+                );
+                Self::read_whirlpool_price(&pool_data, token_mint, &sol_mint, token_decimals)?
+            },
+            PoolType::RaydiumClmm => {
+                require!(
+                    pool_account.owner == &RAYDIUM_CLMM_PROGRAM_ID,
+                    LendingError::InvalidPoolOwner
+                );
+                Self::read_raydium_clmm_price(&pool_data, token_mint, &sol_mint, token_decimals)?
+            },
+            PoolType::MeteoraDlmm => {
+                require!(
+                    pool_account.owner == &METEORA_DLMM_PROGRAM_ID,
+                    LendingError::InvalidPoolOwner
+                );
+                Self::read_meteora_dlmm_price(&pool_data, token_mint, &sol_mint, token_decimals)?
+            },
+            PoolType::MeteoraDammV2 => {
+                require!(
+                    pool_account.owner == &METEORA_DAMM_V2_PROGRAM_ID,
+                    LendingError::InvalidPoolOwner
+                );
+                Self::read_meteora_damm_v2_price(&pool_data, token_mint, &sol_mint, token_decimals)?
+            },
+            // CPMM reserves live in vault accounts - use read_spot_price
+            PoolType::RaydiumCpmm => return Err(LendingError::MissingCpmmVaults.into()),
+            PoolType::Pumpfun => {
+                require!(
+                    pool_account.owner == &PUMPFUN_PROGRAM_ID,
+                    LendingError::InvalidPoolOwner
+                );
+                // A completed curve is frozen at its migration price - read the PumpSwap pool instead
+                require!(
+                    !PumpfunCurveValidator::is_complete(&pool_data)?,
+                    LendingError::PumpfunCurveComplete
+                );
+                Self::read_pumpfun_price(&pool_data, token_decimals)?
+            },
+            PoolType::PumpSwap => Self::read_pumpfun_price(&pool_data, token_decimals)?,
+        };
+        
+        // Validate price is non-zero
+        require!(price > 0, LendingError::ZeroPrice);
+        
+        Ok(price)
+    }
+
+    /// Validate price freshness
+    pub fn is_price_fresh(price_timestamp: i64, current_timestamp: i64) -> bool {
+        current_timestamp - price_timestamp < PRICE_STALENESS_THRESHOLD
+    }
+
+    /// Validate price deviation is within acceptable range
+    pub fn validate_price_deviation(old_price: u64, new_price: u64) -> Result<()> {
+        if old_price == 0 {
+            return Ok(()); // First price reading
+        }
+
+        let price_diff = if new_price > old_price {
+            new_price - old_price
+        } else {
+            old_price - new_price
+        };
+
+        let deviation_bps = SafeMath::mul_div(price_diff, BPS_DIVISOR, old_price)?;
+        
+        if deviation_bps > MAX_PRICE_DEVIATION_BPS {
+            return Err(LendingError::PriceDeviationTooHigh.into());
+        }
+
+        Ok(())
+    }
+
+    /// Get token price - wrapper for read_price_from_pool
+    /// Used when you have the pool account info available
+    pub fn get_token_price(
+        pool_account: &AccountInfo,
+        pool_type: PoolType,
+        token_mint: &Pubkey,
+        token_decimals: u8,
+    ) -> Result<u64> {
+        Self::read_price_from_pool(pool_account, pool_type, token_mint, token_decimals)
+    }
+
+    /// Validate price against recent checkpoint (anti-manipulation)
+    pub fn validate_price_safety(
+        current_price: u64,
+        last_checkpoint_price: u64,
         last_checkpoint_time: i64,
         current_time: i64,
     ) -> Result<()> {
@@ -565,6 +1388,7 @@ impl PriceFeedUtils {
         pool_account: &AccountInfo,
         pool_type: PoolType,
         token_mint: &Pubkey,
+        token_decimals: u8,
         current_timestamp: i64,
         last_price_timestamp: i64,
     ) -> Result<u64> {
@@ -573,15 +1397,522 @@ impl PriceFeedUtils {
             return Err(LendingError::StalePriceFeed.into());
         }
         
-        Self::read_price_from_pool(pool_account, pool_type, token_mint)
+        Self::read_price_from_pool(pool_account, pool_type, token_mint, token_decimals)
+    }
+
+    /// Read the instantaneous pool price for a whitelisted token
+    /// PumpSwap and Raydium CPMM pools are validated and priced from their vault balances
+    /// (base/quote vaults are token_0/token_1 vaults for CPMM), every other pool type is
+    /// read directly via read_price_from_pool
+    pub fn read_spot_price(
+        pool_account: &AccountInfo,
+        pool_type: PoolType,
+        token_mint: &Pubkey,
+        token_decimals: u8,
+        base_vault: Option<&AccountInfo>,
+        quote_vault: Option<&AccountInfo>,
+    ) -> Result<u64> {
+        if pool_type == PoolType::RaydiumCpmm {
+            require!(
+                pool_account.owner == &RAYDIUM_CPMM_PROGRAM_ID,
+                LendingError::InvalidPoolOwner
+            );
+            let token_0_vault = base_vault.ok_or(LendingError::MissingCpmmVaults)?;
+            let token_1_vault = quote_vault.ok_or(LendingError::MissingCpmmVaults)?;
+
+            let pool_data = pool_account.try_borrow_data()?;
+            return Self::read_raydium_cpmm_price(
+                &pool_data,
+                token_mint,
+                &WSOL_MINT,
+                token_0_vault.key,
+                Self::read_token_account_amount(token_0_vault)?,
+                token_1_vault.key,
+                Self::read_token_account_amount(token_1_vault)?,
+                token_decimals,
+            );
+        }
+
+        if pool_type != PoolType::PumpSwap {
+            return Self::read_price_from_pool(pool_account, pool_type, token_mint, token_decimals);
+        }
+
+        let (validated_base_vault, validated_quote_vault) =
+            PumpSwapPoolValidator::validate_full(pool_account, token_mint)?;
+
+        let base_vault_info = base_vault.ok_or(LendingError::MissingPumpSwapVaults)?;
+        let quote_vault_info = quote_vault.ok_or(LendingError::MissingPumpSwapVaults)?;
+
+        require!(
+            base_vault_info.key == &validated_base_vault,
+            LendingError::InvalidPumpSwapVault
+        );
+        require!(
+            quote_vault_info.key == &validated_quote_vault,
+            LendingError::InvalidPumpSwapVault
+        );
+
+        // Token account amount is at offset 64 (after mint, owner)
+        let base_amount = Self::read_token_account_amount(base_vault_info)?;
+        let quote_amount = Self::read_token_account_amount(quote_vault_info)?;
+
+        let pool_data = pool_account.try_borrow_data()?;
+        Self::read_pumpswap_price(
+            &pool_data,
+            base_amount,
+            quote_amount,
+            base_vault_info.key,
+            quote_vault_info.key,
+            token_decimals,
+        )
+    }
+
+    /// Read the amount field of an SPL token account
+    pub fn read_token_account_amount(token_account: &AccountInfo) -> Result<u64> {
+        let data = token_account.try_borrow_data()?;
+        require!(data.len() >= 72, LendingError::InvalidPriceFeed);
+        Ok(u64::from_le_bytes(
+            data[64..72].try_into().map_err(|_| LendingError::InvalidPriceFeed)?
+        ))
+    }
+
+    /// Append a checkpoint to a token's price history ring buffer
+    pub fn record_checkpoint(history: &mut PriceHistory, price: u64, timestamp: i64) -> Result<()> {
+        require!(price > 0, LendingError::ZeroPrice);
+
+        let head = history.head as usize;
+        history.checkpoints[head] = PriceCheckpoint { price, timestamp };
+        history.head = ((head + 1) % PRICE_HISTORY_CAPACITY) as u8;
+        if (history.count as usize) < PRICE_HISTORY_CAPACITY {
+            history.count += 1;
+        }
+        history.last_recorded_at = timestamp;
+        Ok(())
+    }
+
+    /// Reference price over the last TWAP_WINDOW_SECONDS: the median checkpoint
+    /// Unlike a time-weighted mean, a minority of manipulated checkpoints cannot move it,
+    /// however long each one stood
+    /// Requires at least MIN_TWAP_SAMPLES checkpoints inside the window
+    pub fn calculate_twap(history: &PriceHistory, current_time: i64) -> Result<u64> {
+        let window_start = current_time - TWAP_WINDOW_SECONDS;
+        let count = history.count as usize;
+
+        let mut prices = [0u64; PRICE_HISTORY_CAPACITY];
+        let mut samples = 0usize;
+
+        // Walk from newest to oldest
+        for i in 0..count {
+            let index = (history.head as usize + PRICE_HISTORY_CAPACITY - 1 - i) % PRICE_HISTORY_CAPACITY;
+            let checkpoint = history.checkpoints[index];
+            if checkpoint.timestamp < window_start {
+                break;
+            }
+            prices[samples] = checkpoint.price;
+            samples += 1;
+        }
+
+        require!(samples >= MIN_TWAP_SAMPLES as usize, LendingError::InsufficientTwapSamples);
+
+        let window = &mut prices[..samples];
+        window.sort_unstable();
+        let mid = samples / 2;
+        let median = if samples.is_multiple_of(2) {
+            ((window[mid - 1] as u128 + window[mid] as u128) / 2) as u64
+        } else {
+            window[mid]
+        };
+        require!(median > 0, LendingError::ZeroPrice);
+
+        Ok(median)
+    }
+
+    /// Require the current instruction to be a top-level record_price with nothing but compute
+    /// budget instructions beside it, so a price cannot be swapped in, recorded and swapped back
+    /// within one transaction
+    pub fn validate_standalone_record(instructions_sysvar: &AccountInfo) -> Result<()> {
+        use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+        use anchor_lang::solana_program::sysvar::instructions;
+
+        require!(
+            get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT,
+            LendingError::PriceRecordNotStandalone
+        );
+
+        let current_index = instructions::load_current_index_checked(instructions_sysvar)? as usize;
+        let mut index = 0;
+        while let Ok(ix) = instructions::load_instruction_at_checked(index, instructions_sysvar) {
+            let allowed = if index == current_index {
+                ix.program_id == crate::ID
+            } else {
+                ix.program_id == COMPUTE_BUDGET_PROGRAM_ID
+            };
+            require!(allowed, LendingError::PriceRecordNotStandalone);
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// TWAP for a liquidation check. An expired loan is liquidatable by time alone,
+    /// so a thin window yields None for it instead of blocking the liquidation
+    pub fn liquidation_twap(history: &PriceHistory, current_time: i64, liquidatable_by_time: bool) -> Result<Option<u64>> {
+        match Self::calculate_twap(history, current_time) {
+            Ok(twap) => Ok(Some(twap)),
+            Err(_) if liquidatable_by_time => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Newest recorded checkpoint price, however old
+    pub fn latest_price(history: &PriceHistory) -> Result<u64> {
+        require!(history.count > 0, LendingError::InsufficientTwapSamples);
+        let index = (history.head as usize + PRICE_HISTORY_CAPACITY - 1) % PRICE_HISTORY_CAPACITY;
+        let price = history.checkpoints[index].price;
+        require!(price > 0, LendingError::ZeroPrice);
+        Ok(price)
+    }
+
+}
+
+/// Instruction a price attestation was signed for, so it cannot be replayed into another one
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum PriceAction {
+    CreateLoan = 0,
+    ExtendLoan = 1,
+}
+
+/// Ed25519 price attestation utilities
+/// The price authority signs (prefix, action, mint, price, timestamp, borrower) off-line and the
+/// client places an Ed25519 program instruction right before ours in the same transaction
+pub struct PriceAttestationUtils;
+
+/// Attested message body after the prefix: action, mint, price, timestamp, borrower
+const PRICE_MESSAGE_BODY_LEN: usize = 1 + 32 + 8 + 8 + 32;
+
+/// Ed25519 instruction layout: [num_signatures u8, padding u8, offsets (7 x u16)...]
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Instruction index value meaning "data lives in this same Ed25519 instruction"
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+impl PriceAttestationUtils {
+    /// Build the exact message the price authority is expected to sign
+    pub fn build_message(
+        action: PriceAction,
+        token_mint: &Pubkey,
+        price: u64,
+        timestamp: i64,
+        borrower: &Pubkey,
+    ) -> Vec<u8> {
+        let mut message = Vec::with_capacity(PRICE_SIGNATURE_PREFIX.len() + PRICE_MESSAGE_BODY_LEN);
+        message.extend_from_slice(PRICE_SIGNATURE_PREFIX);
+        message.push(action as u8);
+        message.extend_from_slice(token_mint.as_ref());
+        message.extend_from_slice(&price.to_le_bytes());
+        message.extend_from_slice(&timestamp.to_le_bytes());
+        message.extend_from_slice(borrower.as_ref());
+        message
+    }
+
+    /// Extract (signer, message) pairs from an Ed25519 program instruction
+    /// Signature, key and message must all live in the Ed25519 instruction itself,
+    /// otherwise the precompile could have verified bytes from a different instruction
+    fn parse_ed25519_signatures(data: &[u8]) -> Result<Vec<(Pubkey, &[u8])>> {
+        require!(!data.is_empty(), LendingError::InvalidPriceSignature);
+        let num_signatures = data[0] as usize;
+        require!(
+            num_signatures > 0
+                && data.len() >= ED25519_OFFSETS_START + num_signatures * ED25519_OFFSETS_LEN,
+            LendingError::InvalidPriceSignature
+        );
+
+        let mut signatures = Vec::with_capacity(num_signatures);
+        for i in 0..num_signatures {
+            let start = ED25519_OFFSETS_START + i * ED25519_OFFSETS_LEN;
+            let read_u16 = |index: usize| -> u16 {
+                let offset = start + index * 2;
+                u16::from_le_bytes([data[offset], data[offset + 1]])
+            };
+            let signature_instruction_index = read_u16(1);
+            let public_key_offset = read_u16(2) as usize;
+            let public_key_instruction_index = read_u16(3);
+            let message_offset = read_u16(4) as usize;
+            let message_size = read_u16(5) as usize;
+            let message_instruction_index = read_u16(6);
+
+            require!(
+                signature_instruction_index == ED25519_CURRENT_INSTRUCTION
+                    && public_key_instruction_index == ED25519_CURRENT_INSTRUCTION
+                    && message_instruction_index == ED25519_CURRENT_INSTRUCTION,
+                LendingError::InvalidPriceSignature
+            );
+
+            let signer = data
+                .get(public_key_offset..public_key_offset + 32)
+                .ok_or(LendingError::InvalidPriceSignature)?;
+            let message = data
+                .get(message_offset..message_offset + message_size)
+                .ok_or(LendingError::InvalidPriceSignature)?;
+            signatures.push((
+                Pubkey::try_from(signer).map_err(|_| LendingError::InvalidPriceSignature)?,
+                message,
+            ));
+        }
+        Ok(signatures)
+    }
+
+    /// Parse a price message, checking prefix, action, mint and borrower
+    /// Returns (price, timestamp)
+    fn parse_message(
+        message: &[u8],
+        action: PriceAction,
+        token_mint: &Pubkey,
+        borrower: &Pubkey,
+    ) -> Result<(u64, i64)> {
+        let prefix_len = PRICE_SIGNATURE_PREFIX.len();
+        require!(
+            message.len() == prefix_len + PRICE_MESSAGE_BODY_LEN
+                && &message[..prefix_len] == PRICE_SIGNATURE_PREFIX,
+            LendingError::InvalidPriceSignature
+        );
+        let body = &message[prefix_len..];
+        require!(
+            body[0] == action as u8
+                && &body[1..33] == token_mint.as_ref()
+                && &body[49..81] == borrower.as_ref(),
+            LendingError::InvalidPriceSignature
+        );
+        let price = u64::from_le_bytes(body[33..41].try_into().map_err(|_| LendingError::InvalidPriceSignature)?);
+        let timestamp = i64::from_le_bytes(body[41..49].try_into().map_err(|_| LendingError::InvalidPriceSignature)?);
+        Ok((price, timestamp))
+    }
+
+    /// Verify the instruction preceding the current one is an Ed25519 signature check
+    /// by `price_authority` over the expected price message
+    pub fn verify_ed25519_attestation(
+        instructions_sysvar: &AccountInfo,
+        price_authority: &Pubkey,
+        action: PriceAction,
+        token_mint: &Pubkey,
+        price: u64,
+        timestamp: i64,
+        borrower: &Pubkey,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::{ed25519_program, sysvar::instructions};
+
+        require!(
+            instructions::check_id(instructions_sysvar.key),
+            LendingError::InvalidPriceSignature
+        );
+
+        let current_index = instructions::load_current_index_checked(instructions_sysvar)?;
+        require!(current_index > 0, LendingError::InvalidPriceSignature);
+
+        let ed25519_ix = instructions::load_instruction_at_checked(
+            (current_index - 1) as usize,
+            instructions_sysvar,
+        )?;
+        require!(
+            ed25519_ix.program_id == ed25519_program::ID,
+            LendingError::InvalidPriceSignature
+        );
+
+        let signatures = Self::parse_ed25519_signatures(&ed25519_ix.data)?;
+        require!(signatures.len() == 1, LendingError::InvalidPriceSignature);
+        let (signer, message) = signatures[0];
+
+        require!(signer == *price_authority, LendingError::InvalidPriceAuthority);
+
+        let expected = Self::build_message(action, token_mint, price, timestamp, borrower);
+        require!(message == expected.as_slice(), LendingError::InvalidPriceSignature);
+
+        Ok(())
+    }
+
+    /// Accept a price approved either by a co-signing price authority or by an
+    /// Ed25519 attestation verified through the instructions sysvar
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_price_approval(
+        price_authority_signed: bool,
+        instructions_sysvar: Option<&AccountInfo>,
+        price_authority: &Pubkey,
+        action: PriceAction,
+        token_mint: &Pubkey,
+        price: u64,
+        timestamp: i64,
+        borrower: &Pubkey,
+    ) -> Result<()> {
+        if price_authority_signed {
+            return Ok(());
+        }
+
+        let instructions_sysvar = instructions_sysvar.ok_or(LendingError::InvalidPriceSignature)?;
+        Self::verify_ed25519_attestation(
+            instructions_sysvar,
+            price_authority,
+            action,
+            token_mint,
+            price,
+            timestamp,
+            borrower,
+        )
+    }
+
+    /// Median of a non-empty price list (average of the two middle values when even)
+    pub fn median(prices: &mut [u64]) -> Result<u64> {
+        require!(!prices.is_empty(), LendingError::PriceQuorumNotMet);
+        prices.sort_unstable();
+        let mid = prices.len() / 2;
+        if prices.len() % 2 == 1 {
+            Ok(prices[mid])
+        } else {
+            let sum = (prices[mid - 1] as u128) + (prices[mid] as u128);
+            Ok((sum / 2) as u64)
+        }
+    }
+
+    /// Resolve a price from the M-of-N price authority set
+    /// Collects every fresh attestation from registered signers in the preceding Ed25519
+    /// instructions, discards outliers beyond max_deviation_bps of the median and returns
+    /// the median of the rest, provided at least `threshold` attestations remain
+    #[inline(never)]
+    pub fn resolve_quorum_price(
+        instructions_sysvar: &AccountInfo,
+        authority_set: &PriceAuthoritySet,
+        action: PriceAction,
+        token_mint: &Pubkey,
+        borrower: &Pubkey,
+        current_time: i64,
+    ) -> Result<u64> {
+        use anchor_lang::solana_program::{ed25519_program, sysvar::instructions};
+
+        require!(
+            instructions::check_id(instructions_sysvar.key),
+            LendingError::InvalidPriceSignature
+        );
+
+        let current_index = instructions::load_current_index_checked(instructions_sysvar)? as usize;
+
+        let mut seen_signers: Vec<Pubkey> = Vec::with_capacity(authority_set.signer_count as usize);
+        let mut prices: Vec<u64> = Vec::with_capacity(authority_set.signer_count as usize);
+
+        for index in 0..current_index {
+            let ix = instructions::load_instruction_at_checked(index, instructions_sysvar)?;
+            if ix.program_id != ed25519_program::ID {
+                continue;
+            }
+
+            for (signer, message) in Self::parse_ed25519_signatures(&ix.data)? {
+                if !authority_set.is_signer(&signer) {
+                    continue;
+                }
+                require!(!seen_signers.contains(&signer), LendingError::DuplicatePriceSigner);
+
+                let (price, timestamp) = Self::parse_message(message, action, token_mint, borrower)?;
+                let price_age = current_time - timestamp;
+                require!(
+                    (0..=MAX_PRICE_SIGNATURE_AGE_SECONDS).contains(&price_age),
+                    LendingError::PriceSignatureExpired
+                );
+                require!(price > 0, LendingError::ZeroPrice);
+
+                seen_signers.push(signer);
+                prices.push(price);
+            }
+        }
+
+        let threshold = authority_set.threshold as usize;
+        require!(prices.len() >= threshold, LendingError::PriceQuorumNotMet);
+
+        // Drop outliers relative to the median of all attestations
+        let median = Self::median(&mut prices)?;
+        let mut agreeing: Vec<u64> = Vec::with_capacity(prices.len());
+        for price in prices {
+            let deviation = SafeMath::mul_div(price.abs_diff(median), BPS_DIVISOR, median)?;
+            if deviation <= authority_set.max_deviation_bps as u64 {
+                agreeing.push(price);
+            }
+        }
+        require!(agreeing.len() >= threshold, LendingError::PriceQuorumNotMet);
+
+        Self::median(&mut agreeing)
+    }
+
+    /// Resolve the approved price for a price-consuming instruction
+    /// With a price authority set the quorum median is used and `approved_price` /
+    /// `price_timestamp` are ignored, otherwise the single price authority must have
+    /// approved `approved_price` (co-signature or Ed25519 attestation)
+    /// Once `quorum_required` is set the single-authority path is rejected
+    #[allow(clippy::too_many_arguments)]
+    pub fn resolve_approved_price(
+        quorum_required: bool,
+        authority_set: Option<&PriceAuthoritySet>,
+        price_authority_signed: bool,
+        instructions_sysvar: Option<&AccountInfo>,
+        price_authority: &Pubkey,
+        action: PriceAction,
+        token_mint: &Pubkey,
+        borrower: &Pubkey,
+        approved_price: u64,
+        price_timestamp: i64,
+        current_time: i64,
+    ) -> Result<u64> {
+        if let Some(authority_set) = authority_set {
+            let instructions_sysvar = instructions_sysvar.ok_or(LendingError::InvalidPriceSignature)?;
+            return Self::resolve_quorum_price(
+                instructions_sysvar,
+                authority_set,
+                action,
+                token_mint,
+                borrower,
+                current_time,
+            );
+        }
+        require!(!quorum_required, LendingError::PriceQuorumRequired);
+
+        // Check price timestamp is recent (within 30 seconds)
+        let price_age = current_time - price_timestamp;
+        require!(
+            (0..=MAX_PRICE_SIGNATURE_AGE_SECONDS).contains(&price_age),
+            LendingError::PriceSignatureExpired
+        );
+        require!(approved_price > 0, LendingError::ZeroPrice);
+
+        Self::verify_price_approval(
+            price_authority_signed,
+            instructions_sysvar,
+            price_authority,
+            action,
+            token_mint,
+            approved_price,
+            price_timestamp,
+            borrower,
+        )?;
+
+        Ok(approved_price)
     }
-
 }
 
 /// Validation utilities
 pub struct ValidationUtils;
 
 impl ValidationUtils {
+    /// Reject an approved price further than `max_deviation_bps` from the pool TWAP
+    pub fn validate_price_deviation(approved_price: u64, pool_price: u64, max_deviation_bps: u16) -> Result<()> {
+        let deviation = if approved_price > pool_price {
+            SafeMath::mul_div(approved_price - pool_price, BPS_DIVISOR, pool_price)?
+        } else {
+            SafeMath::mul_div(pool_price - approved_price, BPS_DIVISOR, approved_price)?
+        };
+
+        require!(
+            deviation <= max_deviation_bps as u64,
+            LendingError::PriceDeviationTooHigh
+        );
+        Ok(())
+    }
+
     /// Validate loan duration
     pub fn validate_loan_duration(duration_seconds: u64) -> Result<()> {
         if duration_seconds < MIN_LOAN_DURATION {
@@ -593,14 +1924,70 @@ impl ValidationUtils {
         Ok(())
     }
 
-    /// Check if loan is liquidatable (by time)
-    pub fn is_loan_liquidatable_by_time(loan: &Loan, current_time: i64) -> bool {
-        current_time > loan.due_at
+    /// Validate risk limits against their bounds; `allow_unset` lets zero fields through (token overrides)
+    pub fn validate_risk_params(params: &RiskParams, allow_unset: bool) -> Result<()> {
+        let in_bounds = |value: u16, min: u16, max: u16| (allow_unset && value == 0) || (min..=max).contains(&value);
+        require!(
+            in_bounds(params.max_token_exposure_bps, 1, MAX_RISK_EXPOSURE_LIMIT_BPS)
+                && in_bounds(params.max_single_loan_bps, 1, MAX_RISK_EXPOSURE_LIMIT_BPS)
+                && in_bounds(params.max_liquidation_slippage_bps, 1, MAX_RISK_SLIPPAGE_BPS)
+                && in_bounds(params.max_price_deviation_bps, 1, MAX_RISK_PRICE_DEVIATION_BPS)
+                && in_bounds(
+                    params.liquidation_buffer_bps,
+                    MIN_RISK_LIQUIDATION_BUFFER_BPS,
+                    MAX_RISK_LIQUIDATION_BUFFER_BPS,
+                ),
+            LendingError::InvalidRiskParameters
+        );
+        Ok(())
+    }
+
+    /// Check if loan is liquidatable (by time) - past due_at plus any protocol pause time
+    pub fn is_loan_liquidatable_by_time(loan: &Loan, protocol_state: &ProtocolState, current_time: i64) -> bool {
+        current_time > loan.effective_due_at(protocol_state)
     }
 
     /// Check if loan is liquidatable (by price)
+    /// Legacy loans are never price-liquidatable until their prices are rescaled
     pub fn is_loan_liquidatable_by_price(loan: &Loan, current_price: u64) -> bool {
-        current_price <= loan.liquidation_price
+        loan.price_version >= LOAN_PRICE_VERSION && current_price <= loan.liquidation_price
+    }
+
+    /// Check the caller may liquidate - the authorized liquidator always may, anyone else only with
+    /// permissionless liquidation on and the exclusivity window passed. Returns true for keepers
+    pub fn validate_liquidator(
+        protocol_state: &ProtocolState,
+        loan: &Loan,
+        liquidator: &Pubkey,
+        by_time: bool,
+        by_price: bool,
+        current_time: i64,
+    ) -> Result<bool> {
+        if *liquidator == protocol_state.authorized_liquidator {
+            return Ok(false);
+        }
+        require!(protocol_state.permissionless_liquidation, LendingError::UnauthorizedLiquidator);
+
+        let liquidatable_since = Self::liquidatable_since(loan, protocol_state, by_time, by_price)
+            .ok_or(LendingError::LoanNotMarkedLiquidatable)?;
+        require!(
+            current_time >= liquidatable_since.saturating_add(protocol_state.liquidator_exclusivity_seconds),
+            LendingError::LiquidatorExclusivityActive
+        );
+        Ok(true)
+    }
+
+    /// When the loan became liquidatable - the (pause-extended) due date once expired, the recorded
+    /// mark once underwater. None if it is only underwater and nobody has marked it yet
+    pub fn liquidatable_since(
+        loan: &Loan,
+        protocol_state: &ProtocolState,
+        by_time: bool,
+        by_price: bool,
+    ) -> Option<i64> {
+        let time_start = by_time.then_some(loan.effective_due_at(protocol_state));
+        let price_start = (by_price && loan.liquidatable_since > 0).then_some(loan.liquidatable_since);
+        time_start.into_iter().chain(price_start).min()
     }
 
     /// Validate token account ownership
@@ -727,6 +2114,197 @@ impl ExposureCalculator {
     }
 }
 
+/// Per-borrower exposure bookkeeping
+/// Kept out of line so the handlers' stack frames stay within the BPF limit
+pub struct UserExposureTracker;
+
+impl UserExposureTracker {
+    /// Set owner and bump on a freshly created (init_if_needed) exposure account
+    fn ensure_initialized(exposure: &mut UserExposure, user: Pubkey, bump: u8) {
+        if exposure.user == Pubkey::default() {
+            exposure.user = user;
+            exposure.bump = bump;
+        }
+    }
+
+    /// Record a new loan, enforcing MAX_USER_EXPOSURE_BPS of the treasury balance
+    #[inline(never)]
+    pub fn record_borrow(
+        exposure: &mut UserExposure,
+        user: Pubkey,
+        bump: u8,
+        amount: u64,
+        treasury_balance: u64,
+    ) -> Result<()> {
+        Self::ensure_initialized(exposure, user, bump);
+
+        let max_user_exposure = ExposureCalculator::calculate_max_exposure(
+            treasury_balance,
+            MAX_USER_EXPOSURE_BPS as u64,
+        )?;
+        require!(
+            !ExposureCalculator::would_exceed_limit(exposure.total_borrowed, amount, max_user_exposure)?,
+            LendingError::UserExposureTooHigh
+        );
+
+        exposure.total_borrowed = SafeMath::add(exposure.total_borrowed, amount)?;
+        exposure.active_loans_count = SafeMath::add(exposure.active_loans_count, 1)?;
+        exposure.lifetime_borrowed = SafeMath::add(exposure.lifetime_borrowed, amount)?;
+        Ok(())
+    }
+
+    /// Record principal leaving a borrower's book (repayment or liquidation)
+    /// Loans opened before exposure tracking have no recorded borrow, so counters saturate at zero
+    #[inline(never)]
+    pub fn record_principal_closed(
+        exposure: &mut UserExposure,
+        user: Pubkey,
+        bump: u8,
+        principal: u64,
+        loan_closed: bool,
+        liquidated: bool,
+    ) -> Result<()> {
+        Self::ensure_initialized(exposure, user, bump);
+
+        exposure.total_borrowed = exposure.total_borrowed.saturating_sub(principal);
+        if loan_closed {
+            exposure.active_loans_count = exposure.active_loans_count.saturating_sub(1);
+            if liquidated {
+                exposure.loans_liquidated = SafeMath::add(exposure.loans_liquidated, 1)?;
+            } else {
+                exposure.loans_repaid = SafeMath::add(exposure.loans_repaid, 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Insurance fund deposits and bad debt payouts
+pub struct InsuranceFundUtils;
+
+impl InsuranceFundUtils {
+    /// Move an insurance fee from a signer into the fund
+    pub fn deposit<'info>(
+        fund: Option<&mut Account<'info, InsuranceFund>>,
+        from: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let fund = fund.ok_or(LendingError::MissingInsuranceFund)?;
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program,
+                anchor_lang::system_program::Transfer {
+                    from,
+                    to: fund.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        fund.total_deposited = SafeMath::add(fund.total_deposited, amount)?;
+        Ok(())
+    }
+
+    /// SOL the fund can pay out - everything above its rent-exempt minimum
+    pub fn available(fund: &AccountInfo) -> Result<u64> {
+        let rent_minimum = Rent::get()?.minimum_balance(fund.data_len());
+        Ok(fund.lamports().saturating_sub(rent_minimum))
+    }
+
+    /// Record a shortfall liquidation's bad debt and make the treasury whole from the fund
+    /// as far as it can. Returns the amount the fund paid
+    pub fn settle_shortfall<'info>(
+        protocol_state: &mut ProtocolState,
+        token_config: &mut TokenConfig,
+        fund: Option<&mut Account<'info, InsuranceFund>>,
+        treasury: &AccountInfo<'info>,
+        loan: Pubkey,
+        shortfall: u64,
+    ) -> Result<u64> {
+        if shortfall == 0 {
+            return Ok(0);
+        }
+
+        let insurance_payout = match fund {
+            Some(fund) => {
+                let payout = std::cmp::min(shortfall, Self::available(&fund.to_account_info())?);
+                if payout > 0 {
+                    **fund.to_account_info().try_borrow_mut_lamports()? -= payout;
+                    **treasury.try_borrow_mut_lamports()? += payout;
+                    fund.total_paid_out = SafeMath::add(fund.total_paid_out, payout)?;
+                }
+                payout
+            },
+            // Without the fund the treasury would silently absorb the loss
+            None if protocol_state.insurance_fee_bps > 0 => {
+                return Err(LendingError::MissingInsuranceFund.into());
+            },
+            None => 0,
+        };
+
+        protocol_state.total_bad_debt = SafeMath::add(protocol_state.total_bad_debt, shortfall)?;
+        protocol_state.total_insurance_payouts =
+            SafeMath::add(protocol_state.total_insurance_payouts, insurance_payout)?;
+        token_config.total_bad_debt = SafeMath::add(token_config.total_bad_debt, shortfall)?;
+
+        emit!(BadDebtRecorded {
+            loan,
+            token_mint: token_config.mint,
+            shortfall,
+            insurance_payout,
+            total_bad_debt: protocol_state.total_bad_debt,
+        });
+
+        Ok(insurance_payout)
+    }
+}
+
+/// Keeps principal, liquidation profit and fee revenue apart on ProtocolState and TokenConfig
+pub struct RevenueTracker;
+
+impl RevenueTracker {
+    /// Principal and loan fee paid back by the borrower (repay, partial repay, extension)
+    pub fn record_repayment(
+        protocol_state: &mut ProtocolState,
+        token_config: &mut TokenConfig,
+        principal: u64,
+        fee: u64,
+    ) -> Result<()> {
+        protocol_state.total_principal_recovered =
+            SafeMath::add(protocol_state.total_principal_recovered, principal)?;
+        protocol_state.total_fees_earned = SafeMath::add(protocol_state.total_fees_earned, fee)?;
+        token_config.total_principal_recovered =
+            SafeMath::add(token_config.total_principal_recovered, principal)?;
+        token_config.total_fee_revenue = SafeMath::add(token_config.total_fee_revenue, fee)?;
+        Ok(())
+    }
+
+    /// Debt recovered by a liquidation - principal first, anything above it is profit
+    pub fn record_liquidation(
+        protocol_state: &mut ProtocolState,
+        token_config: &mut TokenConfig,
+        debt_recovered: u64,
+        principal: u64,
+    ) -> Result<()> {
+        let principal_recovered = std::cmp::min(debt_recovered, principal);
+        let profit = SafeMath::sub(debt_recovered, principal_recovered)?;
+
+        protocol_state.total_principal_recovered =
+            SafeMath::add(protocol_state.total_principal_recovered, principal_recovered)?;
+        protocol_state.total_liquidation_profit =
+            SafeMath::add(protocol_state.total_liquidation_profit, profit)?;
+        token_config.total_principal_recovered =
+            SafeMath::add(token_config.total_principal_recovered, principal_recovered)?;
+        token_config.total_liquidation_profit =
+            SafeMath::add(token_config.total_liquidation_profit, profit)?;
+        Ok(())
+    }
+}
+
 /// Validate a PumpSwap pool account
 pub struct PumpSwapPoolValidator;
 
@@ -830,4 +2408,1034 @@ impl PumpSwapPoolValidator {
         // 6. Extract and return vault addresses
         Self::extract_vaults(&pool_data)
     }
-}
\ No newline at end of file
+}
+
+/// Validate a pump.fun bonding curve account
+pub struct PumpfunCurveValidator;
+
+impl PumpfunCurveValidator {
+    /// Bonding curve PDA for a mint
+    pub fn derive_bonding_curve(token_mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[PUMPFUN_BONDING_CURVE_SEED, token_mint.as_ref()],
+            &PUMPFUN_PROGRAM_ID,
+        ).0
+    }
+
+    /// PumpSwap pool the curve migrates into when it completes
+    pub fn derive_canonical_pumpswap_pool(token_mint: &Pubkey) -> Pubkey {
+        let (pool_authority, _) = Pubkey::find_program_address(
+            &[PUMPFUN_POOL_AUTHORITY_SEED, token_mint.as_ref()],
+            &PUMPFUN_PROGRAM_ID,
+        );
+        Pubkey::find_program_address(
+            &[
+                PUMPSWAP_POOL_SEED,
+                &PUMPSWAP_CANONICAL_POOL_INDEX.to_le_bytes(),
+                pool_authority.as_ref(),
+                token_mint.as_ref(),
+                WSOL_MINT.as_ref(),
+            ],
+            &PUMPSWAP_PROGRAM_ID,
+        ).0
+    }
+
+    /// Whether the curve has completed and the token migrated to PumpSwap
+    pub fn is_complete(curve_data: &[u8]) -> Result<bool> {
+        require!(curve_data.len() >= PUMPFUN_CURVE_MIN_LEN, LendingError::InvalidPoolData);
+        Ok(curve_data[PUMPFUN_COMPLETE_OFFSET] != 0)
+    }
+
+    /// Virtual (token, sol) reserves that price the curve
+    pub fn read_virtual_reserves(curve_data: &[u8]) -> Result<(u64, u64)> {
+        require!(curve_data.len() >= PUMPFUN_CURVE_MIN_LEN, LendingError::InvalidPoolData);
+        Ok((
+            PriceFeedUtils::read_u64_at(curve_data, PUMPFUN_VIRTUAL_TOKEN_OFFSET)?,
+            PriceFeedUtils::read_u64_at(curve_data, PUMPFUN_VIRTUAL_SOL_OFFSET)?,
+        ))
+    }
+
+    /// Owner, discriminator and PDA checks - the curve must belong to `token_mint`
+    pub fn validate_full(curve_account: &AccountInfo, token_mint: &Pubkey) -> Result<()> {
+        require!(
+            curve_account.owner == &PUMPFUN_PROGRAM_ID,
+            LendingError::InvalidPoolOwner
+        );
+
+        let curve_data = curve_account.try_borrow_data()?;
+        require!(curve_data.len() >= PUMPFUN_CURVE_MIN_LEN, LendingError::InvalidPoolData);
+        require!(
+            curve_data[0..8] == PUMPFUN_BONDING_CURVE_DISCRIMINATOR,
+            LendingError::InvalidPoolData
+        );
+
+        // The curve does not store its mint - bind it through the PDA instead
+        require!(
+            curve_account.key() == Self::derive_bonding_curve(token_mint),
+            LendingError::PoolTokenMismatch
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHIRLPOOL_ACCOUNT_LEN: usize = 653;
+
+    /// Whirlpool account serialized field by field in the declaration order of Orca's
+    /// `Whirlpool` struct, independently of the reader's offset constants. Fields the reader
+    /// ignores get non-zero filler so a misplaced read shows up as a wrong price or mint
+    fn whirlpool_fixture(sqrt_price: u128, mint_a: &Pubkey, mint_b: &Pubkey) -> Vec<u8> {
+        let discriminator = anchor_lang::solana_program::hash::hash(b"account:Whirlpool").to_bytes();
+        let mut data = Vec::with_capacity(WHIRLPOOL_ACCOUNT_LEN);
+        data.extend_from_slice(&discriminator[..8]);
+        data.extend_from_slice(&[0x11; 32]); // whirlpools_config
+        data.push(255); // whirlpool_bump
+        data.extend_from_slice(&64u16.to_le_bytes()); // tick_spacing
+        data.extend_from_slice(&64u16.to_le_bytes()); // fee_tier_index_seed
+        data.extend_from_slice(&3000u16.to_le_bytes()); // fee_rate
+        data.extend_from_slice(&1300u16.to_le_bytes()); // protocol_fee_rate
+        data.extend_from_slice(&u128::MAX.to_le_bytes()); // liquidity
+        data.extend_from_slice(&sqrt_price.to_le_bytes());
+        data.extend_from_slice(&(-12_345i32).to_le_bytes()); // tick_current_index
+        data.extend_from_slice(&u64::MAX.to_le_bytes()); // protocol_fee_owed_a
+        data.extend_from_slice(&u64::MAX.to_le_bytes()); // protocol_fee_owed_b
+        data.extend_from_slice(mint_a.as_ref());
+        data.extend_from_slice(&[0xAA; 32]); // token_vault_a
+        data.extend_from_slice(&u128::MAX.to_le_bytes()); // fee_growth_global_a
+        data.extend_from_slice(mint_b.as_ref());
+        data.extend_from_slice(&[0xBB; 32]); // token_vault_b
+        data.extend_from_slice(&u128::MAX.to_le_bytes()); // fee_growth_global_b
+        data.extend_from_slice(&u64::MAX.to_le_bytes()); // reward_last_updated_timestamp
+        data.extend_from_slice(&[0xCC; 3 * 128]); // reward_infos
+        assert_eq!(data.len(), WHIRLPOOL_ACCOUNT_LEN);
+        data
+    }
+
+    fn memecoin_mint() -> Pubkey {
+        Pubkey::new_from_array([7u8; 32])
+    }
+
+    /// Mainnet pool account dumps, see the file header for the line format
+    const POOL_ACCOUNT_DUMPS: &str = include_str!("../tests/fixtures/pool_accounts.txt");
+
+    /// Minimal standard base64 decoder for the checked-in dumps
+    fn decode_base64(input: &str) -> Vec<u8> {
+        let value = |c: u8| -> u32 {
+            match c {
+                b'A'..=b'Z' => (c - b'A') as u32,
+                b'a'..=b'z' => (c - b'a' + 26) as u32,
+                b'0'..=b'9' => (c - b'0' + 52) as u32,
+                b'+' => 62,
+                b'/' => 63,
+                _ => panic!("invalid base64 character {:?}", c as char),
+            }
+        };
+        let mut out = Vec::with_capacity(input.len() * 3 / 4);
+        for chunk in input.trim_end_matches('=').as_bytes().chunks(4) {
+            let bits = chunk.iter().enumerate().fold(0u32, |acc, (i, &c)| acc | value(c) << (18 - 6 * i));
+            out.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+        }
+        out
+    }
+
+    /// Read a dumped pool with the reader for its type
+    fn read_dumped_pool_price(pool_type: &str, data: &[u8], token_mint: &Pubkey, decimals: u8, vaults: &[&str]) -> u64 {
+        let vault = |i: usize| vaults[i].parse::<Pubkey>().unwrap();
+        let amount = |i: usize| vaults[i].parse::<u64>().unwrap();
+        match pool_type {
+            "whirlpool" => PriceFeedUtils::read_whirlpool_price(data, token_mint, &WSOL_MINT, decimals),
+            "raydium_amm" => PriceFeedUtils::read_raydium_price(data, token_mint, &WSOL_MINT, decimals),
+            "raydium_clmm" => PriceFeedUtils::read_raydium_clmm_price(data, token_mint, &WSOL_MINT, decimals),
+            "meteora_dlmm" => PriceFeedUtils::read_meteora_dlmm_price(data, token_mint, &WSOL_MINT, decimals),
+            "meteora_damm_v2" => PriceFeedUtils::read_meteora_damm_v2_price(data, token_mint, &WSOL_MINT, decimals),
+            "pumpfun" => PriceFeedUtils::read_pumpfun_price(data, decimals),
+            "raydium_cpmm" => PriceFeedUtils::read_raydium_cpmm_price(
+                data, token_mint, &WSOL_MINT, &vault(0), amount(1), &vault(2), amount(3), decimals,
+            ),
+            "pumpswap" => PriceFeedUtils::read_pumpswap_price(
+                data, amount(1), amount(3), &vault(0), &vault(2), decimals,
+            ),
+            other => panic!("unknown pool type {}", other),
+        }
+        .unwrap_or_else(|err| panic!("{} dump rejected: {:?}", pool_type, err))
+    }
+
+    #[test]
+    fn full_mul_u128_matches_known_products() {
+        assert_eq!(SafeMath::full_mul_u128(u128::MAX, 1), (0, u128::MAX));
+        assert_eq!(SafeMath::full_mul_u128(1u128 << 64, 1u128 << 64), (1, 0));
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1
+        assert_eq!(SafeMath::full_mul_u128(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+    }
+
+    #[test]
+    fn mul_shift_u128_shifts_256_bit_product() {
+        assert_eq!(SafeMath::mul_shift_u128(1u128 << 100, 1u128 << 100, 128).unwrap(), 1u128 << 72);
+        assert_eq!(SafeMath::mul_shift_u128(3, 5, 1).unwrap(), 7);
+        assert!(SafeMath::mul_shift_u128(u128::MAX, u128::MAX, 64).is_err());
+        assert!(SafeMath::mul_shift_u128(1, 1, 0).is_err());
+    }
+
+    #[test]
+    fn whirlpool_price_with_sol_as_token_b() {
+        // sqrt(0.25) = 0.5 -> 0.25 lamports per raw token unit
+        let data = whirlpool_fixture(1u128 << 63, &memecoin_mint(), &WSOL_MINT);
+        let price = PriceFeedUtils::read_whirlpool_price(&data, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert_eq!(price, 250_000);
+    }
+
+    #[test]
+    fn whirlpool_price_with_sol_as_token_a() {
+        // sqrt(4) = 2 -> 4 raw token units per lamport -> 0.25 lamports per raw token unit
+        let data = whirlpool_fixture(1u128 << 65, &WSOL_MINT, &memecoin_mint());
+        let price = PriceFeedUtils::read_whirlpool_price(&data, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert!((249_999..=250_000).contains(&price));
+    }
+
+    #[test]
+    fn whirlpool_price_non_trivial_sqrt_price() {
+        // 0.0015 lamports per raw token unit, both orderings
+        let sol_b = whirlpool_fixture(714_439_325_892_274_567, &memecoin_mint(), &WSOL_MINT);
+        let price = PriceFeedUtils::read_whirlpool_price(&sol_b, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert!((1_499..=1_500).contains(&price));
+
+        let sol_a = whirlpool_fixture(476_292_883_928_183_026_778, &WSOL_MINT, &memecoin_mint());
+        let price = PriceFeedUtils::read_whirlpool_price(&sol_a, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert!((1_499..=1_501).contains(&price));
+    }
+
+    #[test]
+    fn whirlpool_rejects_bad_discriminator() {
+        let mut data = whirlpool_fixture(1u128 << 64, &memecoin_mint(), &WSOL_MINT);
+        data[0] ^= 0xFF;
+        assert!(PriceFeedUtils::read_whirlpool_price(&data, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+    }
+
+    #[test]
+    fn whirlpool_rejects_wrong_mints() {
+        let other_mint = Pubkey::new_from_array([9u8; 32]);
+
+        // Pool is for a different token
+        let data = whirlpool_fixture(1u128 << 64, &other_mint, &WSOL_MINT);
+        assert!(PriceFeedUtils::read_whirlpool_price(&data, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+
+        // Pool has no SOL side
+        let data = whirlpool_fixture(1u128 << 64, &other_mint, &memecoin_mint());
+        assert!(PriceFeedUtils::read_whirlpool_price(&data, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+    }
+
+    #[test]
+    fn whirlpool_rejects_short_account() {
+        let data = whirlpool_fixture(1u128 << 64, &memecoin_mint(), &WSOL_MINT);
+        assert!(PriceFeedUtils::read_whirlpool_price(&data[..WHIRLPOOL_MIN_LEN - 1], &memecoin_mint(), &WSOL_MINT, 0).is_err());
+    }
+
+    #[test]
+    #[ignore = "no mainnet pool dumps are checked in yet - see tests/fixtures/pool_accounts.txt"]
+    fn pool_reader_matches_mainnet_dumps() {
+        assert_eq!(decode_base64("AAEC/w=="), vec![0, 1, 2, 255]);
+
+        let entries: Vec<&str> = POOL_ACCOUNT_DUMPS
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        assert!(!entries.is_empty(), "tests/fixtures/pool_accounts.txt has no pool dumps");
+        for line in entries {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            assert!(fields.len() >= 6, "malformed fixture line: {}", line);
+            let token_mint: Pubkey = fields[1].parse().unwrap();
+            let decimals: u8 = fields[2].parse().unwrap();
+            let (min_price, max_price): (u64, u64) = (fields[3].parse().unwrap(), fields[4].parse().unwrap());
+            let data = decode_base64(fields[5]);
+
+            let price = read_dumped_pool_price(fields[0], &data, &token_mint, decimals, &fields[6..]);
+            assert!(
+                (min_price..=max_price).contains(&price),
+                "{} {}: price {} outside {}..={}", fields[0], fields[1], price, min_price, max_price
+            );
+        }
+    }
+
+    #[test]
+    fn dlmm_bin_price_matches_closed_form() {
+        // 1.0025^100 = 1.283624...
+        assert_eq!(PriceFeedUtils::price_from_dlmm_bin(0, 25, true, 0).unwrap(), 1_000_000);
+        let price = PriceFeedUtils::price_from_dlmm_bin(100, 25, true, 0).unwrap();
+        assert!((1_283_623..=1_283_625).contains(&price));
+
+        // 1.01^-693 = 0.00101225..., reached from either token ordering
+        let sol_y = PriceFeedUtils::price_from_dlmm_bin(-693, 100, true, 0).unwrap();
+        let sol_x = PriceFeedUtils::price_from_dlmm_bin(693, 100, false, 0).unwrap();
+        assert!((1_011..=1_012).contains(&sol_y));
+        assert_eq!(sol_x, sol_y);
+    }
+
+    /// Decimals covered by the normalisation tests (5, 6 = pump.fun, 8, 9 = WSOL-like)
+    const TEST_DECIMALS: [u8; 4] = [5, 6, 8, 9];
+
+    #[test]
+    fn reserve_price_is_per_whole_token_for_any_decimals() {
+        // 30 SOL against 1B whole tokens = 30 lamports per whole token
+        for decimals in TEST_DECIMALS {
+            let token_reserve = 1_000_000_000 * 10u64.pow(decimals as u32);
+            let price = PriceFeedUtils::price_from_reserves(30_000_000_000, token_reserve, decimals).unwrap();
+            assert_eq!(price, 30 * PRICE_SCALE as u64, "decimals {}", decimals);
+        }
+    }
+
+    #[test]
+    fn sqrt_price_is_per_whole_token_for_any_decimals() {
+        // 0.25 lamports per raw unit
+        for decimals in TEST_DECIMALS {
+            let data = whirlpool_fixture(1u128 << 63, &memecoin_mint(), &WSOL_MINT);
+            let price = PriceFeedUtils::read_whirlpool_price(&data, &memecoin_mint(), &WSOL_MINT, decimals).unwrap();
+            let expected = 250_000u64 * 10u64.pow(decimals as u32);
+            assert_eq!(price, expected, "decimals {}", decimals);
+        }
+    }
+
+    #[test]
+    fn loan_amount_independent_of_decimals() {
+        // 1000 whole tokens at 30 lamports each, 50% LTV
+        let price = 30 * PRICE_SCALE as u64;
+        for decimals in TEST_DECIMALS {
+            let collateral = 1_000 * 10u64.pow(decimals as u32);
+            assert_eq!(LoanCalculator::calculate_collateral_value(collateral, price, decimals).unwrap(), 30_000);
+            assert_eq!(LoanCalculator::calculate_loan_amount(collateral, price, 5_000, decimals).unwrap(), 15_000);
+        }
+    }
+
+    #[test]
+    fn liquidation_price_uses_canonical_scale() {
+        // 1_000_000 whole tokens backing 1 SOL at 50% LTV + 40% buffer (90% cap)
+        // -> liquidation at 1e9 / (1e6 * 0.9) = 1111.11 lamports per whole token
+        for decimals in TEST_DECIMALS {
+            let collateral = 1_000_000 * 10u64.pow(decimals as u32);
+            let price = LoanCalculator::calculate_liquidation_price(
+                1_000_000_000,
+                collateral,
+                5_000,
+                LOAN_LIQUIDATION_BUFFER_BPS,
+                decimals,
+            ).unwrap();
+            assert_eq!(price, 1_111_111_111, "decimals {}", decimals);
+
+            // Collateral is worth exactly the debt / liquidation LTV at that price
+            let value = LoanCalculator::calculate_collateral_value(collateral, price, decimals).unwrap();
+            assert!((1_111_111_110..=1_111_111_111).contains(&value));
+        }
+    }
+
+    #[test]
+    fn bonding_curve_ltv_cap_survives_duration_bonus() {
+        // Gold tier at the shortest duration would reach 62.5% - curve tokens stay at the cap
+        let boosted = LoanCalculator::calculate_duration_adjusted_ltv(5_000, MIN_LOAN_DURATION).unwrap();
+        assert!(boosted > PUMPFUN_MAX_LTV_BPS);
+        assert_eq!(LoanCalculator::cap_ltv_for_pool(boosted, PoolType::Pumpfun), PUMPFUN_MAX_LTV_BPS);
+        assert_eq!(LoanCalculator::cap_ltv_for_pool(boosted, PoolType::PumpSwap), boosted);
+    }
+
+    #[test]
+    fn bonding_curve_completion_flag() {
+        let mut data = vec![0u8; PUMPFUN_CURVE_MIN_LEN];
+        data[0..8].copy_from_slice(&PUMPFUN_BONDING_CURVE_DISCRIMINATOR);
+        assert!(!PumpfunCurveValidator::is_complete(&data).unwrap());
+        data[PUMPFUN_COMPLETE_OFFSET] = 1;
+        assert!(PumpfunCurveValidator::is_complete(&data).unwrap());
+        assert!(PumpfunCurveValidator::is_complete(&data[..PUMPFUN_COMPLETE_OFFSET]).is_err());
+    }
+
+    #[test]
+    fn shortfall_liquidation_reports_bad_debt() {
+        // Recovered less than principal - everything to the treasury, the rest is bad debt
+        assert_eq!(
+            LoanCalculator::split_liquidation_proceeds(700, 1_000).unwrap(),
+            (700, 0, 300)
+        );
+        // Exactly the principal - all of it back to the treasury
+        assert_eq!(
+            LoanCalculator::split_liquidation_proceeds(1_000, 1_000).unwrap(),
+            (1_000, 0, 0)
+        );
+        // Between principal and principal / 95% - operations never cut into the principal
+        assert_eq!(
+            LoanCalculator::split_liquidation_proceeds(1_040, 1_000).unwrap(),
+            (1_038, 2, 0)
+        );
+        // Fee and penalty fully recovered - operations take 5% of the part above principal
+        assert_eq!(
+            LoanCalculator::split_liquidation_proceeds(1_070, 1_000).unwrap(),
+            (1_067, 3, 0)
+        );
+        // Insurance slice never exceeds the treasury share it is carved from
+        assert_eq!(LoanCalculator::split_insurance_fee(1_000, 400, 1_000).unwrap(), (300, 100));
+        assert_eq!(LoanCalculator::split_insurance_fee(1_000, 50, 1_000).unwrap(), (0, 50));
+    }
+
+    #[test]
+    fn token_risk_overrides_fall_back_to_global() {
+        let global = RiskParams::DEFAULT;
+        ValidationUtils::validate_risk_params(&global, false).unwrap();
+
+        let overrides = RiskParams { max_token_exposure_bps: 300, ..RiskParams::default() };
+        ValidationUtils::validate_risk_params(&overrides, true).unwrap();
+        // Unset fields are only valid as overrides
+        assert!(ValidationUtils::validate_risk_params(&overrides, false).is_err());
+
+        let effective = global.with_overrides(&overrides);
+        assert_eq!(effective.max_token_exposure_bps, 300);
+        assert_eq!(effective.max_single_loan_bps, global.max_single_loan_bps);
+        assert_eq!(effective.liquidation_buffer_bps, LOAN_LIQUIDATION_BUFFER_BPS);
+
+        let too_loose = RiskParams { max_liquidation_slippage_bps: MAX_RISK_SLIPPAGE_BPS + 1, ..global };
+        assert!(ValidationUtils::validate_risk_params(&too_loose, false).is_err());
+    }
+
+    #[test]
+    fn roles_are_scoped_and_admin_holds_all() {
+        let admin = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let protocol_state = ProtocolState { admin, guardian, ..ProtocolState::default() };
+
+        assert!(protocol_state.has_role(&guardian, Role::Guardian));
+        assert!(!protocol_state.has_role(&guardian, Role::Treasurer));
+        assert!(protocol_state.has_role(&admin, Role::Treasurer));
+        // An unassigned role is not held by the default key
+        assert!(!protocol_state.has_role(&Pubkey::default(), Role::RiskManager));
+    }
+
+    #[test]
+    fn multisig_counts_member_approvals_only() {
+        let mut multisig = Multisig { signer_count: 3, threshold: 2, ..Multisig::default() };
+        for i in 0..3 {
+            multisig.signers[i] = Pubkey::new_unique();
+        }
+        let proposal = MultisigProposal {
+            id: 0,
+            action: MultisigAction::UpdatePriceAuthority { new_price_authority: Pubkey::new_unique() },
+            proposed_by: multisig.signers[0],
+            proposed_at: 0,
+            execute_after: 0,
+            approvals: (1 << multisig.signer_index(&multisig.signers[0]).unwrap())
+                | (1 << multisig.signer_index(&multisig.signers[2]).unwrap()),
+            bump: 0,
+        };
+
+        assert_eq!(proposal.approval_count(), 2);
+        assert_eq!(multisig.signer_index(&Pubkey::new_unique()), None);
+        // Unused slots hold the default key, which must not count as a member
+        assert_eq!(multisig.signer_index(&Pubkey::default()), None);
+        assert_eq!(proposal.action.delay(), PARAMETER_CHANGE_DELAY);
+        assert_eq!(MultisigAction::EmergencyDrain { recipient: Pubkey::new_unique() }.delay(), 0);
+        let quorum_change = MultisigAction::UpdatePriceQuorum { threshold: Some(1), max_deviation_bps: None };
+        assert_eq!(quorum_change.delay(), PARAMETER_CHANGE_DELAY);
+    }
+
+    #[test]
+    fn multisig_update_replaces_signers_and_stales_proposals() {
+        use crate::instructions::multisig::apply_multisig_update;
+
+        let mut multisig = Multisig { signer_count: 3, threshold: 2, ..Multisig::default() };
+        for i in 0..3 {
+            multisig.signers[i] = Pubkey::new_unique();
+        }
+        let removed = multisig.signers[2];
+        let mut signers = [Pubkey::default(); MAX_MULTISIG_SIGNERS];
+        signers[0] = multisig.signers[0];
+        signers[1] = Pubkey::new_unique();
+
+        // Same checks as initialize_multisig
+        assert!(apply_multisig_update(&mut multisig, &signers, 2, 0, 100).is_err());
+        assert!(apply_multisig_update(&mut multisig, &signers, 2, 3, 100).is_err());
+        assert!(apply_multisig_update(&mut multisig, &signers, 3, 2, 100).is_err());
+        assert!(apply_multisig_update(&mut multisig, &signers, 0, 1, 100).is_err());
+        let mut duplicated = signers;
+        duplicated[1] = duplicated[0];
+        assert!(apply_multisig_update(&mut multisig, &duplicated, 2, 1, 100).is_err());
+        assert_eq!(multisig.signer_count, 3);
+
+        let update = MultisigAction::UpdateMultisig { signers, signer_count: 2, threshold: 2 };
+        assert_eq!(update.delay(), PARAMETER_CHANGE_DELAY);
+        let mut proposal = MultisigProposal {
+            id: 0,
+            action: update,
+            proposed_by: signers[0],
+            proposed_at: 50,
+            execute_after: 50 + PARAMETER_CHANGE_DELAY,
+            approvals: 0b111,
+            bump: 0,
+        };
+        assert!(multisig.is_current(&proposal));
+
+        apply_multisig_update(&mut multisig, &signers, 2, 2, 100).unwrap();
+        assert_eq!(multisig.signer_count, 2);
+        assert_eq!(multisig.threshold, 2);
+        assert_eq!(multisig.signers[1], signers[1]);
+        // The dropped slot is cleared and the old member is out
+        assert_eq!(multisig.signers[2], Pubkey::default());
+        assert_eq!(multisig.signer_index(&removed), None);
+        // Approvals cast under the old set no longer count
+        assert!(!multisig.is_current(&proposal));
+        proposal.proposed_at = 101;
+        assert!(multisig.is_current(&proposal));
+    }
+
+    #[test]
+    fn pause_time_extends_due_date() {
+        // 1h of pauses before the loan was opened, 2h more since
+        let protocol_state = ProtocolState { total_paused_seconds: 3 * 3600, ..ProtocolState::default() };
+        let loan = Loan { due_at: 100_000, paused_seconds_at_open: 3600, ..Loan::default() };
+
+        assert_eq!(loan.effective_due_at(&protocol_state), 100_000 + 2 * 3600);
+        assert!(!ValidationUtils::is_loan_liquidatable_by_time(&loan, &protocol_state, 100_001));
+        assert!(ValidationUtils::is_loan_liquidatable_by_time(&loan, &protocol_state, 100_000 + 2 * 3600 + 1));
+        assert_eq!(
+            ValidationUtils::liquidatable_since(&loan, &protocol_state, true, false),
+            Some(100_000 + 2 * 3600)
+        );
+    }
+
+    #[test]
+    fn extension_fee_base_is_the_opened_term() {
+        let mut loan = Loan { created_at: 1_000, due_at: 1_000 + 86_400, original_duration: 86_400, ..Loan::default() };
+        // An earlier extension moves due_at but not the fee base
+        loan.due_at += 2 * 86_400;
+        assert_eq!(loan.original_term(), 86_400);
+
+        // Legacy loans fall back to the current term
+        loan.original_duration = 0;
+        assert_eq!(loan.original_term(), 3 * 86_400);
+    }
+
+    #[test]
+    fn approved_price_deviation_from_twap() {
+        ValidationUtils::validate_price_deviation(1_200, 1_000, 2_000).unwrap();
+        ValidationUtils::validate_price_deviation(1_000, 1_200, 2_000).unwrap();
+        assert!(ValidationUtils::validate_price_deviation(1_201, 1_000, 2_000).is_err());
+    }
+
+    #[test]
+    fn keeper_bonus_comes_from_recovered_penalty() {
+        let principal = 1_000_000;
+        let penalty_bps = 500;
+        let debt = LoanCalculator::calculate_liquidation_debt(principal, penalty_bps).unwrap();
+        let penalty = 50_000;
+
+        // Debt fully covered: 2% of proceeds, within the 5% penalty
+        let proceeds = debt + 100_000;
+        let bonus = LoanCalculator::calculate_keeper_bonus(proceeds, debt, principal, penalty_bps, 200).unwrap();
+        assert_eq!(bonus, proceeds * 200 / 10_000);
+        // A large bonus rate is capped at the penalty
+        let bonus = LoanCalculator::calculate_keeper_bonus(proceeds, debt, principal, penalty_bps, 1_000).unwrap();
+        assert_eq!(bonus, penalty);
+
+        // Penalty only partly recovered - the bonus is capped at that part
+        let owed = LoanCalculator::calculate_total_owed(principal, PROTOCOL_FEE_BPS).unwrap();
+        let bonus = LoanCalculator::calculate_keeper_bonus(owed + 3_000, owed + 3_000, principal, penalty_bps, 200).unwrap();
+        assert_eq!(bonus, 3_000);
+
+        // Shortfall - proceeds below principal pay no bonus
+        let bonus = LoanCalculator::calculate_keeper_bonus(800_000, 800_000, principal, penalty_bps, 200).unwrap();
+        assert_eq!(bonus, 0);
+        let (_, _, shortfall) = LoanCalculator::split_liquidation_proceeds(800_000 - bonus, principal).unwrap();
+        assert_eq!(shortfall, 200_000);
+    }
+
+    #[test]
+    fn thin_twap_window_only_blocks_price_liquidation() {
+        // A single stale checkpoint - the TWAP window has too few samples
+        let mut history = PriceHistory::default();
+        PriceFeedUtils::record_checkpoint(&mut history, 5_000, 1_000).unwrap();
+        let now = 1_000 + TWAP_WINDOW_SECONDS * 10;
+
+        assert!(PriceFeedUtils::calculate_twap(&history, now).is_err());
+        assert!(PriceFeedUtils::liquidation_twap(&history, now, false).is_err());
+        assert_eq!(PriceFeedUtils::liquidation_twap(&history, now, true).unwrap(), None);
+        assert_eq!(PriceFeedUtils::latest_price(&history).unwrap(), 5_000);
+    }
+
+    #[test]
+    fn twap_is_the_window_median() {
+        let mut history = PriceHistory::default();
+        // Two spiked checkpoints among five cannot move the median
+        for (i, price) in [1_000, 50_000, 1_010, 990, 60_000].into_iter().enumerate() {
+            PriceFeedUtils::record_checkpoint(&mut history, price, 1_000 + 30 * i as i64).unwrap();
+        }
+        let now = 1_000 + 30 * 4;
+        assert_eq!(PriceFeedUtils::calculate_twap(&history, now).unwrap(), 1_010);
+
+        // An even sample count averages the two middle prices
+        PriceFeedUtils::record_checkpoint(&mut history, 1_000, now + 30).unwrap();
+        assert_eq!(PriceFeedUtils::calculate_twap(&history, now + 30).unwrap(), 1_005);
+
+        // Fewer than MIN_TWAP_SAMPLES inside the window
+        assert!(PriceFeedUtils::calculate_twap(&history, now + TWAP_WINDOW_SECONDS).is_err());
+    }
+
+    #[test]
+    fn legacy_loan_prices_rescale_before_price_liquidation() {
+        // 1 SOL against 1M whole tokens (6 decimals) at a 90% effective LTV
+        let sol_borrowed = 1_000_000_000u64;
+        let collateral_amount = 1_000_000_000_000u64;
+        let (ltv_bps, buffer_bps) = (7_000, 2_000);
+
+        // Pre-upgrade calculate_liquidation_price: sol_borrowed * BPS / (collateral * ltv / BPS)
+        let legacy_liquidation_price = sol_borrowed * BPS_DIVISOR / (collateral_amount * 9_000 / BPS_DIVISOR);
+        assert_eq!(legacy_liquidation_price, 11);
+        let mut loan = Loan {
+            sol_borrowed,
+            collateral_amount,
+            // Legacy PumpSwap entry prices were divided by 1000
+            entry_price: 1,
+            liquidation_price: legacy_liquidation_price,
+            ..Loan::default()
+        };
+
+        // Mismatched units: blocked instead of comparing a canonical TWAP against a legacy price
+        assert!(!ValidationUtils::is_loan_liquidatable_by_price(&loan, 1));
+
+        LoanCalculator::upgrade_loan_prices(&mut loan, ltv_bps, buffer_bps, 6).unwrap();
+        assert_eq!(loan.price_version, LOAN_PRICE_VERSION);
+        // 1000 lamports per token / 0.9
+        assert_eq!(loan.liquidation_price, 1_111_111_111);
+        assert_eq!(loan.entry_price, 0);
+        assert!(ValidationUtils::is_loan_liquidatable_by_price(&loan, 1_000 * PRICE_SCALE as u64));
+        assert!(!ValidationUtils::is_loan_liquidatable_by_price(&loan, 1_200 * PRICE_SCALE as u64));
+
+        // A second upgrade leaves current prices alone
+        LoanCalculator::upgrade_loan_prices(&mut loan, 5_000, 0, 6).unwrap();
+        assert_eq!(loan.liquidation_price, 1_111_111_111);
+    }
+
+    #[test]
+    fn single_price_authority_rejected_once_quorum_required() {
+        let price_authority = Pubkey::new_unique();
+        let resolve = |quorum_required| {
+            PriceAttestationUtils::resolve_approved_price(
+                quorum_required,
+                None,
+                true,
+                None,
+                &price_authority,
+                PriceAction::CreateLoan,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                1_000,
+                100,
+                100,
+            )
+        };
+
+        // A co-signing price authority still works before the set exists
+        assert_eq!(resolve(false).unwrap(), 1_000);
+        assert_eq!(resolve(true).unwrap_err(), LendingError::PriceQuorumRequired.into());
+    }
+
+    #[test]
+    fn liquidation_revenue_excludes_principal() {
+        let mut protocol_state = ProtocolState::default();
+        let mut token_config = TokenConfig::default();
+
+        RevenueTracker::record_liquidation(&mut protocol_state, &mut token_config, 1_070, 1_000).unwrap();
+        RevenueTracker::record_liquidation(&mut protocol_state, &mut token_config, 600, 1_000).unwrap();
+        RevenueTracker::record_repayment(&mut protocol_state, &mut token_config, 500, 10).unwrap();
+
+        assert_eq!(protocol_state.total_principal_recovered, 2_100);
+        assert_eq!(protocol_state.total_liquidation_profit, 70);
+        assert_eq!(protocol_state.total_fees_earned, 10);
+        assert_eq!(token_config.total_principal_recovered, 2_100);
+        assert_eq!(token_config.total_liquidation_profit, 70);
+        assert_eq!(token_config.total_fee_revenue, 10);
+    }
+
+    #[test]
+    fn auction_price_decays_and_stops_during_pauses() {
+        let mut protocol_state = ProtocolState { total_paused_seconds: 600, ..ProtocolState::default() };
+        let auction = LiquidationAuction {
+            start_price: 11_000_000_000,
+            floor_price: 5_000_000_000,
+            started_at: 1_000,
+            duration: AUCTION_DURATION_SECONDS,
+            paused_seconds_at_start: 600,
+            ..LiquidationAuction::default()
+        };
+
+        assert_eq!(auction.current_price(&protocol_state, 1_000), 11_000_000_000);
+        assert_eq!(auction.current_price(&protocol_state, 1_000 + 1_800), 8_000_000_000);
+        assert_eq!(auction.current_price(&protocol_state, 1_000 + 3_600), 5_000_000_000);
+        assert_eq!(auction.current_price(&protocol_state, 1_000 + 100_000), 5_000_000_000);
+
+        // A 600s pause in progress, then the same pause completed: the price does not move
+        protocol_state.paused_at = 1_000 + 1_800;
+        assert_eq!(auction.current_price(&protocol_state, 1_000 + 2_400), 8_000_000_000);
+        protocol_state.paused_at = 0;
+        protocol_state.total_paused_seconds = 1_200;
+        assert_eq!(auction.current_price(&protocol_state, 1_000 + 2_400), 8_000_000_000);
+
+        // Expiry is an hour at the floor, also measured without the pause
+        let expires = 1_000 + 600 + AUCTION_DURATION_SECONDS + AUCTION_SETTLEMENT_GRACE_SECONDS;
+        assert!(!auction.is_expired(&protocol_state, expires - 1));
+        assert!(auction.is_expired(&protocol_state, expires));
+    }
+
+    #[test]
+    fn auction_bids_stop_at_the_debt_and_settle_it() {
+        let protocol_state = ProtocolState::default();
+        let debt = LoanCalculator::calculate_liquidation_debt(1_000_000_000, 500).unwrap();
+        assert_eq!(debt, 1_070_000_000);
+        let mut auction = LiquidationAuction {
+            started_at: 1_000,
+            duration: AUCTION_DURATION_SECONDS,
+            debt,
+            collateral_remaining: 500_000_000_000,
+            ..LiquidationAuction::default()
+        };
+        assert!(!auction.is_settleable(&protocol_state, 1_000));
+
+        // Bid cap: collateral worth exactly the remaining debt at 10_000 lamports per token (6 decimals)
+        let price = 10_000_000_000;
+        let collateral_for_debt =
+            LoanCalculator::calculate_collateral_for_value(auction.remaining_debt(), price, 6).unwrap();
+        assert_eq!(collateral_for_debt, 107_000_000_000);
+        assert_eq!(LoanCalculator::calculate_collateral_value(collateral_for_debt, price, 6).unwrap(), debt);
+
+        // Bidding stops once the debt is covered; SOL above it is the borrower's surplus
+        auction.sol_raised = 1_100_000_000;
+        auction.collateral_remaining = 390_000_000_000;
+        assert_eq!(auction.remaining_debt(), 0);
+        assert!(auction.is_settleable(&protocol_state, 1_000));
+        let (debt_recovered, surplus) = auction.settlement_proceeds();
+        assert_eq!((debt_recovered, surplus), (1_070_000_000, 30_000_000));
+        let (treasury, operations, shortfall) =
+            LoanCalculator::split_liquidation_proceeds(debt_recovered, 1_000_000_000).unwrap();
+        assert_eq!((treasury + operations, shortfall), (debt_recovered, 0));
+
+        // An expired auction that raised less than the principal books the rest as a shortfall
+        auction.sol_raised = 600_000_000;
+        assert_eq!(auction.remaining_debt(), 470_000_000);
+        assert!(!auction.is_settleable(&protocol_state, 1_000));
+        assert!(auction.is_settleable(&protocol_state, 1_000 + AUCTION_DURATION_SECONDS + AUCTION_SETTLEMENT_GRACE_SECONDS));
+        assert_eq!(auction.settlement_proceeds(), (600_000_000, 0));
+        assert_eq!(
+            LoanCalculator::split_liquidation_proceeds(600_000_000, 1_000_000_000).unwrap(),
+            (600_000_000, 0, 400_000_000)
+        );
+    }
+
+    /// Meteora DLMM `LbPair` serialized field by field up to reserve_y (the reader's minimum length)
+    fn dlmm_fixture(active_id: i32, bin_step: u16, mint_x: &Pubkey, mint_y: &Pubkey) -> Vec<u8> {
+        let discriminator = anchor_lang::solana_program::hash::hash(b"account:LbPair").to_bytes();
+        let mut data = Vec::with_capacity(METEORA_DLMM_MIN_LEN);
+        data.extend_from_slice(&discriminator[..8]);
+        data.extend_from_slice(&[0x11; 32]); // parameters: StaticParameters
+        data.extend_from_slice(&[0x22; 32]); // v_parameters: VariableParameters
+        data.push(254); // bump_seed
+        data.extend_from_slice(&bin_step.to_le_bytes()); // bin_step_seed
+        data.push(1); // pair_type
+        data.extend_from_slice(&active_id.to_le_bytes());
+        data.extend_from_slice(&bin_step.to_le_bytes());
+        data.push(0); // status
+        data.push(1); // require_base_factor_seed
+        data.extend_from_slice(&[0x33; 2]); // base_factor_seed
+        data.push(1); // activation_type
+        data.push(0); // creator_pool_on_off_control
+        data.extend_from_slice(mint_x.as_ref());
+        data.extend_from_slice(mint_y.as_ref());
+        data.extend_from_slice(&[0xAA; 32]); // reserve_x
+        data.extend_from_slice(&[0xBB; 32]); // reserve_y
+        assert_eq!(data.len(), METEORA_DLMM_MIN_LEN);
+        data
+    }
+
+    /// Meteora DAMM v2 `Pool` serialized field by field up to sqrt_price
+    fn damm_v2_fixture(sqrt_price: u128, mint_a: &Pubkey, mint_b: &Pubkey) -> Vec<u8> {
+        let discriminator = anchor_lang::solana_program::hash::hash(b"account:Pool").to_bytes();
+        let mut data = Vec::with_capacity(METEORA_DAMM_V2_MIN_LEN);
+        data.extend_from_slice(&discriminator[..8]);
+        data.extend_from_slice(&[0x11; 160]); // pool_fees: PoolFeesStruct
+        data.extend_from_slice(mint_a.as_ref());
+        data.extend_from_slice(mint_b.as_ref());
+        data.extend_from_slice(&[0xAA; 32]); // token_a_vault
+        data.extend_from_slice(&[0xBB; 32]); // token_b_vault
+        data.extend_from_slice(&[0xCC; 32]); // whitelisted_vault
+        data.extend_from_slice(&[0xDD; 32]); // partner
+        data.extend_from_slice(&u128::MAX.to_le_bytes()); // liquidity
+        data.extend_from_slice(&[0; 16]); // _padding
+        data.extend_from_slice(&[0xEE; 4 * 8]); // protocol / partner fees
+        data.extend_from_slice(&1u128.to_le_bytes()); // sqrt_min_price
+        data.extend_from_slice(&u128::MAX.to_le_bytes()); // sqrt_max_price
+        data.extend_from_slice(&sqrt_price.to_le_bytes());
+        assert_eq!(data.len(), METEORA_DAMM_V2_MIN_LEN);
+        data
+    }
+
+    /// Run `check` against a pool account owned by `owner`
+    fn with_pool_account<T>(owner: &Pubkey, data: &mut [u8], check: impl FnOnce(&AccountInfo) -> T) -> T {
+        let key = Pubkey::new_unique();
+        let mut lamports = 1_000_000;
+        let account = AccountInfo::new(&key, false, false, &mut lamports, data, owner, false, 0);
+        check(&account)
+    }
+
+    #[test]
+    fn meteora_dlmm_reader_matches_layout() {
+        // 1.01^-693 lamports per raw token unit = 0.00101225..., from either token ordering
+        let sol_y = dlmm_fixture(-693, 100, &memecoin_mint(), &WSOL_MINT);
+        let sol_x = dlmm_fixture(693, 100, &WSOL_MINT, &memecoin_mint());
+        for data in [&sol_y, &sol_x] {
+            let price = PriceFeedUtils::read_meteora_dlmm_price(data, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+            assert!((1_011..=1_012).contains(&price));
+        }
+
+        // Wrong token, no SOL side, short account
+        let other_mint = Pubkey::new_from_array([9u8; 32]);
+        let wrong_token = dlmm_fixture(-693, 100, &other_mint, &WSOL_MINT);
+        assert!(PriceFeedUtils::read_meteora_dlmm_price(&wrong_token, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+        let no_sol = dlmm_fixture(-693, 100, &other_mint, &memecoin_mint());
+        assert!(PriceFeedUtils::read_meteora_dlmm_price(&no_sol, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+        assert!(PriceFeedUtils::read_meteora_dlmm_price(&sol_y[..METEORA_DLMM_MIN_LEN - 1], &memecoin_mint(), &WSOL_MINT, 0).is_err());
+
+        // Owner check at whitelist time and on every read
+        let mut data = sol_y.clone();
+        with_pool_account(&METEORA_DLMM_PROGRAM_ID, &mut data, |pool| {
+            PriceFeedUtils::validate_meteora_pool(pool, PoolType::MeteoraDlmm, &memecoin_mint()).unwrap();
+            assert!(PriceFeedUtils::read_price_from_pool(pool, PoolType::MeteoraDlmm, &memecoin_mint(), 0).is_ok());
+        });
+        let mut data = sol_y;
+        with_pool_account(&METEORA_DAMM_V2_PROGRAM_ID, &mut data, |pool| {
+            assert_eq!(
+                PriceFeedUtils::validate_meteora_pool(pool, PoolType::MeteoraDlmm, &memecoin_mint()).unwrap_err(),
+                LendingError::InvalidPoolOwner.into()
+            );
+            assert_eq!(
+                PriceFeedUtils::read_price_from_pool(pool, PoolType::MeteoraDlmm, &memecoin_mint(), 0).unwrap_err(),
+                LendingError::InvalidPoolOwner.into()
+            );
+        });
+    }
+
+    #[test]
+    fn meteora_damm_v2_reader_matches_layout() {
+        // 0.0015 lamports per raw token unit, both orderings (same sqrt prices as the whirlpool test)
+        let sol_b = damm_v2_fixture(714_439_325_892_274_567, &memecoin_mint(), &WSOL_MINT);
+        let price = PriceFeedUtils::read_meteora_damm_v2_price(&sol_b, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert!((1_499..=1_500).contains(&price));
+        let sol_a = damm_v2_fixture(476_292_883_928_183_026_778, &WSOL_MINT, &memecoin_mint());
+        let price = PriceFeedUtils::read_meteora_damm_v2_price(&sol_a, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert!((1_499..=1_501).contains(&price));
+
+        // Wrong token, no SOL side, short account
+        let other_mint = Pubkey::new_from_array([9u8; 32]);
+        let wrong_token = damm_v2_fixture(1u128 << 64, &other_mint, &WSOL_MINT);
+        assert!(PriceFeedUtils::read_meteora_damm_v2_price(&wrong_token, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+        let no_sol = damm_v2_fixture(1u128 << 64, &other_mint, &memecoin_mint());
+        assert!(PriceFeedUtils::read_meteora_damm_v2_price(&no_sol, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+        assert!(PriceFeedUtils::read_meteora_damm_v2_price(&sol_b[..METEORA_DAMM_V2_MIN_LEN - 1], &memecoin_mint(), &WSOL_MINT, 0).is_err());
+
+        // Shares its discriminator with PumpSwap's Pool, so only the owner tells them apart
+        let mut data = sol_b.clone();
+        with_pool_account(&METEORA_DAMM_V2_PROGRAM_ID, &mut data, |pool| {
+            PriceFeedUtils::validate_meteora_pool(pool, PoolType::MeteoraDammV2, &memecoin_mint()).unwrap();
+            assert!(PriceFeedUtils::read_price_from_pool(pool, PoolType::MeteoraDammV2, &memecoin_mint(), 0).is_ok());
+        });
+        let mut data = sol_b;
+        with_pool_account(&PUMPSWAP_PROGRAM_ID, &mut data, |pool| {
+            assert_eq!(
+                PriceFeedUtils::validate_meteora_pool(pool, PoolType::MeteoraDammV2, &memecoin_mint()).unwrap_err(),
+                LendingError::InvalidPoolOwner.into()
+            );
+            assert_eq!(
+                PriceFeedUtils::read_price_from_pool(pool, PoolType::MeteoraDammV2, &memecoin_mint(), 0).unwrap_err(),
+                LendingError::InvalidPoolOwner.into()
+            );
+        });
+    }
+
+    /// Raydium CPMM `PoolState` serialized field by field up to fund_fees_token_1
+    fn cpmm_fixture(mint_0: &Pubkey, mint_1: &Pubkey, vault_0: &Pubkey, vault_1: &Pubkey, fees_0: u64, fees_1: u64) -> Vec<u8> {
+        let discriminator = anchor_lang::solana_program::hash::hash(b"account:PoolState").to_bytes();
+        let mut data = Vec::with_capacity(RAYDIUM_CPMM_MIN_LEN);
+        data.extend_from_slice(&discriminator[..8]);
+        data.extend_from_slice(&[0x11; 32]); // amm_config
+        data.extend_from_slice(&[0x22; 32]); // pool_creator
+        data.extend_from_slice(vault_0.as_ref());
+        data.extend_from_slice(vault_1.as_ref());
+        data.extend_from_slice(&[0x33; 32]); // lp_mint
+        data.extend_from_slice(mint_0.as_ref());
+        data.extend_from_slice(mint_1.as_ref());
+        data.extend_from_slice(&[0x44; 3 * 32]); // token_0_program, token_1_program, observation_key
+        data.extend_from_slice(&[255, 0, 9, 6, 9]); // auth_bump, status, lp / mint_0 / mint_1 decimals
+        data.extend_from_slice(&u64::MAX.to_le_bytes()); // lp_supply
+        data.extend_from_slice(&(fees_0 / 2).to_le_bytes()); // protocol_fees_token_0
+        data.extend_from_slice(&(fees_1 / 2).to_le_bytes()); // protocol_fees_token_1
+        data.extend_from_slice(&(fees_0 - fees_0 / 2).to_le_bytes()); // fund_fees_token_0
+        data.extend_from_slice(&(fees_1 - fees_1 / 2).to_le_bytes()); // fund_fees_token_1
+        assert_eq!(data.len(), RAYDIUM_CPMM_MIN_LEN);
+        data
+    }
+
+    /// Raydium CLMM `PoolState` serialized field by field up to sqrt_price_x64
+    fn clmm_fixture(sqrt_price: u128, mint_0: &Pubkey, mint_1: &Pubkey) -> Vec<u8> {
+        let discriminator = anchor_lang::solana_program::hash::hash(b"account:PoolState").to_bytes();
+        let mut data = Vec::with_capacity(RAYDIUM_CLMM_MIN_LEN);
+        data.extend_from_slice(&discriminator[..8]);
+        data.push(255); // bump
+        data.extend_from_slice(&[0x11; 32]); // amm_config
+        data.extend_from_slice(&[0x22; 32]); // owner
+        data.extend_from_slice(mint_0.as_ref());
+        data.extend_from_slice(mint_1.as_ref());
+        data.extend_from_slice(&[0xAA; 32]); // token_vault_0
+        data.extend_from_slice(&[0xBB; 32]); // token_vault_1
+        data.extend_from_slice(&[0xCC; 32]); // observation_key
+        data.extend_from_slice(&[9, 6]); // mint_decimals_0 / mint_decimals_1
+        data.extend_from_slice(&60u16.to_le_bytes()); // tick_spacing
+        data.extend_from_slice(&u128::MAX.to_le_bytes()); // liquidity
+        data.extend_from_slice(&sqrt_price.to_le_bytes());
+        assert_eq!(data.len(), RAYDIUM_CLMM_MIN_LEN);
+        data
+    }
+
+    /// SPL token account bytes holding `amount` (the reader only looks at the amount field)
+    fn token_account_data(amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn raydium_cpmm_reader_matches_layout() {
+        let (vault_0, vault_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let read = |data: &[u8], amount_0: u64, amount_1: u64| {
+            PriceFeedUtils::read_raydium_cpmm_price(data, &memecoin_mint(), &WSOL_MINT, &vault_0, amount_0, &vault_1, amount_1, 0)
+        };
+
+        // 2_000 lamports for 1_000_000 raw units once fees are taken out of the vaults, both orderings
+        let sol_1 = cpmm_fixture(&memecoin_mint(), &WSOL_MINT, &vault_0, &vault_1, 1_500, 50);
+        assert_eq!(read(&sol_1, 1_001_500, 2_050).unwrap(), 2_000);
+        let sol_0 = cpmm_fixture(&WSOL_MINT, &memecoin_mint(), &vault_0, &vault_1, 50, 1_500);
+        assert_eq!(read(&sol_0, 2_050, 1_001_500).unwrap(), 2_000);
+
+        // Wrong token, vaults not the pool's, short account
+        let other_mint = Pubkey::new_from_array([9u8; 32]);
+        let wrong_token = cpmm_fixture(&other_mint, &WSOL_MINT, &vault_0, &vault_1, 0, 0);
+        assert!(read(&wrong_token, 1_000_000, 2_000).is_err());
+        let wrong_vaults = cpmm_fixture(&memecoin_mint(), &WSOL_MINT, &vault_1, &vault_0, 0, 0);
+        assert_eq!(read(&wrong_vaults, 1_000_000, 2_000).unwrap_err(), LendingError::InvalidVault.into());
+        assert!(read(&sol_1[..RAYDIUM_CPMM_MIN_LEN - 1], 1_001_500, 2_050).is_err());
+
+        // Owner check on the spot read, which loads the vault balances itself
+        let spl_token = anchor_spl::token::ID;
+        let (mut vault_0_data, mut vault_1_data) = (token_account_data(1_001_500), token_account_data(2_050));
+        let (mut lamports_0, mut lamports_1) = (1, 1);
+        let vault_0_info = AccountInfo::new(&vault_0, false, false, &mut lamports_0, &mut vault_0_data, &spl_token, false, 0);
+        let vault_1_info = AccountInfo::new(&vault_1, false, false, &mut lamports_1, &mut vault_1_data, &spl_token, false, 0);
+        let spot = |pool: &AccountInfo| {
+            PriceFeedUtils::read_spot_price(pool, PoolType::RaydiumCpmm, &memecoin_mint(), 0, Some(&vault_0_info), Some(&vault_1_info))
+        };
+        let mut data = sol_1.clone();
+        assert_eq!(with_pool_account(&RAYDIUM_CPMM_PROGRAM_ID, &mut data, spot).unwrap(), 2_000);
+        let mut data = sol_1;
+        assert_eq!(
+            with_pool_account(&RAYDIUM_CLMM_PROGRAM_ID, &mut data, spot).unwrap_err(),
+            LendingError::InvalidPoolOwner.into()
+        );
+    }
+
+    #[test]
+    fn raydium_clmm_reader_matches_layout() {
+        // 0.0015 lamports per raw token unit, both orderings (same sqrt prices as the whirlpool test)
+        let sol_1 = clmm_fixture(714_439_325_892_274_567, &memecoin_mint(), &WSOL_MINT);
+        let price = PriceFeedUtils::read_raydium_clmm_price(&sol_1, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert!((1_499..=1_500).contains(&price));
+        let sol_0 = clmm_fixture(476_292_883_928_183_026_778, &WSOL_MINT, &memecoin_mint());
+        let price = PriceFeedUtils::read_raydium_clmm_price(&sol_0, &memecoin_mint(), &WSOL_MINT, 0).unwrap();
+        assert!((1_499..=1_501).contains(&price));
+
+        // Wrong token, no SOL side, short account
+        let other_mint = Pubkey::new_from_array([9u8; 32]);
+        let wrong_token = clmm_fixture(1u128 << 64, &other_mint, &WSOL_MINT);
+        assert!(PriceFeedUtils::read_raydium_clmm_price(&wrong_token, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+        let no_sol = clmm_fixture(1u128 << 64, &other_mint, &memecoin_mint());
+        assert!(PriceFeedUtils::read_raydium_clmm_price(&no_sol, &memecoin_mint(), &WSOL_MINT, 0).is_err());
+        assert!(PriceFeedUtils::read_raydium_clmm_price(&sol_1[..RAYDIUM_CLMM_MIN_LEN - 1], &memecoin_mint(), &WSOL_MINT, 0).is_err());
+
+        // CPMM and CLMM share the PoolState discriminator, so only the owner tells them apart
+        let mut data = sol_1.clone();
+        with_pool_account(&RAYDIUM_CLMM_PROGRAM_ID, &mut data, |pool| {
+            PriceFeedUtils::validate_raydium_pool(pool, PoolType::RaydiumClmm, &memecoin_mint()).unwrap();
+            assert!(PriceFeedUtils::read_price_from_pool(pool, PoolType::RaydiumClmm, &memecoin_mint(), 0).is_ok());
+        });
+        let mut data = sol_1;
+        with_pool_account(&RAYDIUM_CPMM_PROGRAM_ID, &mut data, |pool| {
+            assert_eq!(
+                PriceFeedUtils::validate_raydium_pool(pool, PoolType::RaydiumClmm, &memecoin_mint()).unwrap_err(),
+                LendingError::InvalidPoolOwner.into()
+            );
+            assert_eq!(
+                PriceFeedUtils::read_price_from_pool(pool, PoolType::RaydiumClmm, &memecoin_mint(), 0).unwrap_err(),
+                LendingError::InvalidPoolOwner.into()
+            );
+        });
+    }
+
+    #[test]
+    fn pumpswap_sell_output_matches_constant_product() {
+        use crate::swap::pumpswap::calculate_sell_output;
+
+        // Selling as much base as the pool holds halves the price: 1M out of 2M quote, less 30 bps
+        assert_eq!(calculate_sell_output(1_000_000, 2_000_000, 1_000_000).unwrap(), 997_000);
+        // 1% of a 1M-token / 100 SOL pool: 100 SOL * 1/101 = 990_099_009, then 30 bps off (floored)
+        assert_eq!(
+            calculate_sell_output(1_000_000_000_000, 100_000_000_000, 10_000_000_000).unwrap(),
+            987_128_711
+        );
+        assert_eq!(calculate_sell_output(1_000_000, 2_000_000, 0).unwrap(), 0);
+        // Empty reserves never price a sell
+        assert!(calculate_sell_output(0, 2_000_000, 1_000).is_err());
+        assert!(calculate_sell_output(1_000_000, 0, 1_000).is_err());
+    }
+
+    #[test]
+    fn pumpfun_curve_sell_output_matches_known_values() {
+        use crate::swap::pumpfun::calculate_curve_sell_output;
+
+        // Fresh pump.fun curve: 1.073B virtual tokens (6 decimals) against 30 virtual SOL
+        let virtual_token = 1_073_000_000_000_000;
+        let virtual_sol = 30_000_000_000;
+        // 1M tokens: 30 SOL * 1M / 1.074B = 27_932_960 lamports, less 125 bps (floored)
+        assert_eq!(
+            calculate_curve_sell_output(virtual_token, virtual_sol, 1_000_000_000_000).unwrap(),
+            27_583_798
+        );
+        // Selling the whole virtual supply again returns half the virtual SOL before fees
+        assert_eq!(
+            calculate_curve_sell_output(virtual_token, virtual_sol, virtual_token).unwrap(),
+            14_812_500_000
+        );
+        assert_eq!(calculate_curve_sell_output(virtual_token, virtual_sol, 0).unwrap(), 0);
+        assert!(calculate_curve_sell_output(0, virtual_sol, 1_000).is_err());
+        assert!(calculate_curve_sell_output(virtual_token, 0, 1_000).is_err());
+    }
+
+    #[test]
+    fn price_attestation_is_bound_to_its_action() {
+        let mint = Pubkey::new_unique();
+        let borrower = Pubkey::new_unique();
+        let create = PriceAttestationUtils::build_message(PriceAction::CreateLoan, &mint, 1_000, 100, &borrower);
+        let extend = PriceAttestationUtils::build_message(PriceAction::ExtendLoan, &mint, 1_000, 100, &borrower);
+        assert_ne!(create, extend);
+        assert_eq!(create.len(), PRICE_SIGNATURE_PREFIX.len() + PRICE_MESSAGE_BODY_LEN);
+
+        assert_eq!(
+            PriceAttestationUtils::parse_message(&create, PriceAction::CreateLoan, &mint, &borrower).unwrap(),
+            (1_000, 100)
+        );
+        // A create_loan attestation cannot be replayed into extend_loan, or for another borrower
+        assert!(PriceAttestationUtils::parse_message(&create, PriceAction::ExtendLoan, &mint, &borrower).is_err());
+        assert!(PriceAttestationUtils::parse_message(&extend, PriceAction::ExtendLoan, &mint, &Pubkey::new_unique()).is_err());
+    }
+}
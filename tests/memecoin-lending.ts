@@ -81,6 +81,8 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
   let goldTokenConfigPda: PublicKey;
   let silverTokenConfigPda: PublicKey;
   let bronzeTokenConfigPda: PublicKey;
+  let riskConfigPda: PublicKey;
+  let goldPriceHistoryPda: PublicKey;
   let stakingPoolPda: PublicKey;
   let stakingVaultPda: PublicKey;
   let stakingVaultAuthorityPda: PublicKey;
@@ -219,6 +221,17 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
     );
   }
 
+  /**
+   * Derive the per-borrower exposure PDA
+   */
+  function deriveUserExposurePda(borrowerKey: PublicKey): PublicKey {
+    const [userExposurePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_exposure"), borrowerKey.toBuffer()],
+      program.programId
+    );
+    return userExposurePda;
+  }
+
   /**
    * Derive loan PDAs for a specific index
    */
@@ -297,6 +310,14 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
       [Buffer.from("token_config"), bronzeTokenMint.toBuffer()],
       program.programId
     );
+    [riskConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("risk_config")],
+      program.programId
+    );
+    [goldPriceHistoryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("price_history"), goldTokenMint.toBuffer()],
+      program.programId
+    );
 
     // Create and fund token accounts
     console.log("\n👛 Creating token accounts...");
@@ -392,6 +413,22 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
         expect(err.message).to.include("already in use");
       }
     });
+
+    it("should initialize risk config", async () => {
+      await program.methods
+        .initializeRiskConfig()
+        .accountsStrict({
+          protocolState: protocolStatePda,
+          riskConfig: riskConfigPda,
+          admin: admin.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([admin])
+        .rpc();
+
+      const riskConfig = await program.account.riskConfig.fetch(riskConfigPda);
+      expect(riskConfig.maxPriceDeviationBps).to.be.gt(0);
+    });
   });
 
   // ============= 2. Treasury Operations Tests =============
@@ -654,6 +691,32 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
 
   // ============= 5. Loan Lifecycle Tests =============
   describe("5️⃣  Loan Lifecycle", () => {
+    it("should reject record_price bundled with another instruction", async () => {
+      const recordPriceIx = () =>
+        program.methods
+          .recordPrice()
+          .accountsStrict({
+            tokenConfig: goldTokenConfigPda,
+            tokenMint: goldTokenMint,
+            priceHistory: goldPriceHistoryPda,
+            pumpswapBaseVault: null,
+            pumpswapQuoteVault: null,
+            instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            payer: admin.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .instruction();
+
+      // A checkpoint must not share its transaction with a swap that moves the pool
+      const tx = new Transaction().add(await recordPriceIx(), await recordPriceIx());
+      try {
+        await sendAndConfirmTransaction(connection, tx, [admin]);
+        assert.fail("Should have thrown PriceRecordNotStandalone");
+      } catch (err: any) {
+        expect(err.message + JSON.stringify(err.logs ?? [])).to.include("PriceRecordNotStandalone");
+      }
+    });
+
     it("should create loan with Gold token", async () => {
       const collateralAmount = new BN(10_000 * 10 ** TOKEN_DECIMALS);
       const durationSeconds = new BN(24 * 60 * 60); // 24 hours
@@ -681,14 +744,18 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
         .accountsStrict({
           protocolState: protocolStatePda,
           tokenConfig: goldTokenConfigPda,
+          riskConfig: riskConfigPda,
           loan: activeLoanPda,
           treasury: treasuryPda,
           borrower: borrower.publicKey,
+          userExposure: deriveUserExposurePda(borrower.publicKey),
           borrowerTokenAccount: borrowerGoldTokenAccount,
           vault: activeLoanVaultPda,
-          poolAccount: goldPool.publicKey,
+          priceHistory: goldPriceHistoryPda,
           tokenMint: goldTokenMint,
           priceAuthority: admin.publicKey, // Using admin as price authority for testing
+          instructionsSysvar: null,
+          priceAuthoritySet: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -731,14 +798,18 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
           .accountsStrict({
             protocolState: protocolStatePda,
             tokenConfig: goldTokenConfigPda,
+            riskConfig: riskConfigPda,
             loan: newLoanPda,
             treasury: treasuryPda,
             borrower: borrower2.publicKey,
+            userExposure: deriveUserExposurePda(borrower2.publicKey),
             borrowerTokenAccount: borrower2GoldTokenAccount,
             vault: newVaultPda,
-            poolAccount: goldPool.publicKey,
+            priceHistory: goldPriceHistoryPda,
             tokenMint: goldTokenMint,
             priceAuthority: admin.publicKey,
+            instructionsSysvar: null,
+            priceAuthoritySet: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
@@ -765,14 +836,18 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
           .accountsStrict({
             protocolState: protocolStatePda,
             tokenConfig: goldTokenConfigPda,
+            riskConfig: riskConfigPda,
             loan: newLoanPda,
             treasury: treasuryPda,
             borrower: borrower2.publicKey,
+            userExposure: deriveUserExposurePda(borrower2.publicKey),
             borrowerTokenAccount: borrower2GoldTokenAccount,
             vault: newVaultPda,
-            poolAccount: goldPool.publicKey,
+            priceHistory: goldPriceHistoryPda,
             tokenMint: goldTokenMint,
             priceAuthority: admin.publicKey,
+            instructionsSysvar: null,
+            priceAuthoritySet: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
@@ -858,14 +933,18 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
         .accountsStrict({
           protocolState: protocolStatePda,
           tokenConfig: goldTokenConfigPda,
+          riskConfig: riskConfigPda,
           loan: newLoanPda,
           treasury: treasuryPda,
           borrower: borrower.publicKey,
+          userExposure: deriveUserExposurePda(borrower.publicKey),
           borrowerTokenAccount: borrowerGoldTokenAccount,
           vault: newVaultPda,
-          poolAccount: goldPool.publicKey,
+          priceHistory: goldPriceHistoryPda,
           tokenMint: goldTokenMint,
           priceAuthority: admin.publicKey,
+          instructionsSysvar: null,
+          priceAuthoritySet: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
@@ -1087,14 +1166,18 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
           .accountsStrict({
             protocolState: protocolStatePda,
             tokenConfig: goldTokenConfigPda,
+            riskConfig: riskConfigPda,
             loan: newLoanPda,
             treasury: treasuryPda,
             borrower: borrower.publicKey,
+            userExposure: deriveUserExposurePda(borrower.publicKey),
             borrowerTokenAccount: borrowerGoldTokenAccount,
             vault: newVaultPda,
-            poolAccount: goldPool.publicKey,
+            priceHistory: goldPriceHistoryPda,
             tokenMint: goldTokenMint,
             priceAuthority: admin.publicKey,
+            instructionsSysvar: null,
+            priceAuthoritySet: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
@@ -1129,14 +1212,18 @@ describe("Memecoin Lending Protocol - Enhanced Test Suite", () => {
           .accountsStrict({
             protocolState: protocolStatePda,
             tokenConfig: goldTokenConfigPda,
+            riskConfig: riskConfigPda,
             loan: newLoanPda,
             treasury: treasuryPda,
             borrower: borrower.publicKey,
+            userExposure: deriveUserExposurePda(borrower.publicKey),
             borrowerTokenAccount: borrower2GoldTokenAccount, // Wrong owner
             vault: newVaultPda,
-            poolAccount: goldPool.publicKey,
+            priceHistory: goldPriceHistoryPda,
             tokenMint: goldTokenMint,
             priceAuthority: admin.publicKey,
+            instructionsSysvar: null,
+            priceAuthoritySet: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })